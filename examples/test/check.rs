@@ -0,0 +1,702 @@
+// Copyright 2018 Fredrik Portström <https://portstrom.com>
+// This is free software distributed under the terms specified in
+// the file LICENSE at the top-level directory of this distribution.
+
+use check_cases::*;
+use parse_wiki_text::*;
+use std::{
+    collections::HashMap,
+    fmt::Debug,
+    sync::{atomic::AtomicBool, Arc},
+    time::Instant,
+};
+use test_cases::TEST_CASES;
+
+/// Checks the test cases that have an expected result, and exits with an error if any result differs from the expected one.
+pub fn run_checks(configuration: &Configuration) {
+    let mut failures = vec![];
+    check_attributes(configuration, &mut failures);
+    check_configuration(&mut failures);
+    check_deep_nesting(configuration, &mut failures);
+    check_events(configuration, &mut failures);
+    check_expand(configuration, &mut failures);
+    check_html(configuration, &mut failures);
+    check_nodes(configuration, &mut failures);
+    check_parse_limits(configuration, &mut failures);
+    check_plain_text(configuration, &mut failures);
+    check_reparse(configuration, &mut failures);
+    check_sections(configuration, &mut failures);
+    check_serialize(configuration, &mut failures);
+    check_siteinfo(&mut failures);
+    check_source_map(&mut failures);
+    check_template_calls(configuration, &mut failures);
+    check_titles(&mut failures);
+    check_visit(configuration, &mut failures);
+    if !failures.is_empty() {
+        for failure in &failures {
+            eprintln!("{}", failure);
+        }
+        eprintln!("{} checks failed", failures.len());
+        ::std::process::exit(1);
+    }
+}
+
+fn check<T: Debug + PartialEq>(
+    failures: &mut Vec<String>,
+    input: &dyn Debug,
+    actual: T,
+    expected: T,
+) {
+    if actual != expected {
+        failures.push(format!(
+            "{:?}\n  expected {:?}\n  actual   {:?}",
+            input, expected, actual
+        ));
+    }
+}
+
+fn check_attributes(configuration: &Configuration, failures: &mut Vec<String>) {
+    for (wiki_text, expected) in ATTRIBUTE_CASES {
+        let output = configuration.parse(wiki_text);
+        let mut actual = vec![];
+        for node in depth_first(&output.nodes) {
+            match node {
+                Node::StartTag { name, .. } => {
+                    actual.push(format!("{} {:?} {:?}", name, node.class(), node.style()))
+                }
+                Node::Table { captions, rows, .. } => {
+                    actual.push(format!("table {:?} {:?}", node.class(), node.style()));
+                    for caption in captions {
+                        actual.push(format!(
+                            "caption {:?} {:?}",
+                            caption.class(),
+                            caption.style()
+                        ));
+                    }
+                    for row in rows {
+                        actual.push(format!("row {:?} {:?}", row.class(), row.style()));
+                        for cell in &row.cells {
+                            actual.push(format!(
+                                "cell {:?} {:?} {:?} {:?}",
+                                cell.colspan(),
+                                cell.rowspan(),
+                                cell.class(),
+                                cell.style()
+                            ));
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+        check(failures, wiki_text, &actual.join(", ") as &str, expected);
+    }
+}
+
+fn check_configuration(failures: &mut Vec<String>) {
+    let configuration = Configuration::new(&TITLE_CONFIGURATION_SOURCE);
+    for (name, expected) in NAMESPACE_CASES {
+        let actual = match configuration
+            .namespace_id(name)
+            .and_then(|id| configuration.namespace(id))
+        {
+            None => "None".to_string(),
+            Some(namespace) => format!(
+                "{} {:?} {:?} {:?} {} {}",
+                namespace.id,
+                namespace.name,
+                namespace.canonical_name,
+                namespace.aliases,
+                namespace.case_sensitive,
+                namespace.subpages
+            ),
+        };
+        check(failures, name, &actual as &str, expected);
+    }
+    for (wiki_text, expected) in CONFIGURATION_CASES {
+        let output = configuration.parse(wiki_text);
+        check(
+            failures,
+            wiki_text,
+            &output_kinds(&output) as &str,
+            expected,
+        );
+    }
+}
+
+fn check_deep_nesting(configuration: &Configuration, failures: &mut Vec<String>) {
+    for (start, end, expected) in DEEP_NESTING_CASES {
+        let wiki_text = start.repeat(DEEP_NESTING_COUNT)
+            + &end.repeat(DEEP_NESTING_COUNT)
+            + "\n\nalpha\n\nbeta";
+        // Both the output and the document are dropped without recursion.
+        let output = configuration.parse(&wiki_text);
+        check(
+            failures,
+            &(start, end),
+            nesting_depth(&output.nodes),
+            *expected,
+        );
+        // The nested nodes are reused without recursion when reparsing after a change in the last paragraph.
+        let changed_wiki_text = wiki_text.replace("beta", "gamma");
+        let output = configuration.reparse(
+            output,
+            &wiki_text,
+            wiki_text.len() - 4..wiki_text.len(),
+            &changed_wiki_text,
+        );
+        check(
+            failures,
+            &(start, end),
+            nesting_depth(&output.nodes),
+            *expected,
+        );
+        check(
+            failures,
+            &(start, end),
+            configuration.serialize(&changed_wiki_text, &output.nodes) == changed_wiki_text,
+            true,
+        );
+        // The events are made from the nested nodes without recursion.
+        let events = configuration.parse_events(&wiki_text);
+        check(
+            failures,
+            &(start, end),
+            events
+                .filter(|event| matches!(event, Event::Enter { .. } | Event::Text { .. }))
+                .count(),
+            depth_first(&output.nodes).count(),
+        );
+        drop(output);
+        let document = configuration.parse_document(wiki_text);
+        check(
+            failures,
+            &(start, end),
+            nesting_depth(&document.nodes),
+            *expected,
+        );
+    }
+}
+
+fn check_events(configuration: &Configuration, failures: &mut Vec<String>) {
+    for wiki_text in TEST_CASES
+        .iter()
+        .flat_map(|(_, test_cases)| test_cases.iter())
+    {
+        let output = configuration.parse(wiki_text);
+        let mut actual = vec![];
+        let mut actual_warnings = vec![];
+        for event in configuration.parse_events(wiki_text) {
+            match event {
+                Event::Enter { end, kind, start } => {
+                    actual.push(format!("Enter {:?} {}..{}", kind, start, end))
+                }
+                Event::Exit { end, kind, start } => {
+                    actual.push(format!("Exit {:?} {}..{}", kind, start, end))
+                }
+                Event::Text { end, start, value } => {
+                    actual.push(format!("Text {}..{} {:?}", start, end, value))
+                }
+                Event::Warning(warning) => actual_warnings.push(warning),
+                _ => {}
+            }
+        }
+        check(failures, wiki_text, actual, flattened_nodes(&output.nodes));
+        check(failures, wiki_text, &actual_warnings, &output.warnings);
+    }
+}
+
+fn check_expand(configuration: &Configuration, failures: &mut Vec<String>) {
+    let context = PageContext::default();
+    let mut provider = CountingProvider {
+        count: 0,
+        templates: TEMPLATES
+            .iter()
+            .map(|(title, wiki_text)| (title.to_string(), wiki_text.to_string()))
+            .collect(),
+    };
+    for (wiki_text, expected) in EXPAND_CASES {
+        let actual =
+            configuration.expand_templates(wiki_text, &context, &mut provider, &Default::default());
+        check(failures, wiki_text, &actual as &str, expected);
+    }
+    for (limits, wiki_text, expected) in EXPAND_LIMIT_CASES {
+        let actual = configuration.expand_templates(wiki_text, &context, &mut provider, limits);
+        check(failures, wiki_text, &actual as &str, expected);
+    }
+    // The wiki text of each template is asked for only once, whether or not the template exists.
+    let wiki_text = "{{Echo|alpha}}{{Echo|beta}}{{Nope}}{{Nope}}";
+    provider.count = 0;
+    let _ = configuration.expand_templates(wiki_text, &context, &mut provider, &Default::default());
+    check(failures, &wiki_text, provider.count, 2);
+}
+
+fn check_html(configuration: &Configuration, failures: &mut Vec<String>) {
+    for (wiki_text, expected) in HTML_CASES {
+        let output = configuration.parse(wiki_text);
+        let actual = render_html(&output.nodes, &mut DefaultHtmlHandler);
+        check(failures, wiki_text, &actual as &str, expected);
+    }
+}
+
+fn check_nodes(configuration: &Configuration, failures: &mut Vec<String>) {
+    for (wiki_text, expected) in NODE_CASES {
+        let output = configuration.parse(wiki_text);
+        let actual = format!("{:?}", output.nodes);
+        check(failures, wiki_text, &actual as &str, expected);
+    }
+}
+
+fn check_parse_limits(configuration: &Configuration, failures: &mut Vec<String>) {
+    for (limits, wiki_text, expected) in PARSE_LIMIT_CASES {
+        let output = configuration.parse_with_limits(wiki_text, limits);
+        check(
+            failures,
+            wiki_text,
+            &output_kinds(&output) as &str,
+            expected,
+        );
+    }
+    // The cancellation flag and the deadline stop the parser before the first node.
+    let wiki_text = "a ''b'' c";
+    let limits = ParseLimits {
+        cancel: Some(Arc::new(AtomicBool::new(true))),
+        ..Default::default()
+    };
+    let output = configuration.parse_with_limits(wiki_text, &limits);
+    check(
+        failures,
+        &wiki_text,
+        &output_kinds(&output) as &str,
+        "Text | Cancelled 0..9",
+    );
+    let limits = ParseLimits {
+        deadline: Some(Instant::now()),
+        ..Default::default()
+    };
+    let output = configuration.parse_with_limits(wiki_text, &limits);
+    check(
+        failures,
+        &wiki_text,
+        &output_kinds(&output) as &str,
+        "Text | DeadlineExceeded 0..9",
+    );
+}
+
+fn check_plain_text(configuration: &Configuration, failures: &mut Vec<String>) {
+    for (wiki_text, expected) in PLAIN_TEXT_CASES {
+        let output = configuration.parse(wiki_text);
+        let actual = to_plain_text(&output.nodes, &Default::default());
+        check(failures, wiki_text, &actual as &str, expected);
+    }
+}
+
+fn check_reparse(configuration: &Configuration, failures: &mut Vec<String>) {
+    for (previous_wiki_text, start, end, replacement) in REPARSE_CASES {
+        let wiki_text = format!(
+            "{}{}{}",
+            &previous_wiki_text[..*start],
+            replacement,
+            &previous_wiki_text[*end..]
+        );
+        let previous_output = configuration.parse(previous_wiki_text);
+        let actual = configuration.reparse(
+            previous_output,
+            previous_wiki_text,
+            *start..*end,
+            &wiki_text,
+        );
+        check(
+            failures,
+            &(previous_wiki_text, start, end, replacement),
+            actual,
+            configuration.parse(&wiki_text),
+        );
+    }
+}
+
+fn check_sections(configuration: &Configuration, failures: &mut Vec<String>) {
+    for (wiki_text, expected) in SECTION_CASES {
+        let output = configuration.parse(wiki_text);
+        let actual = section_outline(&sections(&output.nodes, wiki_text));
+        check(failures, wiki_text, &actual as &str, expected);
+    }
+    let output = configuration.parse(SECTION_WIKI_TEXT);
+    for (index, expected) in SECTION_RANGE_CASES {
+        let actual = section_range(&output.nodes, SECTION_WIKI_TEXT, *index);
+        check(failures, index, actual, expected.clone());
+    }
+    for (index, replacement, expected) in REPLACE_SECTION_CASES {
+        let actual = replace_section(&output.nodes, SECTION_WIKI_TEXT, *index, replacement);
+        check(
+            failures,
+            &(index, replacement),
+            actual.as_ref().map(|text| text as &str),
+            *expected,
+        );
+    }
+}
+
+fn check_serialize(configuration: &Configuration, failures: &mut Vec<String>) {
+    for wiki_text in TEST_CASES
+        .iter()
+        .flat_map(|(_, test_cases)| test_cases.iter())
+    {
+        let output = configuration.parse(wiki_text);
+        let actual = configuration.serialize(wiki_text, &output.nodes);
+        check(failures, wiki_text, &actual as &str, wiki_text);
+    }
+    for (wiki_text, text_index, value, expected) in SERIALIZE_EDIT_CASES {
+        let mut output = configuration.parse(wiki_text);
+        let mut editor = TextEditor {
+            count: 0,
+            text_index: *text_index,
+            value,
+        };
+        for node in &mut output.nodes {
+            editor.visit_node_mut(node);
+        }
+        let actual = configuration.serialize(wiki_text, &output.nodes);
+        check(failures, wiki_text, &actual as &str, expected);
+    }
+    for (wiki_text, value, expected) in SERIALIZE_ATTRIBUTE_CASES {
+        let mut output = configuration.parse(wiki_text);
+        for node in output.nodes.iter_mut() {
+            if let Node::Table {
+                captions,
+                parsed_attributes,
+                rows,
+                ..
+            } = node
+            {
+                set_class(parsed_attributes, value);
+                for caption in captions {
+                    set_class(&mut caption.parsed_attributes, value);
+                }
+                for row in rows {
+                    set_class(&mut row.parsed_attributes, value);
+                    for cell in &mut row.cells {
+                        set_class(&mut cell.parsed_attributes, value);
+                    }
+                }
+            }
+        }
+        let actual = configuration.serialize(wiki_text, &output.nodes);
+        check(failures, wiki_text, &actual as &str, expected);
+    }
+    // Nodes that are changed at the bottom of deep nesting are written without recursion.
+    let wiki_text = "{{a|".repeat(DEEP_NESTING_COUNT) + &"}}".repeat(DEEP_NESTING_COUNT);
+    let mut output = configuration.parse(&wiki_text);
+    let mut nodes = &mut output.nodes[..];
+    while let [Node::Template {
+        name, parameters, ..
+    }] = nodes
+    {
+        if parameters[0].value.is_empty() {
+            if let [Node::Text { value, .. }] = &mut name[..] {
+                *value = "b".into();
+            }
+            break;
+        }
+        nodes = &mut parameters[0].value;
+    }
+    let expected =
+        "{{a|".repeat(DEEP_NESTING_COUNT - 1) + "{{b|" + &"}}".repeat(DEEP_NESTING_COUNT);
+    let actual = configuration.serialize(&wiki_text, &output.nodes);
+    check(failures, &"deep nesting", actual == expected, true);
+}
+
+fn check_siteinfo(failures: &mut Vec<String>) {
+    let configuration = match Configuration::from_siteinfo_json(SITEINFO_JSON) {
+        Err(error) => return failures.push(format!("{:?}\n  {}", SITEINFO_JSON, error)),
+        Ok(configuration) => configuration,
+    };
+    for (wiki_text, expected) in SITEINFO_CASES {
+        let output = configuration.parse(wiki_text);
+        check(
+            failures,
+            wiki_text,
+            &format!("{:?}", output.nodes) as &str,
+            expected,
+        );
+    }
+}
+
+fn check_source_map(failures: &mut Vec<String>) {
+    for (wiki_text, position, expected) in SOURCE_MAP_CASES {
+        let source_map = SourceMap::new(wiki_text);
+        let units = [ColumnUnit::Byte, ColumnUnit::Char, ColumnUnit::Utf16];
+        let line_columns = units
+            .iter()
+            .map(|unit| source_map.line_column(*position, *unit))
+            .collect::<Vec<_>>();
+        let utf16_offset = source_map.utf16_offset(*position);
+        let actual = format!(
+            "{} {} {} {} {}",
+            line_columns[0].line,
+            line_columns[0].column,
+            line_columns[1].column,
+            line_columns[2].column,
+            utf16_offset
+        );
+        check(failures, &(wiki_text, position), &actual as &str, expected);
+        // Converting back gives the same position.
+        for (unit, line_column) in units.iter().zip(line_columns) {
+            check(
+                failures,
+                &(wiki_text, position, unit),
+                source_map.position(line_column, *unit),
+                Some(*position),
+            );
+        }
+        check(
+            failures,
+            &(wiki_text, position),
+            source_map.position_from_utf16(utf16_offset),
+            Some(*position),
+        );
+        check(
+            failures,
+            wiki_text,
+            source_map.line_span(source_map.line_count()),
+            None,
+        );
+    }
+    for (wiki_text, line, column, unit, expected) in SOURCE_MAP_POSITION_CASES {
+        let source_map = SourceMap::new(wiki_text);
+        let actual = source_map.position(
+            LineColumn {
+                column: *column,
+                line: *line,
+            },
+            *unit,
+        );
+        check(
+            failures,
+            &(wiki_text, line, column, unit),
+            actual,
+            *expected,
+        );
+    }
+    for (wiki_text, offset, expected) in SOURCE_MAP_UTF16_CASES {
+        let actual = SourceMap::new(wiki_text).position_from_utf16(*offset);
+        check(failures, &(wiki_text, offset), actual, *expected);
+    }
+}
+
+fn check_template_calls(configuration: &Configuration, failures: &mut Vec<String>) {
+    for (wiki_text, expected) in TEMPLATE_CALL_CASES {
+        let output = configuration.parse(wiki_text);
+        let actual = match output
+            .nodes
+            .first()
+            .and_then(|node| TemplateCall::new(node, wiki_text))
+        {
+            None => "None".to_string(),
+            Some(template_call) => {
+                let arguments = template_call
+                    .iter()
+                    .map(|argument| format!("{}={:?}", argument.name, argument.text))
+                    .collect::<Vec<_>>();
+                format!("{:?} | {}", template_call.name_text(), arguments.join(", "))
+            }
+        };
+        check(failures, wiki_text, &actual as &str, expected);
+    }
+}
+
+fn check_titles(failures: &mut Vec<String>) {
+    let configuration = Configuration::new(&TITLE_CONFIGURATION_SOURCE);
+    for (text, default_namespace, expected) in TITLE_CASES {
+        let actual = match configuration.parse_title(text, *default_namespace) {
+            Err(error) => format!("{:?}", error),
+            Ok(title) => format!(
+                "{} {} {:?}",
+                title.namespace,
+                title.full_text(),
+                title.fragment
+            ),
+        };
+        check(
+            failures,
+            &(text, default_namespace),
+            &actual as &str,
+            expected,
+        );
+    }
+}
+
+fn check_visit(configuration: &Configuration, failures: &mut Vec<String>) {
+    for (wiki_text, expected_depth_first, expected_breadth_first) in VISIT_CASES {
+        let output = configuration.parse(wiki_text);
+        let mut actual_depth_first = vec![];
+        let mut iterator = depth_first(&output.nodes);
+        while let Some(node) = iterator.next() {
+            actual_depth_first.push(node_path(iterator.ancestors().iter().cloned(), node));
+        }
+        check(
+            failures,
+            wiki_text,
+            actual_depth_first.join(" "),
+            expected_depth_first.join(" "),
+        );
+        let mut actual_breadth_first = vec![];
+        let mut iterator = breadth_first(&output.nodes);
+        while let Some(node) = iterator.next() {
+            let mut ancestors = iterator.ancestors().collect::<Vec<_>>();
+            ancestors.reverse();
+            actual_breadth_first.push(node_path(ancestors, node));
+        }
+        check(
+            failures,
+            wiki_text,
+            actual_breadth_first.join(" "),
+            expected_breadth_first.join(" "),
+        );
+    }
+}
+
+/// Template provider that counts the requests for wiki text.
+struct CountingProvider {
+    count: usize,
+    templates: HashMap<String, String>,
+}
+
+impl TemplateProvider for CountingProvider {
+    fn wiki_text(&mut self, title: &Title) -> Option<String> {
+        self.count += 1;
+        self.templates.wiki_text(title)
+    }
+}
+
+/// Returns the events for entering and exiting the nodes and for the text, from a depth first walk over the nodes.
+fn flattened_nodes(nodes: &[Node]) -> Vec<String> {
+    let mut events = vec![];
+    let mut open_nodes: Vec<&Node> = vec![];
+    let mut iterator = depth_first(nodes);
+    while let Some(node) = iterator.next() {
+        let depth = iterator.ancestors().len();
+        while open_nodes.len() > depth {
+            let open_node = open_nodes.pop().unwrap();
+            events.push(format!(
+                "Exit {:?} {}..{}",
+                open_node.kind(),
+                open_node.start(),
+                open_node.end()
+            ));
+        }
+        match node {
+            Node::Text { end, start, value } => {
+                events.push(format!("Text {}..{} {:?}", start, end, value))
+            }
+            _ => {
+                events.push(format!(
+                    "Enter {:?} {}..{}",
+                    node.kind(),
+                    node.start(),
+                    node.end()
+                ));
+                open_nodes.push(node);
+            }
+        }
+    }
+    while let Some(open_node) = open_nodes.pop() {
+        events.push(format!(
+            "Exit {:?} {}..{}",
+            open_node.kind(),
+            open_node.start(),
+            open_node.end()
+        ));
+    }
+    events
+}
+
+/// Returns the greatest number of ancestors of any of the nodes.
+fn nesting_depth(nodes: &[Node]) -> usize {
+    let mut depth = 0;
+    let mut iterator = depth_first(nodes);
+    while iterator.next().is_some() {
+        depth = depth.max(iterator.ancestors().len());
+    }
+    depth
+}
+
+/// Returns the types of the given ancestors and node separated by slashes.
+fn node_path<'a, 'b: 'a, I: IntoIterator<Item = &'a Node<'b>>>(
+    ancestors: I,
+    node: &Node,
+) -> String {
+    let mut path = String::new();
+    for ancestor in ancestors {
+        path += &format!("{:?}/", ancestor.kind());
+    }
+    path + &format!("{:?}", node.kind())
+}
+
+/// Returns the types of the nodes at the top level and the messages and positions of the warnings.
+fn output_kinds(output: &Output) -> String {
+    let nodes = output
+        .nodes
+        .iter()
+        .map(|node| format!("{:?}", node.kind()))
+        .collect::<Vec<_>>();
+    let warnings = output
+        .warnings
+        .iter()
+        .map(|warning| format!("{:?} {}..{}", warning.message, warning.start, warning.end))
+        .collect::<Vec<_>>();
+    format!("{} | {}", nodes.join(", "), warnings.join(", "))
+        .trim_end()
+        .to_string()
+}
+
+/// Returns the number, level, positions and number of nodes of each of the sections, with the subsections in brackets.
+fn section_outline(sections: &[Section]) -> String {
+    sections
+        .iter()
+        .map(|section| {
+            let mut outline = format!(
+                "{} {} {}..{} {}",
+                section.index,
+                section.level,
+                section.start,
+                section.end,
+                section.nodes.len()
+            );
+            if !section.sections.is_empty() {
+                outline += &format!(" [{}]", section_outline(&section.sections));
+            }
+            outline
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Sets the value of the class attributes to the given value.
+fn set_class(attributes: &mut [Attribute], value: &str) {
+    for attribute in attributes {
+        if attribute.name == "class" {
+            if let Some(attribute_value) = &mut attribute.value {
+                attribute_value.value = value.to_string().into();
+            }
+        }
+    }
+}
+
+/// Replaces the value of the text node with the given index in depth first order.
+struct TextEditor<'b> {
+    count: usize,
+    text_index: usize,
+    value: &'b str,
+}
+
+impl<'a, 'b> VisitorMut<'a> for TextEditor<'b> {
+    fn visit_text_mut(&mut self, node: &mut Node<'a>) {
+        if let Node::Text { value, .. } = node {
+            if self.count == self.text_index {
+                *value = self.value.to_string().into();
+            }
+            self.count += 1;
+        }
+    }
+}
@@ -0,0 +1,979 @@
+// Copyright 2018 Fredrik Portström <https://portstrom.com>
+// This is free software distributed under the terms specified in
+// the file LICENSE at the top-level directory of this distribution.
+
+use parse_wiki_text::{
+    ColumnUnit, ConfigurationSource, ExpansionLimits, NamespaceSource, ParseLimits,
+};
+use std::ops::Range;
+
+/// Wiki text and the attributes of the start tags and table elements in it, as given by their accessors.
+pub const ATTRIBUTE_CASES: &[(&str, &str)] = &[
+    (
+        "<div CLASS=\"alpha\" class=beta>gamma</div>",
+        "div Some(\"beta\") None",
+    ),
+    (
+        "<span class=alpha style=\"color:red\">beta</span>",
+        "span Some(\"alpha\") Some(\"color:red\")",
+    ),
+    (
+        "{|\n!style=\"color:red\"|alpha||colspan=2|beta\n|}",
+        "table None None, row None None, cell None None None Some(\"color:red\"), cell Some(2) None None None",
+    ),
+    (
+        "{|\n| colspan=x | alpha\n| colspan=\"\" rowspan=-1 | beta\n| colspan=\" 2x\" rowspan=00 | gamma\n| class=a class=b | delta\n|}",
+        "table None None, row None None, cell None None None None, cell None None None None, cell Some(2) Some(0) None None, cell None None Some(\"b\") None",
+    ),
+    (
+        "{| class=\"wikitable\" style=\"width:100%\"\n|+ class=caption | alpha\n|- style=color:red\n| colspan=\"2\" rowspan=3 class='a b' | beta\n|}",
+        "table Some(\"wikitable\") Some(\"width:100%\"), caption Some(\"caption\") None, row None Some(\"color:red\"), cell Some(2) Some(3) Some(\"a b\") None",
+    ),
+];
+
+/// Wiki text and the expected types of the nodes at the top level and the warnings from parsing it with `TITLE_CONFIGURATION_SOURCE`.
+pub const CONFIGURATION_CASES: &[(&str, &str)] = &[
+    ("[[wikt:alpha]]", "InterwikiLink |"),
+    ("[[WIKT:alpha]]", "InterwikiLink |"),
+    ("[[wp:alpha]]", "Link |"),
+    ("[[de:alpha]]", "LanguageLink |"),
+    ("[[fr:alpha]]", "LanguageLink |"),
+    ("[[en:alpha]]", "Link |"),
+    ("[[File:alpha.png]]", "Image |"),
+    ("[[image:alpha.png]]", "Image |"),
+    ("[[Category:alpha]]", "Category |"),
+    ("[[Template:alpha]]", "Link |"),
+];
+
+/// The number of times the start and end of each case in `DEEP_NESTING_CASES` are repeated.
+pub const DEEP_NESTING_COUNT: usize = 100000;
+
+/// Code that starts and ends a node, and the expected nesting depth of the nodes from parsing the code nested `DEEP_NESTING_COUNT` times.
+pub const DEEP_NESTING_CASES: &[(&str, &str, usize)] = &[
+    ("<ref>", "</ref>", 99999),
+    ("{{a|", "}}", 100000),
+    ("{{{a|", "}}}", 100000),
+];
+
+/// Wiki text and the expected text from expanding it with the templates in `TEMPLATES` and the default limits.
+pub const EXPAND_CASES: &[(&str, &str)] = &[
+    ("<!-- alpha -->beta", "beta"),
+    ("{{#expr:(1}}", "<strong class=\"error\">Expression error: Unclosed bracket.</strong>"),
+    ("{{#expr:*2}}", "<strong class=\"error\">Expression error: Unexpected * operator.</strong>"),
+    ("{{#expr:-0}}", "-0"),
+    ("{{#expr:0.1+0.2}}", "0.3"),
+    ("{{#expr:1 2}}", "<strong class=\"error\">Expression error: Unexpected number.</strong>"),
+    ("{{#expr:1 mod 0}}", "<strong class=\"error\">Division by zero.</strong>"),
+    ("{{#expr:1&2}}", "<strong class=\"error\">Expression error: Unrecognized punctuation character &quot;&amp;&quot;.</strong>"),
+    ("{{#expr:1)}}", "<strong class=\"error\">Expression error: Unexpected closing bracket.</strong>"),
+    ("{{#expr:1+}}", "<strong class=\"error\">Expression error: Missing operand for +.</strong>"),
+    ("{{#expr:1.5.5+1}}", "2.5"),
+    ("{{#expr:1/0}}", "<strong class=\"error\">Division by zero.</strong>"),
+    ("{{#expr:1/3}}", "0.33333333333333"),
+    ("{{#expr:1e14}}", "1.0E+14"),
+    ("{{#expr:2 round 1.5}}", "2"),
+    ("{{#expr:2^64}}", "1.844674407371E+19"),
+    ("{{#expr:foo}}", "<strong class=\"error\">Expression error: Unrecognized word &quot;foo&quot;.</strong>"),
+    ("{{#expr:ln 0}}", "<strong class=\"error\">Invalid argument for ln: &lt;= 0.</strong>"),
+    ("{{#expr:pi}}", "3.1415926535898"),
+    ("{{#expr:sqrt -1}}", "<strong class=\"error\">In sqrt: result is not a number.</strong>"),
+    ("{{#expr:trunc -1.5}}", "-1"),
+    ("{{#expr:trunc 1e20}}", "7766279631452241920"),
+    ("{{#expr:}}", ""),
+    ("{{#if: x |yes|no}}", "yes"),
+    ("{{#if: |yes|no}}", "no"),
+    ("{{#if:x|yes}}", "yes"),
+    ("{{#if:|yes}}", ""),
+    ("{{#ifeq: a | a |yes|no}}", "yes"),
+    ("{{#ifeq:01|1|yes|no}}", "yes"),
+    ("{{#ifeq:a|A|yes|no}}", "no"),
+    ("{{#ifeq:{{padleft:|600|a}}|{{padleft:|250|a}}{{padleft:|250|a}}|capped|not capped}}", "capped"),
+    ("{{#iferror:<span class=\"error\">x</span>|e}}", "e"),
+    ("{{#iferror:fine|error|ok}}", "ok"),
+    ("{{#iferror:fine|error}}", "fine"),
+    ("{{#iferror:{{#expr:1+}}|error|ok}}", "error"),
+    ("{{#iferror:{{#expr:1+}}}}", ""),
+    ("{{#ifexpr:0|yes|no}}", "no"),
+    ("{{#ifexpr:1+|yes|no}}", "<strong class=\"error\">Expression error: Missing operand for +.</strong>"),
+    ("{{#ifexpr:1>0|yes|no}}", "yes"),
+    ("{{#switch:1.0|1=one|2=two}}", "one"),
+    ("{{#switch:a|a|b=2|c=3}}", "2"),
+    ("{{#switch:b|a=1|b=2|#default=3}}", "2"),
+    ("{{#switch:b|a|b|c=3|d=4}}", "3"),
+    ("{{#switch:c|a=1|b=2|#default=3}}", "3"),
+    ("{{#switch:c|a=1|b=2|3}}", "3"),
+    ("{{#switch:x|#default=d|a=1}}", "d"),
+    ("{{#switch:x|#default|a=1|d}}", "d"),
+    ("{{#switch:x|a=1|#default|b=2}}", "2"),
+    ("{{#switch:z|a=1}}", ""),
+    ("{{#switch:|=empty|a=1}}", "empty"),
+    ("{{#time:Y-m-d|@0}}", "1970-01-01"),
+    ("{{#time:Y|10000-01-01}}", "<strong class=\"error\">Error: Invalid time.</strong>"),
+    ("{{#time:Y|@-62167219201}}", "<strong class=\"error\">Error: #time only supports years from 0.</strong>"),
+    ("{{#time:Y|@253402300799}}", "9999"),
+    ("{{#time:Y|@253402300800}}", "<strong class=\"error\">Error: #time only supports years up to 9999.</strong>"),
+    ("{{#titleparts:Talk:Foo/bar/baz/quok|-1|2}}", "bar/baz"),
+    ("{{#titleparts:Talk:Foo/bar/baz/quok|-1}}", "Talk:Foo/bar/baz"),
+    ("{{#titleparts:Talk:Foo/bar/baz/quok|-5}}", ""),
+    ("{{#titleparts:Talk:Foo/bar/baz/quok|1|-2}}", "baz"),
+    ("{{#titleparts:Talk:Foo/bar/baz/quok|2|2}}", "bar/baz"),
+    ("{{#titleparts:Talk:Foo/bar/baz/quok|2}}", "Talk:Foo/bar"),
+    ("{{#titleparts:Talk:Foo/bar/baz/quok||-1}}", "quok"),
+    ("{{BASEPAGENAME:Help:Foo/bar/baz}}", "Foo/bar"),
+    ("{{Chain1}}", "123"),
+    ("{{Default|gamma|name=delta}}", "gamma-delta"),
+    ("{{Default}}", "alpha-beta"),
+    ("{{Echo| alpha }}", " alpha "),
+    ("{{Echo|alpha}}", "alpha"),
+    ("{{Echo|{{Echo|{{{1|alpha}}}}}}}", "alpha"),
+    ("{{Echo}}", "{{{1}}}"),
+    ("{{FULLPAGENAME:Talk:Foo bar/baz}}", "Talk:Foo bar/baz"),
+    ("{{FULLPAGENAMEE:Talk:Foo bar&baz}}", "Talk:Foo_bar%26baz"),
+    ("{{Loop}}", "x<span class=\"error\">Template loop detected: [[Template:Loop]]</span>"),
+    ("{{NAMESPACE:Talk:Foo bar/baz}}", "Talk"),
+    ("{{NAMESPACE}}", ""),
+    ("{{Nope}}", "[[:Template:Nope]]"),
+    ("{{Only}}", "bd"),
+    ("{{PAGENAME:Talk:Foo bar/baz}}", "Foo bar/baz"),
+    ("{{PAGENAMEE}}", "Main_Page"),
+    ("{{PAGENAME}}", "Main Page"),
+    ("{{ROOTPAGENAME:Help:Foo/bar/baz}}", "Foo"),
+    ("{{SUBJECTPAGENAME:Talk:Foo}}", "Foo"),
+    ("{{SUBPAGENAME:Foo/bar}}", "Foo/bar"),
+    ("{{SUBPAGENAME:Help:Foo/bar/baz}}", "baz"),
+    ("{{TALKPAGENAME:Foo}}", "Talk:Foo"),
+    ("{{formatnum:-1234}}", "−1,234"),
+    ("{{formatnum:1,234.5|R}}", "1234.5"),
+    ("{{formatnum:1234567.891}}", "1,234,567.891"),
+    ("{{formatnum:1234|NOSEP}}", "1234"),
+    ("{{int:Greeting|A|B}}", "Hello A and B, not $3 or $A"),
+    ("{{int:Missing}}", "⧼Missing⧽"),
+    ("{{int:greeting|A}}", "Hello A and $2, not $3 or $A"),
+    ("{{padleft:7|3|ab}}", "ab7"),
+    ("{{padleft:7|3}}", "007"),
+    ("{{padright:abc|5|xy}}", "abcxy"),
+    ("{{urlencode:a b&c/d~é|PATH}}", "a%20b%26c%2Fd~%C3%A9"),
+    ("{{urlencode:a b&c/d~é|WIKI}}", "a_b%26c/d~%C3%A9"),
+    ("{{urlencode:a b&c/d~é}}", "a+b%26c%2Fd%7E%C3%A9"),
+    ("{{{1|alpha}}}", "alpha"),
+];
+
+/// Limits, wiki text and the expected text from expanding it with the templates in `TEMPLATES` and the limits.
+pub const EXPAND_LIMIT_CASES: &[(ExpansionLimits, &str, &str)] = &[
+    (
+        ExpansionLimits {
+            max_depth: 2,
+            max_expensive_function_count: 100,
+            max_include_size: 1000,
+            max_node_count: 1000,
+        },
+        "{{Chain1}}",
+        "12<span class=\"error\">Template recursion depth limit exceeded (2)</span>",
+    ),
+    (
+        ExpansionLimits {
+            max_depth: 3,
+            max_expensive_function_count: 100,
+            max_include_size: 1000,
+            max_node_count: 1000,
+        },
+        "{{int:Loop}}",
+        "xxx<span class=\"error\">Template recursion depth limit exceeded (3)</span>",
+    ),
+    (
+        ExpansionLimits {
+            max_depth: 100,
+            max_expensive_function_count: 1,
+            max_include_size: 1000,
+            max_node_count: 1000,
+        },
+        "{{#ifexist:Template:Echo|yes|no}}{{#ifexist:Template:Nope|yes|no}}",
+        "yes<span class=\"error\">Expensive parser function count limit exceeded (1)</span>",
+    ),
+    (
+        ExpansionLimits {
+            max_depth: 100,
+            max_expensive_function_count: 100,
+            max_include_size: 15,
+            max_node_count: 1000,
+        },
+        "{{Big}}{{Big}}",
+        "0123456789[[:Template:Big]]<!-- WARNING: template omitted, post-expand include size too large -->",
+    ),
+    (
+        ExpansionLimits {
+            max_depth: 100,
+            max_expensive_function_count: 100,
+            max_include_size: 15,
+            max_node_count: 1000,
+        },
+        "{{Same|0123456789}}{{Same|0123456789}}",
+        "samedifferent",
+    ),
+    (
+        ExpansionLimits {
+            max_depth: 100,
+            max_expensive_function_count: 100,
+            max_include_size: 1000,
+            max_node_count: 3,
+        },
+        "{{Double}}",
+        "xx",
+    ),
+    (
+        ExpansionLimits {
+            max_depth: 100,
+            max_expensive_function_count: 100,
+            max_include_size: 1000,
+            max_node_count: 5,
+        },
+        "{{Double}}{{Double}}",
+        "xx<span class=\"error\">Node-count limit exceeded</span>",
+    ),
+];
+
+/// Wiki text and the expected HTML from rendering it with the default handler.
+pub const HTML_CASES: &[(&str, &str)] = &[
+    (
+        "(http://x.org)",
+        "<p>(<a rel=\"nofollow\" class=\"external free\" href=\"http://x.org\">http://x.org</a>)</p>\n",
+    ),
+    (
+        "1http://x.org a_http://y.org é http://z.org",
+        "<p>1http://x.org a_http://y.org é <a rel=\"nofollow\" class=\"external free\" href=\"http://z.org\">http://z.org</a></p>\n",
+    ),
+    (
+        "<span ONCLICK=\"alert(1)\">x</span>",
+        "<p><span>x</span></p>\n",
+    ),
+    (
+        "<span data-x=\"1\" data-x=\"2\">x</span>",
+        "<p><span data-x=\"2\">x</span></p>\n",
+    ),
+    (
+        "<span onclick=\"alert(1)\" class=\"alpha\">x</span>",
+        "<p><span class=\"alpha\">x</span></p>\n",
+    ),
+    (
+        "<span style=\"a\\62 c\">x</span>",
+        "<p><span>x</span></p>\n",
+    ),
+    (
+        "<span style=\"background:&#x75;rl(http://alpha)\">x</span>",
+        "<p><span>x</span></p>\n",
+    ),
+    (
+        "<span style=\"background:java&#115;cript:alert(1)\">x</span>",
+        "<p><span>x</span></p>\n",
+    ),
+    (
+        "<span style=\"background:url(http://alpha)\">x</span>",
+        "<p><span>x</span></p>\n",
+    ),
+    (
+        "<span style=\"color:red\">x</span>",
+        "<p><span style=\"color:red\">x</span></p>\n",
+    ),
+    (
+        "<span style=\"width:EXPRESSION(alert(1))\">x</span>",
+        "<p><span>x</span></p>\n",
+    ),
+    (
+        "<span title=\"a&#60;b&#x3e;\">x</span>",
+        "<p><span title=\"a&lt;b&gt;\">x</span></p>\n",
+    ),
+    (
+        "<span title=\"a&amp;b\">x</span>",
+        "<p><span title=\"a&amp;b\">x</span></p>\n",
+    ),
+    (
+        "<span title=\"a&bogus;b & c\">x</span>",
+        "<p><span title=\"a&amp;bogus;b &amp; c\">x</span></p>\n",
+    ),
+    (
+        "[[a http://x.org\nhttp://y.org",
+        "<p>[[a <a rel=\"nofollow\" class=\"external free\" href=\"http://x.org\">http://x.org</a>\n<a rel=\"nofollow\" class=\"external free\" href=\"http://y.org\">http://y.org</a></p>\n",
+    ),
+    (
+        "[[a|http://x.org]] http://y.org",
+        "<p><a href=\"/wiki/a\" title=\"a\">http://x.org</a> <a rel=\"nofollow\" class=\"external free\" href=\"http://y.org\">http://y.org</a></p>\n",
+    ),
+    (
+        "[http://x.org a\nhttp://y.org",
+        "<p>[<a rel=\"nofollow\" class=\"external free\" href=\"http://x.org\">http://x.org</a> a\n<a rel=\"nofollow\" class=\"external free\" href=\"http://y.org\">http://y.org</a></p>\n",
+    ),
+    (
+        "[http://x.org a http://y.org] http://z.org",
+        "<p><a rel=\"nofollow\" class=\"external text\" href=\"http://x.org\">a http://y.org</a> <a rel=\"nofollow\" class=\"external free\" href=\"http://z.org\">http://z.org</a></p>\n",
+    ),
+    (
+        "ahttp://x.org HTTP://y.org",
+        "<p>ahttp://x.org <a rel=\"nofollow\" class=\"external free\" href=\"HTTP://y.org\">HTTP://y.org</a></p>\n",
+    ),
+    (
+        "http://x.org.",
+        "<p><a rel=\"nofollow\" class=\"external free\" href=\"http://x.org\">http://x.org</a>.</p>\n",
+    ),
+    (
+        "http://x.org/&lt;",
+        "<p><a rel=\"nofollow\" class=\"external free\" href=\"http://x.org/\">http://x.org/</a>&lt;</p>\n",
+    ),
+    (
+        "http://x.org/a,b;c:d.",
+        "<p><a rel=\"nofollow\" class=\"external free\" href=\"http://x.org/a,b;c:d\">http://x.org/a,b;c:d</a>.</p>\n",
+    ),
+    (
+        "http://x.org/a_(b)",
+        "<p><a rel=\"nofollow\" class=\"external free\" href=\"http://x.org/a_(b)\">http://x.org/a_(b)</a></p>\n",
+    ),
+    (
+        "http://x.org?!",
+        "<p><a rel=\"nofollow\" class=\"external free\" href=\"http://x.org\">http://x.org</a>?!</p>\n",
+    ),
+    (
+        "xhttp://x.org",
+        "<p>xhttp://x.org</p>\n",
+    ),
+    (
+        "<div align=\"center\" href=\"alpha\" id=\"beta\" data-gamma=\"1\" data-mw=\"2\" data-x:y=\"3\" formaction=\"delta\">x</div>",
+        "<div align=\"center\" id=\"beta\" data-gamma=\"1\">x</div>",
+    ),
+    (
+        "<span align=\"center\" aria-label=\"alpha\" lang=\"en\">x</span>",
+        "<p><span aria-label=\"alpha\" lang=\"en\">x</span></p>\n",
+    ),
+    (
+        "<ol start=\"3\" reversed type=\"a\" src=\"alpha\"><li value=\"4\">x</li></ol>",
+        "<ol start=\"3\" reversed=\"\" type=\"a\"><li value=\"4\">x</li></ol>",
+    ),
+    (
+        "<font color=\"red\" size=\"2\" face=\"serif\">x</font>",
+        "<p><font color=\"red\" size=\"2\" face=\"serif\">x</font></p>\n",
+    ),
+    (
+        "{| border=\"1\" cellpadding=\"2\" onmouseover=\"alpha\" background=\"beta\"\n|- valign=\"top\" colspan=\"2\"\n| colspan=\"2\" nowrap scope=\"row\" | x\n|}",
+        "<table border=\"1\" cellpadding=\"2\"><tbody><tr valign=\"top\"><td colspan=\"2\" nowrap=\"\" scope=\"row\">x</td></tr></tbody></table>\n",
+    ),
+];
+
+/// Wiki text and the expected nodes from parsing it, formatted with `Debug`.
+pub const NODE_CASES: &[(&str, &str)] = &[(
+    "[[alpha|beta]]gamma delta",
+    "[Link { end: 19, start: 0, target: \"alpha\", text: [Text { end: 12, start: 8, value: \"beta\" }, Text { end: 19, start: 14, value: \"gamma\" }] }, Text { end: 25, start: 19, value: \" delta\" }]",
+)];
+
+/// Limits for parsing, wiki text and the expected types of the nodes and warnings.
+pub const PARSE_LIMIT_CASES: &[(ParseLimits, &str, &str)] = &[
+    (
+        ParseLimits {
+            cancel: None,
+            deadline: None,
+            max_depth: Some(2),
+            max_input_size: None,
+            max_nodes: None,
+            max_warnings: None,
+        },
+        "{{a|{{b|c}}}} d",
+        "Template, Text |",
+    ),
+    (
+        ParseLimits {
+            cancel: None,
+            deadline: None,
+            max_depth: Some(2),
+            max_input_size: None,
+            max_nodes: None,
+            max_warnings: None,
+        },
+        "{{a|{{b|{{c|d}}}}}} e",
+        "Text | DepthLimitExceeded 10..21",
+    ),
+    (
+        ParseLimits {
+            cancel: None,
+            deadline: None,
+            max_depth: Some(1),
+            max_input_size: None,
+            max_nodes: None,
+            max_warnings: Some(1),
+        },
+        "a\u{1}b\u{2}c\u{3}d {{a|{{b|c}}}} e",
+        "Text | InvalidCharacter 1..2, WarningLimitExceeded 3..4, DepthLimitExceeded 14..23",
+    ),
+    (
+        ParseLimits {
+            cancel: None,
+            deadline: None,
+            max_depth: None,
+            max_input_size: Some(3),
+            max_nodes: None,
+            max_warnings: None,
+        },
+        "abc",
+        "Text |",
+    ),
+    (
+        ParseLimits {
+            cancel: None,
+            deadline: None,
+            max_depth: None,
+            max_input_size: Some(3),
+            max_nodes: None,
+            max_warnings: None,
+        },
+        "abcd",
+        "Text | InputSizeLimitExceeded 0..4",
+    ),
+    (
+        ParseLimits {
+            cancel: None,
+            deadline: None,
+            max_depth: None,
+            max_input_size: None,
+            max_nodes: Some(2),
+            max_warnings: Some(1),
+        },
+        "a\u{1}b\u{2}c ''d'' ''e''",
+        "Text, Italic, Text, Italic, Text | InvalidCharacter 1..2, WarningLimitExceeded 3..4, NodeLimitExceeded 11..17",
+    ),
+    (
+        ParseLimits {
+            cancel: None,
+            deadline: None,
+            max_depth: None,
+            max_input_size: None,
+            max_nodes: Some(3),
+            max_warnings: None,
+        },
+        "a ''b'' c ''d'' e",
+        "Text, Italic, Text, Italic, Text | NodeLimitExceeded 7..17",
+    ),
+    (
+        ParseLimits {
+            cancel: None,
+            deadline: None,
+            max_depth: None,
+            max_input_size: None,
+            max_nodes: None,
+            max_warnings: Some(1),
+        },
+        "a\u{1}b\u{2}c\u{3}d",
+        "Text | InvalidCharacter 1..2, WarningLimitExceeded 3..4",
+    ),
+    (
+        ParseLimits {
+            cancel: None,
+            deadline: None,
+            max_depth: None,
+            max_input_size: None,
+            max_nodes: None,
+            max_warnings: Some(3),
+        },
+        "a\u{1}b\u{2}c\u{3}d",
+        "Text | InvalidCharacter 1..2, InvalidCharacter 3..4, InvalidCharacter 5..6",
+    ),
+];
+
+/// Wiki text and the expected plain text from converting it with the default profile.
+pub const PLAIN_TEXT_CASES: &[(&str, &str)] = &[
+    ("[[alpha|beta]]gamma delta", "betagamma delta"),
+    ("[[alpha]]beta", "alphabeta"),
+    ("alpha [[beta]]", "alpha beta"),
+];
+
+/// Wiki text, the start and end of a range in it and the text to replace the range with, for checking that reparsing the text after the replacement gives the same output as parsing it.
+pub const REPARSE_CASES: &[(&str, usize, usize, &str)] = &[
+    ("#REDIRECT [[alpha]]\n\nbeta", 21, 25, "gamma"),
+    ("<ref>alpha\n\nbeta</ref>\n\ngamma", 16, 22, ""),
+    ("== alpha ==\n\nbeta\n\ngamma", 3, 8, "x"),
+    ("alpha", 0, 5, "beta"),
+    ("alpha\n\nbeta\n\ngamma", 0, 0, "x"),
+    ("alpha\n\nbeta\n\ngamma", 5, 7, ""),
+    ("alpha\n\nbeta\n\ngamma", 7, 7, "* x\n"),
+    ("alpha\n\nbeta\n\ngamma", 7, 7, "{{a|"),
+    ("alpha\n\nbeta\n\ngamma", 7, 11, "delta"),
+    ("alpha\n\nbeta\n\ngamma", 11, 11, "<!--"),
+    ("alpha\n\nbeta\n\ngamma", 18, 18, "\n\ndelta"),
+    ("{{a|\n\nbeta\n\ngamma", 17, 17, "}}"),
+    ("{{a|\n\nbeta\n\ngamma}}\n\ndelta", 6, 10, "x"),
+    ("{|\n|alpha\n\nbeta\n|}\n\ngamma", 10, 14, "x"),
+];
+
+/// Name of a namespace and the expected number, name, canonical name, aliases, case sensitivity and subpages of the namespace it's found as in `TITLE_CONFIGURATION_SOURCE`.
+pub const NAMESPACE_CASES: &[(&str, &str)] = &[
+    ("", "None"),
+    ("talk", "1 \"Talk\" \"Talk\" [] false true"),
+    (
+        "Wikipedia",
+        "4 \"Wikipedia\" \"Project\" [\"WP\"] false true",
+    ),
+    ("project", "4 \"Wikipedia\" \"Project\" [\"WP\"] false true"),
+    ("wp", "4 \"Wikipedia\" \"Project\" [\"WP\"] false true"),
+    ("Image", "6 \"File\" \"File\" [\"Image\"] false false"),
+    ("MediaWiki", "8 \"MediaWiki\" \"MediaWiki\" [] true true"),
+    ("t", "10 \"Template\" \"Template\" [\"T\"] false true"),
+    ("Template_talk", "None"),
+    ("User", "None"),
+];
+
+/// The number of a section in `SECTION_WIKI_TEXT`, a replacement for it, and the expected wiki text from replacing the section.
+pub const REPLACE_SECTION_CASES: &[(usize, &str, Option<&str>)] = &[
+    (
+        0,
+        "new lead\n\n",
+        Some("new lead\n\n== a ==\nalpha\n=== b ===\nbeta\n== c ==\ngamma"),
+    ),
+    (
+        0,
+        "",
+        Some("== a ==\nalpha\n=== b ===\nbeta\n== c ==\ngamma"),
+    ),
+    (
+        1,
+        "== A ==\nbeta",
+        Some("lead\n== A ==\nbeta\n\n== c ==\ngamma"),
+    ),
+    (
+        2,
+        "= B =\ngamma",
+        Some("lead\n== a ==\nalpha\n= B =\ngamma\n\n== c ==\ngamma"),
+    ),
+    (
+        2,
+        "==== B ====\ngamma",
+        Some("lead\n== a ==\nalpha\n==== B ====\ngamma\n\n== c ==\ngamma"),
+    ),
+    (
+        3,
+        "== C ==\ndelta\n\n",
+        Some("lead\n== a ==\nalpha\n=== b ===\nbeta\n== C ==\ndelta"),
+    ),
+    (3, "", Some("lead\n== a ==\nalpha\n=== b ===\nbeta\n")),
+    (4, "== D ==", None),
+];
+
+/// Wiki text and the expected number, level, positions and number of nodes of each of the sections, with the subsections in brackets.
+pub const SECTION_CASES: &[(&str, &str)] = &[
+    ("", "0 0 0..0 0"),
+    ("alpha", "0 0 0..5 1"),
+    ("alpha\n\nbeta\n== a ==\ngamma", "0 0 0..12 3, 1 2 12..25 1"),
+    (
+        "== a ==\nalpha\n==== b ====\nbeta\n=== c ===\ngamma\n== d ==",
+        "0 0 0..0 0, 1 2 0..47 1 [2 4 14..31 1, 3 3 31..47 1], 4 2 47..54 0",
+    ),
+    (
+        "= a =\n=== b ===\n== c ==\n= d =",
+        "0 0 0..0 0, 1 1 0..24 0 [2 3 6..16 0, 3 2 16..24 0], 4 1 24..29 0",
+    ),
+    (
+        "alpha\n<ref>\n== a ==\n</ref>\n== b ==",
+        "0 0 0..27 2, 1 2 27..34 0",
+    ),
+    (
+        "alpha\n{{a|\n== a ==\n}}\n== b ==",
+        "0 0 0..22 2, 1 2 22..29 0",
+    ),
+    (
+        "alpha\n{|\n|\n== a ==\nbeta\n|}\ngamma",
+        "0 0 0..11 2, 1 2 11..32 1",
+    ),
+];
+
+/// The number of a section in `SECTION_WIKI_TEXT` and the expected range of the section.
+pub const SECTION_RANGE_CASES: &[(usize, Option<Range<usize>>)] = &[
+    (0, Some(0..5)),
+    (1, Some(5..34)),
+    (2, Some(19..34)),
+    (3, Some(34..47)),
+    (4, None),
+];
+
+/// Wiki text with a lead section, a section with a subsection, and a last section.
+pub const SECTION_WIKI_TEXT: &str = "lead\n== a ==\nalpha\n=== b ===\nbeta\n== c ==\ngamma";
+
+/// Wiki text, the value to set the class attributes of tables and their parts to, and the expected wiki text from serializing the nodes with the changed attributes.
+pub const SERIALIZE_ATTRIBUTE_CASES: &[(&str, &str, &str)] = &[
+    (
+        "{| class=\"a\" style=\"b\"\n| c\n|}",
+        "d",
+        "{| class=\"d\" style=\"b\"\n| c\n|}",
+    ),
+    (
+        "{|\n|+ class=a | b\n|}",
+        "c d",
+        "{|\n|+ class=\"c d\" | b\n|}",
+    ),
+    (
+        "{|\n|- class='a'\n| b\n|}",
+        "c\"d",
+        "{|\n|- class='c\"d'\n| b\n|}",
+    ),
+    (
+        "{|\n| class=\"a\" | b\n|}",
+        "c",
+        "{|\n| class=\"c\" | b\n|}",
+    ),
+    (
+        "{|\n! class=\"a\" | b || class=\"c\" | d\n|}",
+        "e",
+        "{|\n! class=\"e\" | b\n! class=\"e\" | d\n|}",
+    ),
+    (
+        "alpha\n{| class=\"a\"\n| b\n|}\nbeta",
+        "c",
+        "alpha\n{| class=\"c\"\n| b\n|}\nbeta",
+    ),
+];
+
+/// Wiki text, the index of a text node in depth first order, a new value for the text node, and the expected wiki text from serializing the nodes after replacing the value.
+pub const SERIALIZE_EDIT_CASES: &[(&str, usize, &str, &str)] = &[
+    (
+        " alpha\n {|\n beta\n |}\n gamma",
+        0,
+        "delta",
+        " delta\n{|\n beta\n |}\n gamma",
+    ),
+    (" alpha\nbeta", 1, "gamma", " alpha\ngamma"),
+    (
+        "''alpha'' beta ''alpha''",
+        2,
+        "gamma",
+        "''alpha'' beta ''gamma''",
+    ),
+    ("* alpha\n* alpha", 1, "beta", "*alpha\n*beta"),
+    ("[[alpha]] [[alpha]]", 2, "beta", "[[alpha]] [[alpha|beta]]"),
+    ("[[alpha]]beta", 1, "gamma", "[[alpha|alphagamma]]"),
+    (
+        "[[wikt:alpha]]beta",
+        1,
+        "gamma",
+        "[[:wikt:alpha|wikt:alphagamma]]",
+    ),
+    ("alpha\n beta", 0, "gamma", "gamma\n beta"),
+    ("alpha\n\nalpha", 1, "beta", "alpha\n\nbeta"),
+    ("alpha\n{|\nbeta\n|}", 1, "gamma", "alpha\ngamma\n{|\n|}"),
+    (
+        "{{alpha|beta}}{{alpha|beta}}",
+        3,
+        "gamma",
+        "{{alpha|beta}}{{alpha|gamma}}",
+    ),
+    ("{{{alpha}}} beta", 0, "gamma", "{{{gamma}}} beta"),
+    ("{{{alpha|beta}}}", 1, "gamma", "{{{alpha|gamma}}}"),
+];
+
+/// Site info in JSON format with a link trail using hexadecimal escapes, interwikis that are and aren't language links, and interwikis with the same prefix as a namespace.
+pub const SITEINFO_JSON: &str = concat!(
+    r#"{"query":{"general":{"linktrail":"/^((?:[a-z]|\\x{e0}|\\xe9|[\\x{f0}-\\x{f2}])+)(.*)$/sDu"},"#,
+    r#""namespaces":{"4":{"id":4,"name":"Wikipedia","canonical":"Project"}},"#,
+    r#""namespacealiases":[{"id":4,"alias":"WP"}],"#,
+    r#""interwikimap":["#,
+    r#"{"prefix":"de","language":"Deutsch","url":"https://de.example.org/$1"},"#,
+    r#"{"prefix":"es","local":false,"language":"español","url":"https://es.example.org/$1"},"#,
+    r#"{"prefix":"fr","local":"","language":"français","url":"https://fr.example.org/$1"},"#,
+    r#"{"prefix":"simple","local":true,"extralanglink":true,"url":"https://simple.example.org/$1"},"#,
+    r#"{"prefix":"wikipedia","local":true,"url":"https://en.example.org/$1"},"#,
+    r#"{"prefix":"wikt","local":true,"url":"https://wikt.example.org/$1"},"#,
+    r#"{"prefix":"wp","local":true,"language":"WP","url":"https://wp.example.org/$1"}]}}"#
+);
+
+/// Wiki text and the expected debug representation of the nodes from parsing it with the configuration from `SITEINFO_JSON`.
+pub const SITEINFO_CASES: &[(&str, &str)] = &[
+    (
+        "[[a]]bàéñòóx",
+        r#"[Link { end: 14, start: 0, target: "a", text: [Text { end: 3, start: 2, value: "a" }, Text { end: 14, start: 5, value: "bàéñò" }] }, Text { end: 17, start: 14, value: "óx" }]"#,
+    ),
+    (
+        "[[WP:a]]",
+        r#"[Link { end: 8, start: 0, target: "WP:a", text: [Text { end: 6, start: 2, value: "WP:a" }] }]"#,
+    ),
+    (
+        "[[Wikipedia:a]]",
+        r#"[Link { end: 15, start: 0, target: "Wikipedia:a", text: [Text { end: 13, start: 2, value: "Wikipedia:a" }] }]"#,
+    ),
+    (
+        "[[de:a]]",
+        r#"[InterwikiLink { end: 8, prefix: "de", start: 0, target: "a", text: [Text { end: 6, start: 2, value: "de:a" }] }]"#,
+    ),
+    (
+        "[[es:a]]",
+        r#"[InterwikiLink { end: 8, prefix: "es", start: 0, target: "a", text: [Text { end: 6, start: 2, value: "es:a" }] }]"#,
+    ),
+    (
+        "[[fr:a]]",
+        r#"[LanguageLink { end: 8, prefix: "fr", start: 0, target: "a", text: [] }]"#,
+    ),
+    (
+        "[[simple:a]]",
+        r#"[LanguageLink { end: 12, prefix: "simple", start: 0, target: "a", text: [] }]"#,
+    ),
+    (
+        "[[wikt:a]]",
+        r#"[InterwikiLink { end: 10, prefix: "wikt", start: 0, target: "a", text: [Text { end: 8, start: 2, value: "wikt:a" }] }]"#,
+    ),
+];
+
+/// Wiki text, a byte position, and the expected line, the column in bytes, characters and UTF-16 code units, and the offset in UTF-16 code units of the position.
+pub const SOURCE_MAP_CASES: &[(&str, usize, &str)] = &[
+    ("", 0, "0 0 0 0 0"),
+    ("\n\n", 1, "1 0 0 0 1"),
+    ("\n\n", 2, "2 0 0 0 2"),
+    ("alpha\r\nbeta", 5, "0 5 5 5 5"),
+    ("alpha\r\nbeta", 6, "0 6 6 6 6"),
+    ("alpha\r\nbeta", 7, "1 0 0 0 7"),
+    ("alpha\r\nbeta", 11, "1 4 4 4 11"),
+    ("a\u{1F600}b\n\u{1F600}c", 1, "0 1 1 1 1"),
+    ("a\u{1F600}b\n\u{1F600}c", 5, "0 5 2 3 3"),
+    ("a\u{1F600}b\n\u{1F600}c", 7, "1 0 0 0 5"),
+    ("a\u{1F600}b\n\u{1F600}c", 11, "1 4 1 2 7"),
+    ("a\u{1F600}b\n\u{1F600}c", 12, "1 5 2 3 8"),
+    ("\u{E9}\r\n\u{E9}", 6, "1 2 1 1 4"),
+];
+
+/// Wiki text, a line, a column, the unit of the column, and the expected byte position of the line and column.
+pub const SOURCE_MAP_POSITION_CASES: &[(&str, usize, usize, ColumnUnit, Option<usize>)] = &[
+    ("a\r\nb", 0, 2, ColumnUnit::Byte, Some(2)),
+    ("a\r\nb", 0, 3, ColumnUnit::Byte, None),
+    ("a\r\nb", 1, 1, ColumnUnit::Char, Some(4)),
+    ("a\r\nb", 1, 2, ColumnUnit::Char, None),
+    ("a\r\nb", 2, 0, ColumnUnit::Byte, None),
+    ("a\u{1F600}b", 0, 2, ColumnUnit::Byte, None),
+    ("a\u{1F600}b", 0, 2, ColumnUnit::Char, Some(5)),
+    ("a\u{1F600}b", 0, 2, ColumnUnit::Utf16, None),
+    ("a\u{1F600}b", 0, 3, ColumnUnit::Utf16, Some(5)),
+    ("a\u{1F600}b", 0, 4, ColumnUnit::Utf16, Some(6)),
+    ("a\u{1F600}b", 0, 5, ColumnUnit::Utf16, None),
+];
+
+/// Wiki text, an offset in UTF-16 code units, and the expected byte position of the offset.
+pub const SOURCE_MAP_UTF16_CASES: &[(&str, usize, Option<usize>)] = &[
+    ("", 0, Some(0)),
+    ("", 1, None),
+    ("a\u{1F600}b", 2, None),
+    ("a\u{1F600}b", 3, Some(5)),
+    ("a\u{1F600}b", 4, Some(6)),
+    ("a\u{1F600}b", 5, None),
+    ("a\r\n\u{1F600}", 3, Some(3)),
+    ("a\r\n\u{1F600}", 5, Some(7)),
+    ("a\r\n\u{1F600}", 6, None),
+];
+
+/// Wiki text and the expected name and arguments of the template call from the first node of parsing it.
+pub const TEMPLATE_CALL_CASES: &[(&str, &str)] = &[
+    ("alpha", "None"),
+    ("{{ a <!-- b --> | c }}", "\"a\" | 1=\" c \""),
+    ("{{a| x | y }}", "\"a\" | 1=\" x \", 2=\" y \""),
+    ("{{a|x|1=y}}", "\"a\" | 1=\"y\""),
+    ("{{a|1=y|x}}", "\"a\" | 1=\"x\""),
+    ("{{a|2=x|y}}", "\"a\" | 2=\"x\", 1=\"y\""),
+    ("{{a|3=x|y|z|w}}", "\"a\" | 3=\"w\", 1=\"y\", 2=\"z\""),
+    ("{{a|x|y=1|z|y=2}}", "\"a\" | 1=\"x\", y=\"2\", 2=\"z\""),
+    ("{{a| y = v \n}}", "\"a\" | y=\"v\""),
+    ("{{a|y=}}", "\"a\" | y=\"\""),
+    ("{{a|y=<!--c--> v}}", "\"a\" | y=\"v\""),
+    ("{{a|y=v <!--c-->\n}}", "\"a\" | y=\"v\""),
+    ("{{a|y=v<!--c-->w}}", "\"a\" | y=\"vw\""),
+    ("{{a|<!--c--> x}}", "\"a\" | 1=\" x\""),
+    ("{{a| <!--c--> = x}}", "\"a\" | =\"x\""),
+];
+
+/// Full titles and wiki text of the templates used when expanding templates.
+pub const TEMPLATES: &[(&str, &str)] = &[
+    ("MediaWiki:Greeting", "Hello $1 and $2, not $3 or $$1"),
+    ("MediaWiki:Loop", "x{{int:loop}}"),
+    ("Template:Big", "0123456789"),
+    ("Template:Chain1", "1{{Chain2}}"),
+    ("Template:Chain2", "2{{Chain3}}"),
+    ("Template:Chain3", "3"),
+    ("Template:Default", "{{{1|alpha}}}-{{{name|beta}}}"),
+    ("Template:Double", "{{Single}}{{Single}}"),
+    ("Template:Echo", "{{{1}}}"),
+    ("Template:Loop", "x{{Loop}}"),
+    (
+        "Template:Only",
+        "a<onlyinclude>b</onlyinclude>c<onlyinclude>d</onlyinclude>e",
+    ),
+    (
+        "Template:Same",
+        "{{#ifeq:{{{1}}}|0123456789|same|different}}",
+    ),
+    ("Template:Single", "x"),
+];
+
+/// Configuration with namespaces that have aliases and differ in case sensitivity, and interwiki and language link prefixes, one of which is also an alias of a namespace.
+pub const TITLE_CONFIGURATION_SOURCE: ConfigurationSource = ConfigurationSource {
+    category_namespaces: &[],
+    extension_tags: &[],
+    file_namespaces: &[],
+    interwiki_prefixes: &["wikt", "wp"],
+    language_link_prefixes: &["de", "fr"],
+    link_trail: "",
+    magic_words: &[],
+    namespaces: &[
+        NamespaceSource {
+            aliases: &[],
+            canonical_name: "",
+            case_sensitive: false,
+            id: 0,
+            name: "",
+            subpages: false,
+        },
+        NamespaceSource {
+            aliases: &[],
+            canonical_name: "Talk",
+            case_sensitive: false,
+            id: 1,
+            name: "Talk",
+            subpages: true,
+        },
+        NamespaceSource {
+            aliases: &["WP"],
+            canonical_name: "Project",
+            case_sensitive: false,
+            id: 4,
+            name: "Wikipedia",
+            subpages: true,
+        },
+        NamespaceSource {
+            aliases: &["Image"],
+            canonical_name: "File",
+            case_sensitive: false,
+            id: 6,
+            name: "File",
+            subpages: false,
+        },
+        NamespaceSource {
+            aliases: &[],
+            canonical_name: "MediaWiki",
+            case_sensitive: true,
+            id: 8,
+            name: "MediaWiki",
+            subpages: true,
+        },
+        NamespaceSource {
+            aliases: &["T"],
+            canonical_name: "Template",
+            case_sensitive: false,
+            id: 10,
+            name: "Template",
+            subpages: true,
+        },
+        NamespaceSource {
+            aliases: &[],
+            canonical_name: "Category",
+            case_sensitive: false,
+            id: 14,
+            name: "Category",
+            subpages: false,
+        },
+    ],
+    protocols: &["http://"],
+    redirect_magic_words: &[],
+};
+
+/// Text, the default namespace and the expected number of the namespace, the full text and the fragment of the title from parsing it with `TITLE_CONFIGURATION_SOURCE`, or the expected error.
+pub const TITLE_CASES: &[(&str, i32, &str)] = &[
+    ("  foo  _bar ", 0, "0 Foo bar None"),
+    ("#bar", 0, "0  Some(\"bar\")"),
+    (":foo", 10, "0 Foo None"),
+    ("Foo", 0, "0 Foo None"),
+    ("Foo#bar_baz", 0, "0 Foo Some(\"bar baz\")"),
+    ("Foo::bar", 0, "0 Foo::bar None"),
+    ("Image:a.png", 0, "6 File:A.png None"),
+    ("MediaWiki:foo", 0, "8 MediaWiki:foo None"),
+    ("Talk::foo", 0, "LeadingColon"),
+    ("Talk:wikt:Foo", 0, "TalkNamespaceWithInterwiki"),
+    ("Template:", 0, "Empty"),
+    ("Template:foo_bar", 0, "10 Template:Foo bar None"),
+    ("WIKT:Template:foo", 10, "0 wikt:Template:foo None"),
+    ("WP:foo", 0, "4 Wikipedia:Foo None"),
+    ("a&amp;b", 0, "0 A&b None"),
+    ("a/../b", 0, "RelativePath"),
+    ("a<b", 0, "IllegalCharacter"),
+    ("a~~~b", 0, "Tildes"),
+    ("de:Foo", 0, "0 de:Foo None"),
+    ("foo", 10, "10 Template:Foo None"),
+    ("project:foo", 0, "4 Wikipedia:Foo None"),
+    ("t:foo", 0, "10 Template:Foo None"),
+    ("wikt::foo", 10, "0 wikt:foo None"),
+    ("wikt:foo", 10, "0 wikt:foo None"),
+    ("wp:foo", 0, "4 Wikipedia:Foo None"),
+    ("ßa", 0, "0 ßa None"),
+    ("édith", 0, "0 Édith None"),
+];
+
+/// Wiki text and the nodes in depth first and breadth first order, each given as the types of its ancestors and itself separated by slashes.
+pub const VISIT_CASES: &[(&str, &[&str], &[&str])] = &[
+    (
+        "* alpha [[beta]]\n* gamma",
+        &[
+            "UnorderedList",
+            "UnorderedList/Text",
+            "UnorderedList/Link",
+            "UnorderedList/Link/Text",
+            "UnorderedList/Text",
+        ],
+        &[
+            "UnorderedList",
+            "UnorderedList/Text",
+            "UnorderedList/Link",
+            "UnorderedList/Text",
+            "UnorderedList/Link/Text",
+        ],
+    ),
+    (
+        "==alpha [[beta]]==\n{{gamma}}",
+        &[
+            "Heading",
+            "Heading/Text",
+            "Heading/Link",
+            "Heading/Link/Text",
+            "Template",
+            "Template/Text",
+        ],
+        &[
+            "Heading",
+            "Template",
+            "Heading/Text",
+            "Heading/Link",
+            "Template/Text",
+            "Heading/Link/Text",
+        ],
+    ),
+    (
+        "[[alpha|beta ''gamma'']] delta",
+        &[
+            "Link",
+            "Link/Text",
+            "Link/Italic",
+            "Link/Text",
+            "Link/Italic",
+            "Text",
+        ],
+        &[
+            "Link",
+            "Text",
+            "Link/Text",
+            "Link/Italic",
+            "Link/Text",
+            "Link/Italic",
+        ],
+    ),
+    (
+        "{{alpha|beta={{gamma}}}}",
+        &[
+            "Template",
+            "Template/Text",
+            "Template/Text",
+            "Template/Template",
+            "Template/Template/Text",
+        ],
+        &[
+            "Template",
+            "Template/Text",
+            "Template/Text",
+            "Template/Template",
+            "Template/Template/Text",
+        ],
+    ),
+];
@@ -4,13 +4,19 @@
 
 extern crate parse_wiki_text;
 
+mod check;
+mod check_cases;
 mod test;
 mod test_cases;
 
 fn main() {
     let mut args = std::env::args();
     match args.nth(1) {
-        None => return test::run_test(&Default::default()),
+        None => {
+            let configuration = Default::default();
+            check::run_checks(&configuration);
+            return test::run_test(&configuration);
+        }
         Some(command) => match &command as _ {
             "file" => if let Some(path) = args.next() {
                 if args.next().is_none() {
@@ -124,6 +124,27 @@ pub const TEST_CASES: &[(&str, &[&str])] = &[
             "alpha[//beta]gamma",
         ],
     ),
+    (
+        "free external link",
+        &[
+            "(http://alpha)",
+            "(http://alpha_(beta))",
+            "== http://alpha ==",
+            "HTTP://alpha",
+            "[[alpha|http://beta]]",
+            "[http://alpha",
+            "alpha http://beta, gamma",
+            "alpha http://beta.",
+            "alphahttp://beta",
+            "http://",
+            "http://alpha&amp;",
+            "http://alpha&lt;beta",
+            "http://alpha''beta''",
+            "http://alpha<b>beta</b>",
+            "mailto:alpha",
+            "{{alpha|http://beta|gamma=http://delta}}",
+        ],
+    ),
     (
         "heading",
         &[
@@ -194,6 +215,7 @@ pub const TEST_CASES: &[(&str, &[&str])] = &[
     (
         "link",
         &[
+            "[[:de:alpha]]beta",
             "[[FILE:alpha]]",
             "[[File:alpha]]",
             "[[alpha",
@@ -212,9 +234,12 @@ pub const TEST_CASES: &[(&str, &[&str])] = &[
             "[[alpha|beta[[gamma]]]]",
             "[[alpha|beta]]",
             "[[alpha|beta]]gamma",
+            "[[alpha|beta]]gamma delta",
             "[[category:alpha]]",
             "[[category:alpha]]beta",
             "[[category:alpha|beta]]",
+            "[[de:alpha]]",
+            "[[de:alpha|beta]]",
             "[[file:alpha]]",
             "[[file:alpha]]beta",
             "[[file:alpha|[[beta]]]]",
@@ -224,6 +249,8 @@ pub const TEST_CASES: &[(&str, &[&str])] = &[
             "[[file:alpha|beta]]",
             "[[file:alpha|beta]]gamma",
             "[[image:alpha]]",
+            "[[wikt:alpha]]beta",
+            "[[wikt:alpha|beta]]",
             "[[|]]",
             "[[|alpha]]",
             "alpha [[beta]]",
@@ -610,6 +637,7 @@ pub const TEST_CASES: &[(&str, &[&str])] = &[
             "{|\n|}\nalpha",
             "{|\n|} \nalpha",
             "{|\n|}alpha",
+            "{| class=alpha\n|- style=\"beta\"\n! colspan=2 rowspan=\"3\" | gamma\n| {{delta}} class=epsilon | zeta\n|}",
             "{|alpha\nbeta\n|}",
             "{|alpha\n|}",
         ],
@@ -639,6 +667,9 @@ pub const TEST_CASES: &[(&str, &[&str])] = &[
             "<alpha",
             "<alpha>",
             "<b",
+            "<b Alpha = beta/>",
+            "<b alpha=\"beta\" gamma='delta' epsilon>",
+            "<b alpha=\"beta>",
             "<b alpha>",
             "<b alpha>beta",
             "<b</b>",
@@ -649,11 +680,14 @@ pub const TEST_CASES: &[(&str, &[&str])] = &[
             "<br\t>",
             "<br\n>",
             "<br >",
+            "<br/>",
             "<br>",
             "<r<ref>alpha</ref>beta",
             "<ref",
             "<ref />",
             "<ref >",
+            "<ref name=\"alpha\"/>",
+            "<ref name=alpha group=\"beta\">gamma</ref>",
             "<ref/>",
             "<ref>",
             "<ref>\talpha</ref>",
@@ -664,7 +698,12 @@ pub const TEST_CASES: &[(&str, &[&str])] = &[
             "<ref>alpha\n</ref>",
             "<ref>alpha </ref>",
             "<ref>alpha</ref>",
+            "alpha</includeonly>beta",
             "alpha<b>",
+            "alpha<includeonly>beta",
+            "alpha<includeonly>beta</includeonly>gamma",
+            "alpha<noinclude>beta</noinclude>gamma",
+            "alpha<onlyinclude>beta</onlyinclude>gamma",
         ],
     ),
     (
@@ -0,0 +1,232 @@
+// Copyright 2018 Fredrik Portström <https://portstrom.com>
+// This is free software distributed under the terms specified in
+// the file LICENSE at the top-level directory of this distribution.
+
+macro_rules! impl_attribute_accessors {
+    ($type:tt) => {
+        impl<'a> ::$type<'a> {
+            /// Returns the attribute with the given name, if any. When the attribute is repeated, the last one takes effect, as in Mediawiki.
+            pub fn attribute(&self, name: &str) -> Option<&::Attribute<'a>> {
+                find_attribute(&self.parsed_attributes, name)
+            }
+
+            /// Returns the value of the `class` attribute, if any.
+            pub fn class(&self) -> Option<&str> {
+                find_attribute_value(&self.parsed_attributes, "class")
+            }
+
+            /// Returns the value of the `style` attribute, if any.
+            pub fn style(&self) -> Option<&str> {
+                find_attribute_value(&self.parsed_attributes, "style")
+            }
+        }
+    };
+}
+
+impl_attribute_accessors!(TableCaption);
+impl_attribute_accessors!(TableCell);
+impl_attribute_accessors!(TableRow);
+
+impl<'a> ::TableCell<'a> {
+    /// Returns the number of columns spanned by the cell according to the `colspan` attribute, if any.
+    pub fn colspan(&self) -> Option<u32> {
+        find_attribute_value(&self.parsed_attributes, "colspan").and_then(parse_integer)
+    }
+
+    /// Returns the number of rows spanned by the cell according to the `rowspan` attribute, if any.
+    pub fn rowspan(&self) -> Option<u32> {
+        find_attribute_value(&self.parsed_attributes, "rowspan").and_then(parse_integer)
+    }
+}
+
+impl<'a> ::Node<'a> {
+    /// Returns the attribute with the given name of a start tag, tag or table, if any. When the attribute is repeated, the last one takes effect, as in Mediawiki.
+    pub fn attribute(&self, name: &str) -> Option<&::Attribute<'a>> {
+        find_attribute(self.parsed_attributes(), name)
+    }
+
+    /// Returns the value of the `class` attribute of a start tag, tag or table, if any.
+    pub fn class(&self) -> Option<&str> {
+        find_attribute_value(self.parsed_attributes(), "class")
+    }
+
+    /// Returns the value of the `style` attribute of a start tag, tag or table, if any.
+    pub fn style(&self) -> Option<&str> {
+        find_attribute_value(self.parsed_attributes(), "style")
+    }
+
+    fn parsed_attributes(&self) -> &[::Attribute<'a>] {
+        match self {
+            ::Node::StartTag { attributes, .. } | ::Node::Tag { attributes, .. } => attributes,
+            ::Node::Table {
+                parsed_attributes, ..
+            } => parsed_attributes,
+            _ => &[],
+        }
+    }
+}
+
+pub fn find_attribute<'a, 'b>(
+    attributes: &'b [::Attribute<'a>],
+    name: &str,
+) -> Option<&'b ::Attribute<'a>> {
+    attributes
+        .iter()
+        .rev()
+        .find(|attribute| attribute.name.eq_ignore_ascii_case(name))
+}
+
+pub fn find_attribute_value<'a>(attributes: &'a [::Attribute], name: &str) -> Option<&'a str> {
+    find_attribute(attributes, name).map(|attribute| match &attribute.value {
+        None => "",
+        Some(value) => &value.value,
+    })
+}
+
+pub fn parse_attributes<'a>(
+    wiki_text: &'a str,
+    start_position: usize,
+    end_position: usize,
+) -> Vec<::Attribute<'a>> {
+    let mut attributes = vec![];
+    let mut position = start_position;
+    let mut boundary_position = start_position;
+    while position < end_position {
+        if position >= boundary_position {
+            if let Some(attribute) = parse_attribute(wiki_text, position, end_position) {
+                position = attribute.end;
+                boundary_position = position + 1;
+                attributes.push(attribute);
+                continue;
+            }
+        }
+        position = match wiki_text[position..end_position]
+            .bytes()
+            .position(is_space)
+        {
+            None => end_position,
+            Some(space_position) => position + space_position + 1,
+        };
+    }
+    attributes
+}
+
+fn parse_attribute<'a>(
+    wiki_text: &'a str,
+    start_position: usize,
+    end_position: usize,
+) -> Option<::Attribute<'a>> {
+    let mut name_end_position = start_position;
+    for character in wiki_text[start_position..end_position].chars() {
+        if !(character.is_alphanumeric()
+            || character == ':'
+            || character == '_'
+            || name_end_position > start_position && (character == '-' || character == '.'))
+        {
+            break;
+        }
+        name_end_position += character.len_utf8();
+    }
+    if name_end_position == start_position {
+        return None;
+    }
+    let name = &wiki_text[start_position..name_end_position];
+    let name = if name.as_bytes().iter().any(u8::is_ascii_uppercase) {
+        name.to_ascii_lowercase().into()
+    } else {
+        ::Cow::Borrowed(name)
+    };
+    let bytes = &wiki_text.as_bytes()[..end_position];
+    let skip_spaces = |mut position: usize| {
+        while let Some(&character) = bytes.get(position) {
+            if !is_space(character) {
+                break;
+            }
+            position += 1;
+        }
+        position
+    };
+    let position = skip_spaces(name_end_position);
+    if bytes.get(position) != Some(&b'=') {
+        return Some(::Attribute {
+            end: name_end_position,
+            name,
+            start: start_position,
+            value: None,
+        });
+    }
+    let value_start_position = skip_spaces(position + 1);
+    let (quote, value_start_position, value_end_position, end) =
+        match bytes.get(value_start_position) {
+            Some(&b'"') => parse_quoted_value(bytes, b'"', value_start_position, end_position),
+            Some(&b'\'') => parse_quoted_value(bytes, b'\'', value_start_position, end_position),
+            _ => {
+                let mut value_end_position = value_start_position;
+                while match bytes.get(value_end_position) {
+                    None | Some(b'>') => false,
+                    Some(&character) => !is_space(character),
+                } {
+                    value_end_position += 1;
+                }
+                (
+                    ::AttributeQuote::Unquoted,
+                    value_start_position,
+                    value_end_position,
+                    value_end_position,
+                )
+            }
+        };
+    Some(::Attribute {
+        end,
+        name,
+        start: start_position,
+        value: Some(::AttributeValue {
+            end: value_end_position,
+            quote,
+            start: value_start_position,
+            value: ::Cow::Borrowed(&wiki_text[value_start_position..value_end_position]),
+        }),
+    })
+}
+
+fn parse_quoted_value(
+    bytes: &[u8],
+    quote_character: u8,
+    quote_position: usize,
+    end_position: usize,
+) -> (::AttributeQuote, usize, usize, usize) {
+    let quote = if quote_character == b'"' {
+        ::AttributeQuote::Double
+    } else {
+        ::AttributeQuote::Single
+    };
+    let value_start_position = quote_position + 1;
+    match bytes[value_start_position..end_position]
+        .iter()
+        .position(|character| *character == quote_character)
+    {
+        None => (quote, value_start_position, end_position, end_position),
+        Some(position) => {
+            let value_end_position = value_start_position + position;
+            (
+                quote,
+                value_start_position,
+                value_end_position,
+                value_end_position + 1,
+            )
+        }
+    }
+}
+
+fn parse_integer(value: &str) -> Option<u32> {
+    let value = value.trim_start();
+    let digits_length = value
+        .bytes()
+        .position(|character| !character.is_ascii_digit())
+        .unwrap_or(value.len());
+    value[..digits_length].parse().ok()
+}
+
+fn is_space(character: u8) -> bool {
+    b"\t\n\x0c\r ".contains(&character)
+}
@@ -13,19 +13,19 @@ pub fn parse_bold_italic(state: &mut ::State) {
     let length = state.scan_position - start_position;
     if length < 3 {
         state.flushed_position = state.scan_position;
-        state.nodes.push(::Node::Italic {
+        state.push_node(::Node::Italic {
             end: state.flushed_position,
             start: start_position,
         });
     } else if length < 5 {
         state.flushed_position = start_position + 3;
-        state.nodes.push(::Node::Bold {
+        state.push_node(::Node::Bold {
             end: state.flushed_position,
             start: start_position,
         });
     } else {
         state.flushed_position = start_position + 5;
-        state.nodes.push(::Node::BoldItalic {
+        state.push_node(::Node::BoldItalic {
             end: state.flushed_position,
             start: start_position,
         });
@@ -11,7 +11,7 @@ pub fn parse_character_entity(state: &mut ::State, configuration: &::Configurati
         state.flush(start_position);
         state.flushed_position = match_length + start_position + 1;
         state.scan_position = state.flushed_position;
-        state.nodes.push(::Node::CharacterEntity {
+        state.push_node(::Node::CharacterEntity {
             character,
             end: state.scan_position,
             start: start_position,
@@ -20,3 +20,31 @@ pub fn parse_character_entity(state: &mut ::State, configuration: &::Configurati
         state.scan_position += 1;
     }
 }
+
+/// Decodes a numeric character reference in text following an ampersand. Returns the length of the reference excluding the ampersand and the character it refers to.
+pub fn decode_numeric_entity(text: &str) -> Option<(usize, char)> {
+    if !text.starts_with('#') {
+        return None;
+    }
+    let (radix, start) = match text.as_bytes().get(1) {
+        Some(b'x') | Some(b'X') => (16, 2),
+        _ => (10, 1),
+    };
+    let length = text[start..].find(';')?;
+    let digits = &text[start..start + length];
+    if digits.is_empty() || !digits.chars().all(|character| character.is_digit(radix)) {
+        return None;
+    }
+    // Like in MediaWiki, code points that are not allowed in HTML become the replacement character.
+    let character = u32::from_str_radix(digits, radix)
+        .ok()
+        .filter(|code_point| {
+            matches!(
+                code_point,
+                0x9 | 0xA | 0xD | 0x20..=0xD7FF | 0xE000..=0xFFFD | 0x10000..=0x10FFFF
+            )
+        })
+        .and_then(::std::char::from_u32)
+        .unwrap_or('\u{FFFD}');
+    Some((start + length + 1, character))
+}
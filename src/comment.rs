@@ -28,7 +28,7 @@ pub fn parse_comment(state: &mut ::State) {
     }
     state.flushed_position = position;
     state.scan_position = position;
-    state.nodes.push(::Node::Comment {
+    state.push_node(::Node::Comment {
         end: state.scan_position,
         start: start_position,
     });
@@ -79,8 +79,8 @@ fn parse_end_tag(
                     message: ::WarningMessage::MissingEndTagRewinding,
                     start: tag_start_position,
                 });
-                state.stack.truncate(open_node_index + 2);
-                let open_node = state.stack.pop().unwrap();
+                state.truncate_open_nodes(open_node_index + 2);
+                let open_node = state.pop_open_node().unwrap();
                 state.rewind(open_node.nodes, open_node.start);
             } else {
                 state.warnings.push(::Warning {
@@ -88,15 +88,19 @@ fn parse_end_tag(
                     message: ::WarningMessage::EndTagInComment,
                     start: tag_start_position,
                 });
-                state.nodes.push(::Node::Comment {
+                state.push_node(::Node::Comment {
                     end: tag_start_position,
                     start: comment_start_position,
                 });
-                let open_node = state.stack.pop().unwrap();
+                let open_node = state.pop_open_node().unwrap();
                 state.flushed_position = tag_end_position;
                 state.scan_position = state.flushed_position;
                 let nodes = ::std::mem::replace(&mut state.nodes, open_node.nodes);
-                state.nodes.push(::Node::Tag {
+                state.push_node(::Node::Tag {
+                    attributes: match open_node.type_ {
+                        ::OpenNodeType::Tag { attributes, .. } => attributes,
+                        _ => unreachable!(),
+                    },
                     end: state.scan_position,
                     name: tag_name,
                     nodes,
@@ -5,6 +5,9 @@
 /// Site specific configuration of a wiki.
 ///
 /// This is generated using the program [`fetch_site_configuration`](https://github.com/portstrom/fetch_site_configuration).
+///
+/// Every field defaults to empty, so fields that aren't known, such as those added in later versions of this crate, can be left out with `..Default::default()`.
+#[derive(Default)]
 pub struct ConfigurationSource<'a> {
     /// Aliases of the category namespace.
     pub category_namespaces: &'a [&'a str],
@@ -15,12 +18,21 @@ pub struct ConfigurationSource<'a> {
     /// Aliases of the file namespace.
     pub file_namespaces: &'a [&'a str],
 
+    /// Prefixes of interwiki links, linking to pages on other wikis. Prefixes that are also names or aliases of namespaces are ignored, like in MediaWiki.
+    pub interwiki_prefixes: &'a [&'a str],
+
+    /// Prefixes of interwiki links that are links to the same subject in other languages. These are interwiki prefixes even if they are not in `interwiki_prefixes`. Prefixes that are also names or aliases of namespaces are ignored.
+    pub language_link_prefixes: &'a [&'a str],
+
     /// Characters that can appear in link trails.
     pub link_trail: &'a str,
 
     /// Magic words that can appear between `__` and `__`.
     pub magic_words: &'a [&'a str],
 
+    /// The namespaces of the wiki. When empty, the namespaces that exist in every wiki are used, with their canonical names and MediaWiki's default settings.
+    pub namespaces: &'a [NamespaceSource<'a>],
+
     /// Protocols that can be used for external links.
     pub protocols: &'a [&'a str],
 
@@ -28,10 +40,35 @@ pub struct ConfigurationSource<'a> {
     pub redirect_magic_words: &'a [&'a str],
 }
 
+/// Namespace in the site specific configuration of a wiki.
+///
+/// The namespaces numbered 6 and 14 are the file and category namespaces, so their names and aliases are treated as if they were listed in `file_namespaces` and `category_namespaces`.
+pub struct NamespaceSource<'a> {
+    /// Other names of the namespace.
+    pub aliases: &'a [&'a str],
+
+    /// The name of the namespace that is the same for all wikis, such as `Project`. Empty for the main namespace and for namespaces that have no canonical name.
+    pub canonical_name: &'a str,
+
+    /// Whether the first letter of page names in the namespace is case sensitive, which is the case when the site info gives `case-sensitive` rather than `first-letter`.
+    pub case_sensitive: bool,
+
+    /// The number of the namespace.
+    pub id: i32,
+
+    /// The name of the namespace as used on the wiki.
+    pub name: &'a str,
+
+    /// Whether `/` in page names in the namespace separates subpages.
+    pub subpages: bool,
+}
+
 #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
 pub enum Namespace {
     Category,
     File,
+    Interwiki,
+    Language,
 }
 
 impl ::Configuration {
@@ -40,9 +77,13 @@ impl ::Configuration {
     pub fn new(source: &ConfigurationSource) -> Self {
         let mut configuration = ::Configuration {
             character_entities: ::Trie::new(),
+            interwiki_prefixes: ::HashSet::new(),
             link_trail_character_set: ::HashSet::new(),
             magic_words: ::Trie::new(),
+            namespace_data: vec![],
+            namespace_ids: ::HashMap::new(),
             namespaces: ::Trie::new(),
+            protocol_word_ends: Some(vec![]),
             protocols: ::Trie::new(),
             redirect_magic_words: ::Trie::new(),
             tag_name_map: ::HashMap::new(),
@@ -57,19 +98,85 @@ impl ::Configuration {
         }
         for protocol in source.protocols {
             configuration.protocols.add_term(protocol, ());
+            // Free external links are looked for only in words followed by a character that follows the letters and digits at the start of a protocol, or in every word when a protocol has only letters and digits.
+            let word_length = protocol
+                .bytes()
+                .take_while(u8::is_ascii_alphanumeric)
+                .count();
+            match (
+                protocol.as_bytes().get(word_length),
+                &mut configuration.protocol_word_ends,
+            ) {
+                (None, protocol_word_ends) => *protocol_word_ends = None,
+                (Some(byte), Some(protocol_word_ends)) => if !protocol_word_ends.contains(byte) {
+                    protocol_word_ends.push(*byte);
+                },
+                (Some(_), None) => {}
+            }
         }
         for magic_word in source.magic_words {
             configuration.magic_words.add_term(magic_word, ());
         }
+        if source.namespaces.is_empty() {
+            for (id, name, subpages) in ::title::CANONICAL_NAMESPACES {
+                configuration.add_namespace(::NamespaceData {
+                    aliases: vec![],
+                    canonical_name: name.to_string(),
+                    case_sensitive: false,
+                    id: *id,
+                    name: name.to_string(),
+                    subpages: *subpages,
+                });
+            }
+        }
+        for namespace in source.namespaces {
+            configuration.add_namespace(::NamespaceData {
+                aliases: namespace.aliases.iter().map(|alias| alias.to_string()).collect(),
+                canonical_name: namespace.canonical_name.to_string(),
+                case_sensitive: namespace.case_sensitive,
+                id: namespace.id,
+                name: namespace.name.to_string(),
+                subpages: namespace.subpages,
+            });
+        }
         for namespace in source.category_namespaces {
             configuration
                 .namespaces
                 .add_term(&format!("{}:", namespace), Namespace::Category);
+            configuration
+                .namespace_ids
+                .insert(namespace.replace('_', " ").to_lowercase(), 14);
         }
         for namespace in source.file_namespaces {
             configuration
                 .namespaces
                 .add_term(&format!("{}:", namespace), Namespace::File);
+            configuration
+                .namespace_ids
+                .insert(namespace.replace('_', " ").to_lowercase(), 6);
+        }
+        // Like in MediaWiki, names of namespaces take precedence over interwiki prefixes.
+        for prefix in source.language_link_prefixes {
+            if configuration.is_namespace_name(prefix) {
+                continue;
+            }
+            configuration
+                .namespaces
+                .add_term(&format!("{}:", prefix), Namespace::Language);
+            configuration
+                .interwiki_prefixes
+                .insert(prefix.to_lowercase());
+        }
+        for prefix in source.interwiki_prefixes {
+            if configuration.is_namespace_name(prefix) {
+                continue;
+            }
+            configuration
+                .namespaces
+                .add_term(&format!("{}:", prefix), Namespace::Interwiki);
+            configuration
+                .interwiki_prefixes
+                .insert(prefix.to_lowercase());
         }
         for redirect_magic_word in source.redirect_magic_words {
             configuration
@@ -148,10 +255,37 @@ impl ::Configuration {
         configuration
     }
 
+    fn add_namespace(&mut self, data: ::NamespaceData) {
+        let type_ = match data.id {
+            6 => Some(Namespace::File),
+            14 => Some(Namespace::Category),
+            _ => None,
+        };
+        for name in ::std::iter::once(&data.name)
+            .chain(::std::iter::once(&data.canonical_name))
+            .chain(&data.aliases)
+        {
+            if name.is_empty() {
+                continue;
+            }
+            if let Some(type_) = type_ {
+                self.namespaces.add_term(&format!("{}:", name), type_);
+            }
+            self.namespace_ids
+                .insert(name.replace('_', " ").to_lowercase(), data.id);
+        }
+        self.namespace_data.push(data);
+    }
+
+    fn is_namespace_name(&self, name: &str) -> bool {
+        self.namespace_ids
+            .contains_key(&name.replace('_', " ").to_lowercase())
+    }
+
     /// Parses wiki text into structured data.
     #[must_use]
     pub fn parse<'a>(&self, wiki_text: &'a str) -> ::Output<'a> {
-        ::parse::parse(self, wiki_text)
+        ::parse::parse(self, wiki_text, &::ParseLimits::default(), ::TransclusionMode::View)
     }
 }
 
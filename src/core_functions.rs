@@ -0,0 +1,593 @@
+// Copyright 2018 Fredrik Portström <https://portstrom.com>
+// This is free software distributed under the terms specified in
+// the file LICENSE at the top-level directory of this distribution.
+
+use expand::{Expander, Frame, TemplateProvider};
+
+impl<'a, P: TemplateProvider + ?Sized> Expander<'a, P> {
+    /// Evaluates the core parser function of Mediawiki with the given name, or returns `None` if there is no such function.
+    ///
+    /// The first argument is the text following the colon in the name of the template, and the remaining arguments are the parameters of the template, which are expanded only when used.
+    pub fn call_core_function(
+        &mut self,
+        frame: &Frame,
+        wiki_text: &str,
+        name: &str,
+        first_argument: &str,
+        parameters: &[::Parameter],
+    ) -> Option<String> {
+        let argument = |expander: &mut Self, index: usize| match parameters.get(index) {
+            None => String::new(),
+            Some(parameter) => expander
+                .expand_parameter(frame, wiki_text, parameter)
+                .trim()
+                .to_string(),
+        };
+        // Unlike the other functions, the names of the variables that take a title as argument are case sensitive.
+        if let Some(text) = self.page_name_variable(name, Some(first_argument)) {
+            return Some(text);
+        }
+        Some(match &name.to_lowercase() as &str {
+            "#tag" => self.tag(frame, wiki_text, first_argument, parameters),
+            "anchorencode" => anchor_encode(self.configuration, first_argument),
+            "formatnum" => {
+                let option = argument(self, 0);
+                format_number(first_argument, &option, &self.context.content_language)
+            }
+            "int" => {
+                if first_argument.is_empty() {
+                    return None;
+                }
+                let arguments: Vec<String> = (0..parameters.len())
+                    .map(|index| argument(self, index))
+                    .collect();
+                self.message(frame, first_argument, &arguments)
+            }
+            "lc" => first_argument.to_lowercase(),
+            "lcfirst" => change_first_character(first_argument, char::to_lowercase),
+            "padleft" | "padright" => {
+                let length = ::parser_functions::php_int(&argument(self, 0));
+                let padding = match parameters.get(1) {
+                    None => "0".to_string(),
+                    Some(_) => argument(self, 1),
+                };
+                pad(
+                    first_argument,
+                    length,
+                    &padding,
+                    name.eq_ignore_ascii_case("padleft"),
+                )
+            }
+            "uc" => first_argument.to_uppercase(),
+            "ucfirst" => change_first_character(first_argument, char::to_uppercase),
+            "urlencode" => {
+                let style = argument(self, 0);
+                url_encode(first_argument, &style)
+            }
+            _ => return None,
+        })
+    }
+
+    /// Gets the value of the variable with the given name, such as `PAGENAME`, or returns `None` if there is no such variable.
+    pub fn variable(&mut self, name: &str) -> Option<String> {
+        if let Some(text) = self.page_name_variable(name, None) {
+            return Some(text);
+        }
+        match name {
+            "CONTENTLANG" | "CONTENTLANGUAGE" => {
+                return Some(self.context.content_language.clone());
+            }
+            "SITENAME" => return Some(self.context.site_name.clone()),
+            _ => {}
+        }
+        let current = name
+            .strip_prefix("CURRENT")
+            .or_else(|| name.strip_prefix("LOCAL"));
+        let (format, time) = if let Some(current) = current {
+            let format = match current {
+                "DAY" => "j",
+                "DAY2" => "d",
+                "DAYNAME" => "l",
+                "DOW" => "w",
+                "HOUR" => "H",
+                "MONTH" | "MONTH2" => "m",
+                "MONTH1" => "n",
+                "MONTHABBREV" => "M",
+                "MONTHNAME" => "F",
+                "MONTHNAMEGEN" => "xg",
+                "TIME" => "H:i",
+                "TIMESTAMP" => "YmdHis",
+                "WEEK" => "W",
+                "YEAR" => "Y",
+                _ => return None,
+            };
+            (format, self.provider.current_time())
+        } else if let Some(revision) = name.strip_prefix("REVISION") {
+            let format = match revision {
+                "DAY" => "j",
+                "DAY2" => "d",
+                "MONTH" => "m",
+                "MONTH1" => "n",
+                "TIMESTAMP" => "YmdHis",
+                "YEAR" => "Y",
+                _ => return None,
+            };
+            let time = match self.context.revision_timestamp {
+                None => self.provider.current_time(),
+                Some(time) => time,
+            };
+            (format, time)
+        } else {
+            return None;
+        };
+        let text = ::time::format_time(format, &format!("@{}", time), time).unwrap_or_default();
+        // Like in Mediawiki, the week number has no leading zero.
+        Some(if format == "W" {
+            text.trim_start_matches('0').to_string()
+        } else {
+            text
+        })
+    }
+
+    fn message(&mut self, frame: &Frame, key: &str, arguments: &[String]) -> String {
+        let normalized_key = change_first_character(&key.replace(' ', "_"), char::to_lowercase);
+        let page_text = match self.configuration.parse_title(key, 8) {
+            Ok(title) if title.namespace == 8 && title.interwiki.is_none() => {
+                self.provider.wiki_text(&title)
+            }
+            _ => None,
+        };
+        let text = match page_text.or_else(|| {
+            self.provider
+                .message(&normalized_key, &self.context.content_language)
+        }) {
+            None => {
+                let mut text = "⧼".to_string();
+                write_escaped(key, &mut text);
+                text.push('⧽');
+                return text;
+            }
+            Some(text) => text,
+        };
+        // The parameters are replaced in a single pass, so that the arguments can contain `$` followed by digits.
+        let mut replaced = String::new();
+        let mut rest = &text as &str;
+        while let Some(position) = rest.find('$') {
+            replaced += &rest[..position];
+            let digit_count = rest[position + 1..]
+                .bytes()
+                .take_while(u8::is_ascii_digit)
+                .count();
+            let digits = &rest[position + 1..position + 1 + digit_count];
+            match digits
+                .parse::<usize>()
+                .ok()
+                .and_then(|number| number.checked_sub(1))
+                .and_then(|index| arguments.get(index))
+            {
+                None => replaced += &rest[position..position + 1 + digit_count],
+                Some(argument) => replaced += argument,
+            }
+            rest = &rest[position + 1 + digit_count..];
+        }
+        replaced += rest;
+        // Like in Mediawiki, the message is expanded as wiki text, without the arguments of the template it's used in. The message can use itself, so it counts towards the maximum depth like a template.
+        if frame.depth >= self.limits.max_depth {
+            return self.depth_limit_error();
+        }
+        let output = self.configuration.parse(&replaced);
+        let mut expanded = String::new();
+        self.expand_nodes(
+            &Frame {
+                depth: frame.depth + 1,
+                parent: Some(frame),
+                ..Frame::default()
+            },
+            &replaced,
+            &output.nodes,
+            0,
+            replaced.len(),
+            &mut expanded,
+        );
+        expanded
+    }
+
+    fn page_name_variable(&mut self, name: &str, argument: Option<&str>) -> Option<String> {
+        let (base_name, url_encoded) = match name {
+            "NAMESPACENUMBER" => ("NAMESPACENUMBER", false),
+            _ => match name.strip_suffix('E') {
+                Some(base_name) if is_page_name_variable(base_name) => (base_name, true),
+                _ if is_page_name_variable(name) => (name, false),
+                _ => return None,
+            },
+        };
+        let parsed_title;
+        let title = match argument {
+            None => &self.context.title,
+            Some(argument) => match self.configuration.parse_title(argument, 0) {
+                Err(_) => return Some(String::new()),
+                Ok(title) => {
+                    parsed_title = title;
+                    &parsed_title
+                }
+            },
+        };
+        let namespace_name = |id: i32| match id {
+            0 => String::new(),
+            id => self
+                .configuration
+                .namespace(id)
+                .map_or_else(String::new, |namespace| namespace.name.clone()),
+        };
+        // Special pages and media have no talk pages.
+        let can_have_talk_page = title.namespace >= 0 && title.interwiki.is_none();
+        let with_namespace = |namespace_name: String| {
+            if namespace_name.is_empty() {
+                title.page_name.clone()
+            } else {
+                format!("{}:{}", namespace_name, title.page_name)
+            }
+        };
+        let text = match base_name {
+            "BASEPAGENAME" => title.base_page_name().to_string(),
+            "FULLPAGENAME" => title.full_text(),
+            "NAMESPACE" => title.namespace_name.clone(),
+            "NAMESPACENUMBER" => title.namespace.to_string(),
+            "PAGENAME" => title.page_name.clone(),
+            "ROOTPAGENAME" => title.subpage_path()[0].to_string(),
+            "SUBJECTPAGENAME" | "ARTICLEPAGENAME" => {
+                if can_have_talk_page {
+                    with_namespace(namespace_name(title.namespace & !1))
+                } else {
+                    title.full_text()
+                }
+            }
+            "SUBJECTSPACE" | "ARTICLESPACE" => {
+                if can_have_talk_page {
+                    namespace_name(title.namespace & !1)
+                } else {
+                    title.namespace_name.clone()
+                }
+            }
+            "SUBPAGENAME" => title.subpage_path().last().unwrap_or(&"").to_string(),
+            "TALKPAGENAME" if can_have_talk_page => {
+                with_namespace(namespace_name(title.namespace | 1))
+            }
+            "TALKSPACE" if can_have_talk_page => namespace_name(title.namespace | 1),
+            _ => String::new(),
+        };
+        Some(escape_wiki_text(&if url_encoded {
+            let mut encoded = String::new();
+            write_url_encoded(&text.replace(' ', "_"), UrlEncoding::Wiki, &mut encoded);
+            encoded
+        } else {
+            text
+        }))
+    }
+
+    fn tag(
+        &mut self,
+        frame: &Frame,
+        wiki_text: &str,
+        name: &str,
+        parameters: &[::Parameter],
+    ) -> String {
+        let name = name.to_lowercase();
+        let content = parameters
+            .first()
+            .map(|parameter| self.expand_parameter(frame, wiki_text, parameter));
+        let mut attributes: Vec<(String, String)> = vec![];
+        for parameter in parameters.iter().skip(1) {
+            let attribute_name = match &parameter.name {
+                None => continue,
+                Some(attribute_name) => self.expand_sequence(frame, wiki_text, attribute_name),
+            };
+            let attribute_name = attribute_name.trim().to_string();
+            let value = self.expand_sequence(frame, wiki_text, &parameter.value);
+            let value = value.trim();
+            // A value enclosed in quotes is unquoted.
+            let value = if value.len() >= 2
+                && (value.starts_with('"') || value.starts_with('\''))
+                && (value.ends_with('"') || value.ends_with('\''))
+            {
+                &value[1..value.len() - 1]
+            } else {
+                value
+            };
+            match attributes
+                .iter_mut()
+                .find(|(existing_name, _)| *existing_name == attribute_name)
+            {
+                Some(attribute) => attribute.1 = value.to_string(),
+                None => attributes.push((attribute_name, value.to_string())),
+            }
+        }
+        let mut output = format!("<{}", name);
+        for (attribute_name, value) in attributes {
+            output.push(' ');
+            for character in attribute_name.chars() {
+                match character {
+                    '\'' => output += "&#039;",
+                    _ => write_escaped(character.encode_utf8(&mut [0; 4]), &mut output),
+                }
+            }
+            output += "=\"";
+            write_escaped(&value, &mut output);
+            output.push('"');
+        }
+        match content {
+            None => output += "/>",
+            Some(content) => {
+                output.push('>');
+                output += &content;
+                output += &format!("</{}>", name);
+            }
+        }
+        output
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum UrlEncoding {
+    Path,
+    Query,
+    Wiki,
+}
+
+/// Encodes the text the same way as the anchor of a heading, as by `anchorencode`.
+fn anchor_encode(configuration: &::Configuration, text: &str) -> String {
+    let output = configuration.parse(text);
+    let text = ::to_plain_text(
+        &output.nodes,
+        &::PlainTextProfile {
+            keep_references: true,
+            keep_tables: true,
+            template: None,
+        },
+    );
+    let text = text
+        .split(|character: char| character.is_whitespace() || character == '_')
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>()
+        .join("_");
+    let mut output = String::new();
+    let mut position = 0;
+    while position < text.len() {
+        let rest = &text[position..];
+        // The first character of these words is encoded so that they are not magic links.
+        if let Some(word) = ["ISBN", "PMID", "RFC"]
+            .iter()
+            .find(|word| rest.starts_with(*word))
+        {
+            output += &format!("&#{};{}", word.as_bytes()[0], &word[1..]);
+            position += word.len();
+            continue;
+        }
+        if rest.starts_with("__") {
+            output += "&#95;_";
+            position += 2;
+            continue;
+        }
+        if rest.starts_with("''") {
+            output += "&#39;&#39;";
+            position += 2;
+            continue;
+        }
+        let character = rest.chars().next().unwrap_or_default();
+        match character {
+            '"' => output += "&quot;",
+            '&' => output += "&amp;",
+            '\'' => output += "&#039;",
+            '<' => output += "&lt;",
+            '>' => output += "&gt;",
+            '[' => output += "&#91;",
+            ']' => output += "&#93;",
+            '{' => output += "&#123;",
+            '|' => output += "&#124;",
+            '}' => output += "&#125;",
+            ':' if rest.starts_with("://") => output += "&#58;",
+            _ => output.push(character),
+        }
+        position += character.len_utf8();
+    }
+    output
+}
+
+fn change_first_character<I: Iterator<Item = char>>(text: &str, change: fn(char) -> I) -> String {
+    let mut characters = text.chars();
+    match characters.next() {
+        None => String::new(),
+        Some(first) => change(first).chain(characters).collect(),
+    }
+}
+
+/// Escapes the characters that have a meaning in wiki text the same way as Mediawiki, so that the text is displayed as is.
+fn escape_wiki_text(text: &str) -> String {
+    let mut output = String::new();
+    let mut position = 0;
+    while position < text.len() {
+        let rest = &text[position..];
+        if let Some(word) = ["ISBN ", "PMID ", "RFC "]
+            .iter()
+            .find(|word| rest.starts_with(*word))
+        {
+            output += &word[..word.len() - 1];
+            output += "&#32;";
+            position += word.len();
+            continue;
+        }
+        if let Some((pattern, replacement)) =
+            [("://", "&#58;//"), ("__", "_&#95;"), ("~~~", "~~&#126;")]
+                .iter()
+                .find(|(pattern, _)| rest.starts_with(pattern))
+        {
+            output += replacement;
+            position += pattern.len();
+            continue;
+        }
+        let character = rest.chars().next().unwrap_or_default();
+        match character {
+            '"' | '&' | '\'' | ';' | '<' | '=' | '>' | '[' | ']' | '{' | '|' | '}' => {
+                output += &format!("&#{};", character as u32)
+            }
+            '#' | '*' | ':' | ' ' | '\n' | '\r' | '\t' if position == 0 => {
+                output += &format!("&#{};", character as u32)
+            }
+            _ => output.push(character),
+        }
+        position += character.len_utf8();
+    }
+    output
+}
+
+/// Formats the numbers in the text the same way as `formatnum`, or converts formatted numbers back if the option is `R`.
+///
+/// Numbers are formatted with the separators of the content language where known, and with the separators used in English otherwise.
+fn format_number(text: &str, option: &str, language: &str) -> String {
+    let (group_separator, decimal_separator) = match language {
+        "da" | "de" | "id" | "it" | "nl" => (".", ","),
+        _ => (",", "."),
+    };
+    if option == "R" {
+        let mut output = String::new();
+        for character in text.chars() {
+            match character {
+                '\u{2212}' => output.push('-'),
+                _ if group_separator.starts_with(character) => {}
+                _ if decimal_separator.starts_with(character) => output.push('.'),
+                _ => output.push(character),
+            }
+        }
+        return output;
+    }
+    let group = option != "NOSEP";
+    let bytes = text.as_bytes();
+    let mut output = String::new();
+    let mut position = 0;
+    while position < bytes.len() {
+        let digit_count = |position: usize| {
+            bytes[position.min(bytes.len())..]
+                .iter()
+                .take_while(|byte| byte.is_ascii_digit())
+                .count()
+        };
+        let negative = bytes[position] == b'-';
+        let start = position + negative as usize;
+        let integer_digits = digit_count(start);
+        let has_fraction = bytes.get(start + integer_digits) == Some(&b'.');
+        if integer_digits == 0 && !(has_fraction && digit_count(start + 1) > 0) {
+            let character = text[position..].chars().next().unwrap_or_default();
+            output.push(character);
+            position += character.len_utf8();
+            continue;
+        }
+        if negative {
+            output.push('\u{2212}');
+        }
+        let integer = &text[start..start + integer_digits];
+        for (index, digit) in integer.chars().enumerate() {
+            if group && index > 0 && (integer_digits - index) % 3 == 0 {
+                output += group_separator;
+            }
+            output.push(digit);
+        }
+        position = start + integer_digits;
+        if has_fraction {
+            let fraction_digits = digit_count(position + 1);
+            output += decimal_separator;
+            output += &text[position + 1..position + 1 + fraction_digits];
+            position += 1 + fraction_digits;
+        }
+        if let Some(b'E') | Some(b'e') = bytes.get(position) {
+            let sign = match bytes.get(position + 1) {
+                Some(b'+') | Some(b'-') => 1,
+                _ => 0,
+            };
+            let exponent_digits = digit_count(position + 1 + sign);
+            if exponent_digits > 0 {
+                let end = position + 1 + sign + exponent_digits;
+                output += &text[position..end];
+                position = end;
+            }
+        }
+    }
+    output
+}
+
+fn is_page_name_variable(name: &str) -> bool {
+    matches!(
+        name,
+        "ARTICLEPAGENAME"
+            | "ARTICLESPACE"
+            | "BASEPAGENAME"
+            | "FULLPAGENAME"
+            | "NAMESPACE"
+            | "PAGENAME"
+            | "ROOTPAGENAME"
+            | "SUBJECTPAGENAME"
+            | "SUBJECTSPACE"
+            | "SUBPAGENAME"
+            | "TALKPAGENAME"
+            | "TALKSPACE"
+    )
+}
+
+/// Pads the text to the given length in characters by repeating the padding, as by `padleft` and `padright`. The length is at most 500.
+fn pad(text: &str, length: i64, padding: &str, left: bool) -> String {
+    let padding: Vec<char> = padding.chars().collect();
+    let mut remaining = length.min(500) - text.chars().count() as i64;
+    if padding.is_empty() || remaining <= 0 {
+        return text.to_string();
+    }
+    let mut padded = String::new();
+    while remaining > 0 {
+        padded.extend(padding.iter().take(remaining as usize));
+        remaining -= padding.len() as i64;
+    }
+    if left {
+        padded + text
+    } else {
+        text.to_string() + &padded
+    }
+}
+
+/// Encodes the text for use in a URL, as by `urlencode`. The style is `QUERY` for a query string, which is the default, `PATH` for a path or `WIKI` for a page name.
+fn url_encode(text: &str, style: &str) -> String {
+    let mut output = String::new();
+    match style {
+        "PATH" => write_url_encoded(text, UrlEncoding::Path, &mut output),
+        "WIKI" => write_url_encoded(&text.replace(' ', "_"), UrlEncoding::Wiki, &mut output),
+        _ => write_url_encoded(text, UrlEncoding::Query, &mut output),
+    }
+    output
+}
+
+fn write_escaped(text: &str, output: &mut String) {
+    for character in text.chars() {
+        match character {
+            '"' => *output += "&quot;",
+            '&' => *output += "&amp;",
+            '<' => *output += "&lt;",
+            '>' => *output += "&gt;",
+            _ => output.push(character),
+        }
+    }
+}
+
+fn write_url_encoded(text: &str, encoding: UrlEncoding, output: &mut String) {
+    for byte in text.bytes() {
+        match byte {
+            b' ' if encoding == UrlEncoding::Query => output.push('+'),
+            b'-' | b'.' | b'_' | b'0'..=b'9' | b'A'..=b'Z' | b'a'..=b'z' => {
+                output.push(byte as char)
+            }
+            b'~' if encoding != UrlEncoding::Query => output.push('~'),
+            b'!' | b'$' | b'(' | b')' | b'*' | b',' | b'/' | b':' | b';' | b'@'
+                if encoding == UrlEncoding::Wiki =>
+            {
+                output.push(byte as char)
+            }
+            _ => *output += &format!("%{:02X}", byte),
+        }
+    }
+}
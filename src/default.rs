@@ -14,12 +14,15 @@ pub fn create_configuration() -> ::Configuration {
             "graph",
             "hiero",
             "imagemap",
+            "includeonly",
             "indicator",
             "inputbox",
             "mapframe",
             "maplink",
             "math",
+            "noinclude",
             "nowiki",
+            "onlyinclude",
             "poem",
             "pre",
             "ref",
@@ -32,6 +35,39 @@ pub fn create_configuration() -> ::Configuration {
             "timeline",
         ],
         file_namespaces: &["file", "image"],
+        interwiki_prefixes: &[
+            "commons",
+            "mediawikiwiki",
+            "meta",
+            "wikibooks",
+            "wikidata",
+            "wikinews",
+            "wikiquote",
+            "wikisource",
+            "wikispecies",
+            "wikiversity",
+            "wikivoyage",
+            "wikt",
+            "wiktionary",
+        ],
+        language_link_prefixes: &[
+            "ar",
+            "de",
+            "en",
+            "es",
+            "fa",
+            "fr",
+            "it",
+            "ja",
+            "nl",
+            "pl",
+            "pt",
+            "ru",
+            "sv",
+            "uk",
+            "vi",
+            "zh",
+        ],
         link_trail: "ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz",
         magic_words: &[
             "DISAMBIG",
@@ -53,6 +89,7 @@ pub fn create_configuration() -> ::Configuration {
             "STATICREDIRECT",
             "TOC",
         ],
+        namespaces: &[],
         protocols: &[
             "//",
             "bitcoin:",
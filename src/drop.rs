@@ -0,0 +1,89 @@
+// Copyright 2018 Fredrik Portström <https://portstrom.com>
+// This is free software distributed under the terms specified in
+// the file LICENSE at the top-level directory of this distribution.
+
+// Dropping a tree of nodes the default way recurses once for each level of nesting, which overflows the stack for deeply nested wiki text.
+
+use std::{
+    fmt, ops::{Deref, DerefMut},
+};
+
+impl ::Document {
+    /// Takes the top level of parsed nodes from the document, dropping the rest of it.
+    #[must_use]
+    pub fn into_nodes(self) -> Vec<::Node<'static>> {
+        self.nodes.into_vec()
+    }
+}
+
+impl<'a> ::Nodes<'a> {
+    /// Takes the vector of nodes.
+    #[must_use]
+    pub fn into_vec(mut self) -> Vec<::Node<'a>> {
+        ::std::mem::take(&mut self.0)
+    }
+}
+
+impl<'a> fmt::Debug for ::Nodes<'a> {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        self.0.fmt(formatter)
+    }
+}
+
+impl<'a> Deref for ::Nodes<'a> {
+    type Target = Vec<::Node<'a>>;
+
+    fn deref(&self) -> &Vec<::Node<'a>> {
+        &self.0
+    }
+}
+
+impl<'a> DerefMut for ::Nodes<'a> {
+    fn deref_mut(&mut self) -> &mut Vec<::Node<'a>> {
+        &mut self.0
+    }
+}
+
+impl<'a> Drop for ::Nodes<'a> {
+    fn drop(&mut self) {
+        let mut nodes = ::std::mem::take(&mut self.0);
+        while let Some(mut node) = nodes.pop() {
+            ::visit::for_each_child_sequence_mut(&mut node, |child_nodes| {
+                nodes.append(child_nodes)
+            });
+        }
+    }
+}
+
+impl<'a> From<Vec<::Node<'a>>> for ::Nodes<'a> {
+    fn from(nodes: Vec<::Node<'a>>) -> Self {
+        ::Nodes(nodes)
+    }
+}
+
+impl<'a> IntoIterator for ::Nodes<'a> {
+    type IntoIter = ::std::vec::IntoIter<::Node<'a>>;
+    type Item = ::Node<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.into_vec().into_iter()
+    }
+}
+
+impl<'a, 'b> IntoIterator for &'b ::Nodes<'a> {
+    type IntoIter = ::std::slice::Iter<'b, ::Node<'a>>;
+    type Item = &'b ::Node<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.iter()
+    }
+}
+
+impl<'a, 'b> IntoIterator for &'b mut ::Nodes<'a> {
+    type IntoIter = ::std::slice::IterMut<'b, ::Node<'a>>;
+    type Item = &'b mut ::Node<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.iter_mut()
+    }
+}
@@ -0,0 +1,368 @@
+// Copyright 2018 Fredrik Portström <https://portstrom.com>
+// This is free software distributed under the terms specified in
+// the file LICENSE at the top-level directory of this distribution.
+
+use std::collections::VecDeque;
+use Positioned;
+
+/// Event in the stream of events from parsing wiki text with `Configuration::parse_events`.
+///
+/// The events for a node are `Enter`, then the events for the content of the node, then `Exit`, except that text is a single `Text` event. The content of some nodes is divided into parts, which are in turn delimited by `EnterPart` and `ExitPart`. Flattening the nodes of `Configuration::parse` this way gives the same events in the same order.
+#[derive(Debug, Eq, PartialEq)]
+pub enum Event<'a> {
+    /// The start of a node.
+    Enter {
+        /// The byte position in the wiki text where the node ends.
+        end: usize,
+
+        /// The type of the node.
+        kind: ::NodeKind,
+
+        /// The byte position in the wiki text where the node starts.
+        start: usize,
+    },
+
+    /// The start of a part of a node.
+    EnterPart {
+        /// The byte position in the wiki text where the part ends.
+        end: usize,
+
+        /// The type of the part.
+        kind: PartKind,
+
+        /// The byte position in the wiki text where the part starts.
+        start: usize,
+    },
+
+    /// The end of a node.
+    Exit {
+        /// The byte position in the wiki text where the node ends.
+        end: usize,
+
+        /// The type of the node.
+        kind: ::NodeKind,
+
+        /// The byte position in the wiki text where the node starts.
+        start: usize,
+    },
+
+    /// The end of a part of a node.
+    ExitPart {
+        /// The byte position in the wiki text where the part ends.
+        end: usize,
+
+        /// The type of the part.
+        kind: PartKind,
+
+        /// The byte position in the wiki text where the part starts.
+        start: usize,
+    },
+
+    /// Text, the same as a node of type `Node::Text`.
+    Text {
+        /// The byte position in the wiki text where the text ends.
+        end: usize,
+
+        /// The byte position in the wiki text where the text starts.
+        start: usize,
+
+        /// The text.
+        value: ::Cow<'a, str>,
+    },
+
+    /// Warning from the parser. The warnings come in the same order as in `Output::warnings`, each one before the events of the nodes that were parsed after it.
+    Warning(::Warning),
+}
+
+/// Iterator over the events from parsing wiki text.
+///
+/// The text is parsed in chunks as the events are consumed. A chunk ends at a line break where no nodes are open, and the nodes of a chunk are parsed in full before any of their events are given, so the nodes of at most one chunk are kept in memory at a time. A table, template or tag that is open over many lines makes a chunk of all those lines, and a node that is never closed is only rewound at the end of the text, so for such wiki text as much is kept in memory as by `Configuration::parse`.
+pub struct Events<'a, 'b> {
+    configuration: &'b ::Configuration,
+    events: VecDeque<Event<'a>>,
+    is_finished: bool,
+    is_started: bool,
+    state: ::State<'a>,
+}
+
+/// Identifier for the type of a part of a node.
+///
+/// A part that has no nodes has an empty span at the start of the element it's part of.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum PartKind {
+    /// The attributes of a table, table caption, table row or table cell. It comes first in the element, and only if the element has attributes.
+    Attributes,
+
+    /// The default of a parameter. It comes after the name of the parameter, and only if the parameter has a default.
+    Default,
+
+    /// A `DefinitionListItem` of a definition list.
+    DefinitionListItem,
+
+    /// A `ListItem` of an ordered or unordered list.
+    ListItem,
+
+    /// The name of a parameter, template or parameter of a template. It comes first in the element, and in a parameter of a template only if the parameter is named.
+    Name,
+
+    /// A `Parameter` of a template.
+    Parameter,
+
+    /// A `TableCaption` of a table.
+    TableCaption,
+
+    /// A `TableCell` of a table row.
+    TableCell,
+
+    /// A `TableRow` of a table.
+    TableRow,
+}
+
+impl ::Configuration {
+    /// Parses wiki text into a stream of events instead of a tree of nodes.
+    ///
+    /// Useful for processing large amounts of wiki text when only some of the nodes are of interest, or for building data structures other than `Node`.
+    #[must_use]
+    pub fn parse_events<'a, 'b>(&'b self, wiki_text: &'a str) -> Events<'a, 'b> {
+        Events {
+            configuration: self,
+            events: VecDeque::new(),
+            is_finished: false,
+            is_started: false,
+            state: ::State {
+                flushed_position: 0,
+                is_only_include: false,
+                mode: ::TransclusionMode::View,
+                node_count: 0,
+                nodes: vec![],
+                open_link_count: 0,
+                scan_position: 0,
+                stack: vec![],
+                warnings: vec![],
+                wiki_text,
+            },
+        }
+    }
+}
+
+impl<'a, 'b> Iterator for Events<'a, 'b> {
+    type Item = Event<'a>;
+
+    fn next(&mut self) -> Option<Event<'a>> {
+        while self.events.is_empty() && !self.is_finished {
+            self.parse_chunk();
+        }
+        self.events.pop_front()
+    }
+}
+
+impl<'a, 'b> Events<'a, 'b> {
+    fn parse_chunk(&mut self) {
+        let state = &mut self.state;
+        if !self.is_started {
+            ::parse::parse_beginning_of_text(self.configuration, state);
+            self.is_started = true;
+        }
+        // With no open nodes, all nodes except the last are final. Text before an open node that is rewound is joined with the text of the open node, so the last node is kept until more nodes have been parsed.
+        let count = if ::parse::parse_until(
+            self.configuration,
+            &::ParseLimits::default(),
+            state,
+            |state| state.stack.is_empty() && state.nodes.len() > 1,
+        ) {
+            state.nodes.len() - 1
+        } else {
+            let end_position = state.skip_whitespace_backwards(state.wiki_text.len());
+            state.flush(end_position);
+            self.is_finished = true;
+            state.nodes.len()
+        };
+        let events = &mut self.events;
+        events.extend(state.warnings.drain(..).map(Event::Warning));
+        for node in state.nodes.drain(..count) {
+            push_node(events, node);
+        }
+    }
+}
+
+/// Event, or node that is yet to be turned into events.
+enum Item<'a> {
+    Event(Event<'a>),
+    Node(::Node<'a>),
+}
+
+// Nodes are turned into events with an explicit stack instead of recursion, since recursing once for each level of nesting overflows the stack for deeply nested wiki text.
+fn push_node<'a>(events: &mut VecDeque<Event<'a>>, node: ::Node<'a>) {
+    let mut stack = vec![Item::Node(node)];
+    while let Some(item) = stack.pop() {
+        match item {
+            Item::Event(event) => events.push_back(event),
+            Item::Node(::Node::Text { end, start, value }) => {
+                events.push_back(Event::Text { end, start, value })
+            }
+            Item::Node(node) => {
+                let end = node.end();
+                let kind = node.kind();
+                let start = node.start();
+                events.push_back(Event::Enter { end, kind, start });
+                let mut items = vec![];
+                push_content(&mut items, node);
+                items.push(Item::Event(Event::Exit { end, kind, start }));
+                stack.extend(items.into_iter().rev());
+            }
+        }
+    }
+}
+
+/// Pushes the items for the content of the node, which are the events between its `Enter` and `Exit` events.
+fn push_content<'a>(items: &mut Vec<Item<'a>>, node: ::Node<'a>) {
+    let start = node.start();
+    match node {
+        ::Node::Category { ordinal: nodes, .. }
+        | ::Node::ExternalLink { nodes, .. }
+        | ::Node::Heading { nodes, .. }
+        | ::Node::Image { text: nodes, .. }
+        | ::Node::InterwikiLink { text: nodes, .. }
+        | ::Node::LanguageLink { text: nodes, .. }
+        | ::Node::Link { text: nodes, .. }
+        | ::Node::Preformatted { nodes, .. }
+        | ::Node::Tag { nodes, .. } => push_nodes(items, nodes),
+        ::Node::DefinitionList {
+            items: list_items, ..
+        } => {
+            for item in list_items {
+                push_part(
+                    items,
+                    PartKind::DefinitionListItem,
+                    item.start,
+                    item.end,
+                    item.nodes,
+                );
+            }
+        }
+        ::Node::OrderedList {
+            items: list_items, ..
+        }
+        | ::Node::UnorderedList {
+            items: list_items, ..
+        } => {
+            for item in list_items {
+                push_part(items, PartKind::ListItem, item.start, item.end, item.nodes);
+            }
+        }
+        ::Node::Parameter { default, name, .. } => {
+            push_nodes_part(items, PartKind::Name, start, name);
+            if let Some(default) = default {
+                push_nodes_part(items, PartKind::Default, start, default);
+            }
+        }
+        ::Node::Table {
+            attributes,
+            captions,
+            rows,
+            ..
+        } => {
+            if !attributes.is_empty() {
+                push_nodes_part(items, PartKind::Attributes, start, attributes);
+            }
+            for caption in captions {
+                items.push(Item::Event(Event::EnterPart {
+                    end: caption.end,
+                    kind: PartKind::TableCaption,
+                    start: caption.start,
+                }));
+                if let Some(attributes) = caption.attributes {
+                    push_nodes_part(items, PartKind::Attributes, caption.start, attributes);
+                }
+                push_nodes(items, caption.content);
+                items.push(Item::Event(Event::ExitPart {
+                    end: caption.end,
+                    kind: PartKind::TableCaption,
+                    start: caption.start,
+                }));
+            }
+            for row in rows {
+                items.push(Item::Event(Event::EnterPart {
+                    end: row.end,
+                    kind: PartKind::TableRow,
+                    start: row.start,
+                }));
+                if !row.attributes.is_empty() {
+                    push_nodes_part(items, PartKind::Attributes, row.start, row.attributes);
+                }
+                for cell in row.cells {
+                    items.push(Item::Event(Event::EnterPart {
+                        end: cell.end,
+                        kind: PartKind::TableCell,
+                        start: cell.start,
+                    }));
+                    if let Some(attributes) = cell.attributes {
+                        push_nodes_part(items, PartKind::Attributes, cell.start, attributes);
+                    }
+                    push_nodes(items, cell.content);
+                    items.push(Item::Event(Event::ExitPart {
+                        end: cell.end,
+                        kind: PartKind::TableCell,
+                        start: cell.start,
+                    }));
+                }
+                items.push(Item::Event(Event::ExitPart {
+                    end: row.end,
+                    kind: PartKind::TableRow,
+                    start: row.start,
+                }));
+            }
+        }
+        ::Node::Template {
+            name, parameters, ..
+        } => {
+            push_nodes_part(items, PartKind::Name, start, name);
+            for parameter in parameters {
+                items.push(Item::Event(Event::EnterPart {
+                    end: parameter.end,
+                    kind: PartKind::Parameter,
+                    start: parameter.start,
+                }));
+                if let Some(name) = parameter.name {
+                    push_nodes_part(items, PartKind::Name, parameter.start, name);
+                }
+                push_nodes(items, parameter.value);
+                items.push(Item::Event(Event::ExitPart {
+                    end: parameter.end,
+                    kind: PartKind::Parameter,
+                    start: parameter.start,
+                }));
+            }
+        }
+        _ => {}
+    }
+}
+
+fn push_nodes<'a>(items: &mut Vec<Item<'a>>, nodes: Vec<::Node<'a>>) {
+    items.extend(nodes.into_iter().map(Item::Node));
+}
+
+fn push_nodes_part<'a>(
+    items: &mut Vec<Item<'a>>,
+    kind: PartKind,
+    element_start: usize,
+    nodes: Vec<::Node<'a>>,
+) {
+    let (start, end) = match (nodes.first(), nodes.last()) {
+        (Some(first), Some(last)) => (first.start(), last.end()),
+        _ => (element_start, element_start),
+    };
+    push_part(items, kind, start, end, nodes);
+}
+
+fn push_part<'a>(
+    items: &mut Vec<Item<'a>>,
+    kind: PartKind,
+    start: usize,
+    end: usize,
+    nodes: Vec<::Node<'a>>,
+) {
+    items.push(Item::Event(Event::EnterPart { end, kind, start }));
+    push_nodes(items, nodes);
+    items.push(Item::Event(Event::ExitPart { end, kind, start }));
+}
@@ -0,0 +1,583 @@
+// Copyright 2018 Fredrik Portström <https://portstrom.com>
+// This is free software distributed under the terms specified in
+// the file LICENSE at the top-level directory of this distribution.
+
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    rc::Rc,
+    time::{SystemTime, UNIX_EPOCH},
+};
+use Positioned;
+
+/// Source of the wiki text of the templates to expand, and of the other information about the wiki that expanding templates depends on.
+///
+/// Only `wiki_text` must be implemented. A `HashMap` from the full text of titles, such as `Template:Infobox`, to wiki text is a template provider that holds the templates in memory.
+pub trait TemplateProvider {
+    /// Returns the current time as the number of seconds since 1970-01-01 00:00:00 UTC, used by `#time` when no time is given.
+    ///
+    /// By default the time is read from the system clock. Implement this method to get the same output every time the same wiki text is expanded.
+    fn current_time(&mut self) -> i64 {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |duration| duration.as_secs() as i64)
+    }
+
+    /// Returns the text of the interface message with the given key in the given language, used by `int:`.
+    ///
+    /// The key has underscores for spaces and starts with a lowercase letter, such as `mainpage`. The message may contain parameters `$1`, `$2` and so on, which are replaced by the arguments. A message is looked up here only if there is no page for it in the MediaWiki namespace. By default there are no messages other than such pages.
+    fn message(&mut self, _key: &str, _language: &str) -> Option<String> {
+        None
+    }
+
+    /// Returns whether the page with the given title exists, used by `#ifexist`.
+    ///
+    /// By default a page exists if `wiki_text` returns its wiki text. Like in Mediawiki, this is an expensive function, limited by `ExpansionLimits::max_expensive_function_count`.
+    fn page_exists(&mut self, title: &::Title) -> bool {
+        self.wiki_text(title).is_some()
+    }
+
+    /// Returns the wiki text of the page with the given title, or `None` if there is no such page.
+    ///
+    /// The title is normalized, so all the ways a template can be written give the same title. Templates are in the template namespace unless the name in the wiki text has another namespace, or starts with `:` for the main namespace. The wiki text of a template is asked for only once for each expansion, no matter how many times the template is used.
+    fn wiki_text(&mut self, title: &::Title) -> Option<String>;
+}
+
+impl TemplateProvider for HashMap<String, String> {
+    fn wiki_text(&mut self, title: &::Title) -> Option<String> {
+        self.get(&title.full_text()).cloned()
+    }
+}
+
+/// Limits on the expansion of templates, so that a template that transcludes itself or produces an enormous amount of text doesn't exhaust the resources.
+///
+/// The default limits are the same as the default limits of Mediawiki.
+#[derive(Clone, Debug)]
+pub struct ExpansionLimits {
+    /// The maximum number of templates being expanded inside each other. Further templates are replaced by an error message.
+    pub max_depth: usize,
+
+    /// The maximum number of calls to expensive parser functions, such as `#ifexist`. Further calls are replaced by an error message.
+    pub max_expensive_function_count: usize,
+
+    /// The maximum total size in bytes of the text produced by templates, counting the text of nested templates once for each level. Templates that would exceed the size are replaced by a link to the template. The same size limits the total size of the arguments substituted for parameters, which are left out when exceeding the size.
+    pub max_include_size: usize,
+
+    /// The maximum total number of comments, parameters, templates and transclusion tags expanded, counting the nodes of a template each time it's used. When exceeded, expansion stops and the remaining nodes are replaced by an error message. This bounds the work done for templates that use other templates many times, which otherwise grows exponentially with the depth.
+    pub max_node_count: usize,
+}
+
+impl Default for ExpansionLimits {
+    fn default() -> Self {
+        ExpansionLimits {
+            max_depth: 100,
+            max_expensive_function_count: 100,
+            max_include_size: 2 * 1024 * 1024,
+            max_node_count: 1_000_000,
+        }
+    }
+}
+
+/// Information about the page whose wiki text is expanded, which variables such as `{{PAGENAME}}` and `{{CURRENTYEAR}}` refer to.
+#[derive(Clone, Debug)]
+pub struct PageContext {
+    /// The language code of the content of the wiki, such as `en`, used by `{{CONTENTLANGUAGE}}`, `formatnum:` and `int:`.
+    pub content_language: String,
+
+    /// The time the revision of the page was saved as the number of seconds since 1970-01-01 00:00:00 UTC, used by `{{REVISIONTIMESTAMP}}` and the like. When `None`, the current time given by the template provider is used, as when previewing an edit.
+    pub revision_timestamp: Option<i64>,
+
+    /// The name of the wiki, used by `{{SITENAME}}`.
+    pub site_name: String,
+
+    /// The title of the page, which includes its namespace.
+    pub title: ::Title,
+}
+
+impl Default for PageContext {
+    fn default() -> Self {
+        PageContext {
+            content_language: "en".to_string(),
+            revision_timestamp: None,
+            site_name: "MediaWiki".to_string(),
+            title: ::Title {
+                canonical_namespace_name: String::new(),
+                fragment: None,
+                interwiki: None,
+                namespace: 0,
+                namespace_name: String::new(),
+                page_name: "Main Page".to_string(),
+                subpages: false,
+            },
+        }
+    }
+}
+
+pub struct Argument<'a> {
+    end: usize,
+    frame: &'a Frame<'a>,
+    nodes: &'a [::Node<'a>],
+    start: usize,
+    trim: bool,
+    value: RefCell<Option<String>>,
+    wiki_text: &'a str,
+}
+
+pub struct Expander<'a, P: TemplateProvider + ?Sized + 'a> {
+    pub argument_size: usize,
+    pub configuration: &'a ::Configuration,
+    pub context: &'a PageContext,
+    pub expensive_function_count: usize,
+    pub include_size: usize,
+    pub limits: &'a ExpansionLimits,
+    pub node_count: usize,
+    pub provider: &'a mut P,
+
+    /// The parsed wiki text of the templates used so far, or `None` for templates that don't exist.
+    pub templates: HashMap<String, Option<Rc<::Document>>>,
+}
+
+/// Template being expanded, with its arguments, each one expanded when first used.
+#[derive(Default)]
+pub struct Frame<'a> {
+    pub arguments: HashMap<String, Argument<'a>>,
+    pub depth: usize,
+    pub parent: Option<&'a Frame<'a>>,
+    pub title: String,
+}
+
+impl ::Configuration {
+    /// Expands the templates in wiki text of the page described by the given context, getting the wiki text of the templates from the given provider.
+    ///
+    /// Parameters such as `{{{1|default}}}` in a template are replaced by the arguments of the template call, and parameters in the given wiki text are replaced by their default values. Comments are removed the same way as by Mediawiki. The parser functions `#expr`, `#if`, `#ifeq`, `#iferror`, `#ifexist`, `#ifexpr`, `#switch`, `#time` and `#titleparts` of the ParserFunctions extension are evaluated, with the current time and the existence of pages given by the provider. So are the variables for the page name, namespace, site, language and time, such as `{{PAGENAME}}` and `{{CURRENTYEAR}}`, and the functions `anchorencode`, `formatnum`, `int`, `lc`, `lcfirst`, `padleft`, `padright`, `uc`, `ucfirst`, `urlencode` and `#tag` of Mediawiki, with the messages for `int` given by the provider. A template that transcludes itself, directly or through other templates, is replaced by an error message, as is a template nested deeper than the limit. A template that doesn't exist is replaced by a link to it.
+    ///
+    /// Templates are only found where the parser finds them, so templates in places where the parser doesn't parse templates, such as inside HTML attributes, are not expanded.
+    #[must_use]
+    pub fn expand_templates<P: TemplateProvider + ?Sized>(
+        &self,
+        wiki_text: &str,
+        context: &PageContext,
+        provider: &mut P,
+        limits: &ExpansionLimits,
+    ) -> String {
+        let mut expander = Expander {
+            argument_size: 0,
+            configuration: self,
+            context,
+            expensive_function_count: 0,
+            include_size: 0,
+            limits,
+            node_count: 0,
+            provider,
+            templates: HashMap::new(),
+        };
+        let output = self.parse(wiki_text);
+        let mut text = String::new();
+        expander.expand_nodes(
+            &Frame::default(),
+            wiki_text,
+            &output.nodes,
+            0,
+            wiki_text.len(),
+            &mut text,
+        );
+        text
+    }
+
+    /// Expands the templates in wiki text and parses the result, which gives the structure of pages whose tables and lists are assembled by templates.
+    ///
+    /// See `expand_templates`. The positions in the nodes are positions in the expanded wiki text, which is part of the returned document.
+    #[must_use]
+    pub fn parse_expanded<P: TemplateProvider + ?Sized>(
+        &self,
+        wiki_text: &str,
+        context: &PageContext,
+        provider: &mut P,
+        limits: &ExpansionLimits,
+    ) -> ::Document {
+        self.parse_document(self.expand_templates(wiki_text, context, provider, limits))
+    }
+}
+
+impl<'a, P: TemplateProvider + ?Sized> Expander<'a, P> {
+    pub fn expand_nodes(
+        &mut self,
+        frame: &Frame,
+        wiki_text: &str,
+        nodes: &[::Node],
+        start: usize,
+        end: usize,
+        output: &mut String,
+    ) {
+        let mut found = vec![];
+        for node in nodes {
+            find_expandable(node, &mut found);
+        }
+        self.expand_found(frame, wiki_text, found, start, end, output);
+    }
+
+    fn expand_found(
+        &mut self,
+        frame: &Frame,
+        wiki_text: &str,
+        mut found: Vec<&::Node>,
+        start: usize,
+        end: usize,
+        output: &mut String,
+    ) {
+        found.sort_by_key(|node| node.start());
+        self.node_count += found.len();
+        if !found.is_empty() && self.node_count > self.limits.max_node_count {
+            *output += "<span class=\"error\">Node-count limit exceeded</span>";
+            return;
+        }
+        let mut position = start;
+        for node in found {
+            let node_start = node.start();
+            if node_start < position {
+                continue;
+            }
+            let node_end = node_end(node, wiki_text);
+            match node {
+                ::Node::Comment { .. } => {
+                    // A comment alone on a line is removed together with the line.
+                    let line_start = position
+                        + wiki_text[position..node_start]
+                            .trim_end_matches([' ', '\t'])
+                            .len();
+                    let line_end = node_end
+                        + (wiki_text[node_end..].len()
+                            - wiki_text[node_end..].trim_start_matches([' ', '\t']).len());
+                    if line_start > 0
+                        && wiki_text.as_bytes()[line_start - 1] == b'\n'
+                        && wiki_text.as_bytes().get(line_end) == Some(&b'\n')
+                    {
+                        *output += &wiki_text[position..line_start];
+                        position = line_end + 1;
+                        continue;
+                    }
+                    *output += &wiki_text[position..node_start];
+                }
+                ::Node::Parameter { default, name, .. } => {
+                    *output += &wiki_text[position..node_start];
+                    let name_text = self.expand_sequence(frame, wiki_text, name);
+                    if let Some(argument) = frame.arguments.get(name_text.trim()) {
+                        let value = self.argument_value(argument);
+                        if self.argument_size + value.len() > self.limits.max_include_size {
+                            *output +=
+                                "<!-- WARNING: argument omitted, expansion size too large -->";
+                        } else {
+                            self.argument_size += value.len();
+                            *output += &value;
+                        }
+                    } else if let Some(default) = default {
+                        *output += &self.expand_sequence(frame, wiki_text, default);
+                    } else {
+                        *output += "{{{";
+                        self.expand_nodes(
+                            frame,
+                            wiki_text,
+                            name,
+                            node_start + 3,
+                            node_end - 3,
+                            output,
+                        );
+                        *output += "}}}";
+                    }
+                }
+                ::Node::Template {
+                    name, parameters, ..
+                } => {
+                    *output += &wiki_text[position..node_start];
+                    let text = self.expand_template(frame, wiki_text, node, name, parameters);
+                    // Like in Mediawiki, a template that starts with a table or a list starts on a new line.
+                    if node_start > 0
+                        && wiki_text.as_bytes()[node_start - 1] != b'\n'
+                        && (text.starts_with("{|") || text.starts_with(['#', '*', ':', ';']))
+                    {
+                        output.push('\n');
+                    }
+                    *output += &text;
+                }
+                // The tags for transclusion and the text they leave out are removed.
+                _ => *output += &wiki_text[position..node_start],
+            }
+            position = node_end;
+        }
+        *output += &wiki_text[position..end];
+    }
+
+    /// Expands a parameter of a template call as a whole, including its name if any, as an argument of a parser function.
+    pub fn expand_parameter(
+        &mut self,
+        frame: &Frame,
+        wiki_text: &str,
+        parameter: &::Parameter,
+    ) -> String {
+        let mut found = vec![];
+        for node in parameter.name.iter().flatten().chain(&parameter.value) {
+            find_expandable(node, &mut found);
+        }
+        let mut output = String::new();
+        if parameter.end > parameter.start {
+            self.expand_found(
+                frame,
+                wiki_text,
+                found,
+                parameter.start,
+                parameter.end,
+                &mut output,
+            );
+        }
+        output
+    }
+
+    pub fn expand_sequence(&mut self, frame: &Frame, wiki_text: &str, nodes: &[::Node]) -> String {
+        let mut output = String::new();
+        if let (Some(first), Some(last)) = (nodes.first(), nodes.last()) {
+            let end = node_end(last, wiki_text);
+            self.expand_nodes(frame, wiki_text, nodes, first.start(), end, &mut output);
+        }
+        output
+    }
+
+    fn expand_template(
+        &mut self,
+        frame: &Frame,
+        wiki_text: &str,
+        node: &::Node,
+        name: &[::Node],
+        parameters: &[::Parameter],
+    ) -> String {
+        let name_text = self.expand_sequence(frame, wiki_text, name);
+        let name_text = name_text.trim();
+        if let Some(position) = name_text.find(':') {
+            let function_name = &name_text[..position];
+            let first_argument = name_text[position + 1..].trim();
+            if let Some(text) =
+                self.call_core_function(frame, wiki_text, function_name, first_argument, parameters)
+            {
+                return text;
+            }
+            if let Some(text) = self.call_parser_function(
+                frame,
+                wiki_text,
+                function_name,
+                first_argument,
+                parameters,
+            ) {
+                return text;
+            }
+        } else if let Some(text) = self.variable(name_text) {
+            return text;
+        }
+        let title = match self.configuration.parse_title(name_text, 10) {
+            Ok(title) => title,
+            Err(_) => {
+                let mut found = vec![];
+                for node in name {
+                    find_expandable(node, &mut found);
+                }
+                for parameter in parameters {
+                    for node in parameter.name.iter().flatten().chain(&parameter.value) {
+                        find_expandable(node, &mut found);
+                    }
+                }
+                let mut output = "{{".to_string();
+                let end = node_end(node, wiki_text);
+                self.expand_found(
+                    frame,
+                    wiki_text,
+                    found,
+                    node.start() + 2,
+                    end - 2,
+                    &mut output,
+                );
+                output += "}}";
+                return output;
+            }
+        };
+        if title.interwiki.is_some() {
+            return "[Interwiki transcluding is disabled]".to_string();
+        }
+        let full_text = title.full_text();
+        if frame.depth >= self.limits.max_depth {
+            return self.depth_limit_error();
+        }
+        // Like in Mediawiki, a template is in a loop only if it's being expanded from itself, not if it's used in an argument to itself.
+        let mut ancestor = Some(frame);
+        while let Some(ancestor_frame) = ancestor {
+            if ancestor_frame.title == full_text {
+                return format!(
+                    "<span class=\"error\">Template loop detected: [[{}]]</span>",
+                    full_text
+                );
+            }
+            ancestor = ancestor_frame.parent;
+        }
+        let template = match self.template(&title, &full_text) {
+            None => return format!("[[:{}]]", full_text),
+            Some(template) => template,
+        };
+        let mut arguments = HashMap::new();
+        let mut position = 0;
+        for parameter in parameters {
+            let (name, argument) = match &parameter.name {
+                None => {
+                    position += 1;
+                    // The parser leaves out the whitespace around the value, which is part of a positional argument.
+                    (
+                        position.to_string(),
+                        Argument {
+                            end: ::state::skip_whitespace_forwards(wiki_text, parameter.end),
+                            frame,
+                            nodes: &parameter.value,
+                            start: ::state::skip_whitespace_backwards(wiki_text, parameter.start),
+                            trim: false,
+                            value: RefCell::new(None),
+                            wiki_text,
+                        },
+                    )
+                }
+                Some(name) => (
+                    self.expand_sequence(frame, wiki_text, name)
+                        .trim()
+                        .to_string(),
+                    match (parameter.value.first(), parameter.value.last()) {
+                        (Some(first), Some(last)) => Argument {
+                            end: node_end(last, wiki_text),
+                            frame,
+                            nodes: &parameter.value,
+                            start: first.start(),
+                            trim: true,
+                            value: RefCell::new(None),
+                            wiki_text,
+                        },
+                        _ => Argument {
+                            end: 0,
+                            frame,
+                            nodes: &[],
+                            start: 0,
+                            trim: true,
+                            value: RefCell::new(None),
+                            wiki_text,
+                        },
+                    },
+                ),
+            };
+            arguments.insert(name, argument);
+        }
+        let mut text = String::new();
+        self.expand_nodes(
+            &Frame {
+                arguments,
+                depth: frame.depth + 1,
+                parent: Some(frame),
+                title: full_text.clone(),
+            },
+            &template.wiki_text,
+            &template.nodes,
+            0,
+            template.wiki_text.len(),
+            &mut text,
+        );
+        if self.include_size + text.len() > self.limits.max_include_size {
+            return format!(
+                "[[:{}]]<!-- WARNING: template omitted, post-expand include size too large -->",
+                full_text
+            );
+        }
+        self.include_size += text.len();
+        text
+    }
+
+    /// Counts a call to an expensive parser function. Returns an error message if the number of calls exceeds the limit.
+    pub fn count_expensive_function(&mut self) -> Result<(), String> {
+        self.expensive_function_count += 1;
+        if self.expensive_function_count > self.limits.max_expensive_function_count {
+            Err(format!(
+                "<span class=\"error\">Expensive parser function count limit exceeded ({})</span>",
+                self.limits.max_expensive_function_count
+            ))
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Returns the error message for exceeding the maximum depth.
+    pub fn depth_limit_error(&self) -> String {
+        format!(
+            "<span class=\"error\">Template recursion depth limit exceeded ({})</span>",
+            self.limits.max_depth
+        )
+    }
+
+    /// Gets the parsed wiki text of a template from the provider the first time it's used, and from the templates used so far after that.
+    fn template(&mut self, title: &::Title, full_text: &str) -> Option<Rc<::Document>> {
+        if let Some(template) = self.templates.get(full_text) {
+            return template.clone();
+        }
+        let template = self.provider.wiki_text(title).map(|wiki_text| {
+            let ::Output { nodes, warnings } = self
+                .configuration
+                .parse_with_mode(&wiki_text, ::TransclusionMode::Transclude)
+                .into_owned();
+            Rc::new(::Document {
+                nodes,
+                warnings,
+                wiki_text,
+            })
+        });
+        self.templates.insert(full_text.to_string(), template.clone());
+        template
+    }
+
+    fn argument_value(&mut self, argument: &Argument) -> String {
+        if let Some(value) = &*argument.value.borrow() {
+            return value.clone();
+        }
+        let mut value = String::new();
+        self.expand_nodes(
+            argument.frame,
+            argument.wiki_text,
+            argument.nodes,
+            argument.start,
+            argument.end,
+            &mut value,
+        );
+        if argument.trim {
+            value = value.trim().to_string();
+        }
+        *argument.value.borrow_mut() = Some(value.clone());
+        value
+    }
+}
+
+/// Finds the nodes that are replaced when expanding templates, not including the nodes inside them.
+fn find_expandable<'a, 'b>(node: &'a ::Node<'b>, found: &mut Vec<&'a ::Node<'b>>) {
+    match node {
+        ::Node::Comment { .. } | ::Node::Parameter { .. } | ::Node::Template { .. } => {
+            found.push(node)
+        }
+        ::Node::EndTag { name, .. } | ::Node::StartTag { name, .. } | ::Node::Tag { name, .. }
+            if ::transclusion::is_transclusion_tag(name) =>
+        {
+            found.push(node)
+        }
+        ::Node::Tag { name, .. } if name == "nowiki" || name == "pre" => {}
+        _ => {
+            for child in node.children() {
+                find_expandable(child, found);
+            }
+        }
+    }
+}
+
+/// The byte position in the wiki text where the node ends. Unlike other nodes, the end of a parameter node doesn't include the closing `}}}`.
+fn node_end(node: &::Node, wiki_text: &str) -> usize {
+    let end = node.end();
+    match node {
+        ::Node::Parameter { .. } if wiki_text[end..].starts_with("}}}") => end + 3,
+        _ => end,
+    }
+}
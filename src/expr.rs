@@ -0,0 +1,787 @@
+// Copyright 2018 Fredrik Portström <https://portstrom.com>
+// This is free software distributed under the terms specified in
+// the file LICENSE at the top-level directory of this distribution.
+
+use std::convert::TryFrom;
+use std::fmt;
+
+/// The maximum number of operands or operators waiting to be evaluated, the same as in Mediawiki.
+const MAX_STACK_SIZE: usize = 100;
+
+/// Reason an expression could not be evaluated by `#expr`.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub enum ExprError {
+    /// Division or modulo by zero.
+    DivisionByZero,
+
+    /// The argument of `asin` or `acos` is less than -1 or greater than 1.
+    InvalidArgument {
+        /// The name of the operator, `asin` or `acos`.
+        operator: &'static str,
+    },
+
+    /// The argument of `ln` is not positive.
+    InvalidArgumentLn,
+
+    /// An operator doesn't have as many operands as it takes.
+    MissingOperand {
+        /// The name of the operator, such as `+` or `round`.
+        operator: &'static str,
+    },
+
+    /// The result of `sqrt` is not a number.
+    NotANumber {
+        /// The name of the operator, `sqrt`.
+        operator: &'static str,
+    },
+
+    /// The expression is nested too deeply.
+    StackExhausted,
+
+    /// A `(` is not followed by a matching `)`.
+    UnclosedBracket,
+
+    /// A `)` is not preceded by a matching `(`.
+    UnexpectedClosingBracket,
+
+    /// A number where an operator is expected.
+    UnexpectedNumber,
+
+    /// An operator where a number is expected.
+    UnexpectedOperator {
+        /// The operator as it appears in the expression, such as `*` or `mod`.
+        operator: &'static str,
+    },
+
+    /// A character that is not part of any operator.
+    UnrecognizedPunctuation {
+        /// The character.
+        character: char,
+    },
+
+    /// A word that is not the name of any operator.
+    UnrecognizedWord {
+        /// The word, converted to lowercase.
+        word: String,
+    },
+}
+
+impl fmt::Display for ExprError {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ExprError::DivisionByZero => formatter.write_str("Division by zero."),
+            ExprError::InvalidArgument { operator } => {
+                write!(formatter, "Invalid argument for {}: < -1 or > 1.", operator)
+            }
+            ExprError::InvalidArgumentLn => formatter.write_str("Invalid argument for ln: <= 0."),
+            ExprError::MissingOperand { operator } => write!(
+                formatter,
+                "Expression error: Missing operand for {}.",
+                operator
+            ),
+            ExprError::NotANumber { operator } => {
+                write!(formatter, "In {}: result is not a number.", operator)
+            }
+            ExprError::StackExhausted => formatter.write_str("Expression error: Stack exhausted."),
+            ExprError::UnclosedBracket => {
+                formatter.write_str("Expression error: Unclosed bracket.")
+            }
+            ExprError::UnexpectedClosingBracket => {
+                formatter.write_str("Expression error: Unexpected closing bracket.")
+            }
+            ExprError::UnexpectedNumber => {
+                formatter.write_str("Expression error: Unexpected number.")
+            }
+            ExprError::UnexpectedOperator { operator } => write!(
+                formatter,
+                "Expression error: Unexpected {} operator.",
+                operator
+            ),
+            ExprError::UnrecognizedPunctuation { character } => write!(
+                formatter,
+                "Expression error: Unrecognized punctuation character \"{}\".",
+                character
+            ),
+            ExprError::UnrecognizedWord { word } => write!(
+                formatter,
+                "Expression error: Unrecognized word \"{}\".",
+                word
+            ),
+        }
+    }
+}
+
+/// Number in an expression, which like in PHP is either an integer or a floating point number.
+#[derive(Clone, Copy)]
+enum Number {
+    Float(f64),
+    Integer(i64),
+}
+
+#[derive(Clone, Copy, Eq, PartialEq)]
+enum Operator {
+    Abs,
+    And,
+    Arccos,
+    Arcsin,
+    Arctan,
+    Ceil,
+    Cos,
+    Divide,
+    Equality,
+    Exp,
+    Exponent,
+    Floor,
+    Fmod,
+    Greater,
+    GreaterEqual,
+    Less,
+    LessEqual,
+    Ln,
+    Minus,
+    Mod,
+    Negative,
+    Not,
+    NotEqual,
+    Open,
+    Or,
+    Plus,
+    Positive,
+    Pow,
+    Round,
+    Sin,
+    Sqrt,
+    Tan,
+    Times,
+    Trunc,
+}
+
+impl Number {
+    fn to_float(self) -> f64 {
+        match self {
+            Number::Float(value) => value,
+            Number::Integer(value) => value as f64,
+        }
+    }
+
+    /// Converts the number to an integer the same way as `(int)` in PHP.
+    fn to_integer(self) -> i64 {
+        match self {
+            Number::Float(value) => float_to_integer(value),
+            Number::Integer(value) => value,
+        }
+    }
+
+    fn is_true(self) -> bool {
+        match self {
+            Number::Float(value) => value != 0.0,
+            Number::Integer(value) => value != 0,
+        }
+    }
+}
+
+impl fmt::Display for Number {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Number::Float(value) => formatter.write_str(&format_float(*value)),
+            Number::Integer(value) => write!(formatter, "{}", value),
+        }
+    }
+}
+
+impl Operator {
+    fn name(self) -> &'static str {
+        match self {
+            Operator::Abs => "abs",
+            Operator::And => "and",
+            Operator::Arccos => "acos",
+            Operator::Arcsin => "asin",
+            Operator::Arctan => "atan",
+            Operator::Ceil => "ceil",
+            Operator::Cos => "cos",
+            Operator::Divide => "/",
+            Operator::Equality => "=",
+            Operator::Exp => "exp",
+            Operator::Exponent => "e",
+            Operator::Floor => "floor",
+            Operator::Fmod => "fmod",
+            Operator::Greater => ">",
+            Operator::GreaterEqual => ">=",
+            Operator::Less => "<",
+            Operator::LessEqual => "<=",
+            Operator::Ln => "ln",
+            Operator::Minus | Operator::Negative => "-",
+            Operator::Mod => "mod",
+            Operator::Not => "not",
+            Operator::NotEqual => "<>",
+            Operator::Open => "(",
+            Operator::Or => "or",
+            Operator::Plus | Operator::Positive => "+",
+            Operator::Pow => "^",
+            Operator::Round => "round",
+            Operator::Sin => "sin",
+            Operator::Sqrt => "sqrt",
+            Operator::Tan => "tan",
+            Operator::Times => "*",
+            Operator::Trunc => "trunc",
+        }
+    }
+
+    fn precedence(self) -> i32 {
+        match self {
+            Operator::Exponent | Operator::Negative | Operator::Positive => 10,
+            Operator::Abs
+            | Operator::Arccos
+            | Operator::Arcsin
+            | Operator::Arctan
+            | Operator::Ceil
+            | Operator::Cos
+            | Operator::Exp
+            | Operator::Floor
+            | Operator::Ln
+            | Operator::Not
+            | Operator::Sin
+            | Operator::Sqrt
+            | Operator::Tan
+            | Operator::Trunc => 9,
+            Operator::Pow => 8,
+            Operator::Divide | Operator::Fmod | Operator::Mod | Operator::Times => 7,
+            Operator::Minus | Operator::Plus => 6,
+            Operator::Round => 5,
+            Operator::Equality
+            | Operator::Greater
+            | Operator::GreaterEqual
+            | Operator::Less
+            | Operator::LessEqual
+            | Operator::NotEqual => 4,
+            Operator::And => 3,
+            Operator::Or => 2,
+            Operator::Open => -1,
+        }
+    }
+
+    fn from_word(word: &str) -> Option<Self> {
+        Some(match word {
+            "abs" => Operator::Abs,
+            "acos" => Operator::Arccos,
+            "and" => Operator::And,
+            "asin" => Operator::Arcsin,
+            "atan" => Operator::Arctan,
+            "ceil" => Operator::Ceil,
+            "cos" => Operator::Cos,
+            "div" => Operator::Divide,
+            "e" => Operator::Exponent,
+            "exp" => Operator::Exp,
+            "floor" => Operator::Floor,
+            "fmod" => Operator::Fmod,
+            "ln" => Operator::Ln,
+            "mod" => Operator::Mod,
+            "not" => Operator::Not,
+            "or" => Operator::Or,
+            "round" => Operator::Round,
+            "sin" => Operator::Sin,
+            "sqrt" => Operator::Sqrt,
+            "tan" => Operator::Tan,
+            "trunc" => Operator::Trunc,
+            _ => return None,
+        })
+    }
+}
+
+/// Evaluates an expression the same way as `{{#expr:}}` in the ParserFunctions extension of Mediawiki, giving the result formatted the same way.
+///
+/// The operators are `+`, `-`, `*`, `/` and its synonym `div`, `mod`, `fmod`, `^`, `e` for scientific notation, `round`, the comparisons `=`, `<>`, `!=`, `<`, `>`, `<=` and `>=`, the logical operators `and`, `or` and `not`, and the functions `abs`, `trunc`, `floor`, `ceil`, `sqrt`, `exp`, `ln`, `sin`, `cos`, `tan`, `asin`, `acos` and `atan`. The words `e` and `pi` alone are the constants. Like in PHP, a result that is not an integer is formatted with 14 significant digits, and large and small numbers are formatted in scientific notation such as `1.0E+20`. An empty expression gives an empty result.
+pub fn evaluate_expression(expression: &str) -> Result<String, ExprError> {
+    let expression = expression
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&minus;", "-")
+        .replace('\u{2212}', "-");
+    let bytes = expression.as_bytes();
+    let mut operands = vec![];
+    let mut operators: Vec<Operator> = vec![];
+    let mut expecting_expression = true;
+    let mut position = 0;
+    while position < bytes.len() {
+        if operands.len() > MAX_STACK_SIZE || operators.len() > MAX_STACK_SIZE {
+            return Err(ExprError::StackExhausted);
+        }
+        let operator = match bytes[position] {
+            b'\t' | b'\n' | b'\r' | b' ' => {
+                position += 1;
+                continue;
+            }
+            b'.' | b'0'..=b'9' => {
+                if !expecting_expression {
+                    return Err(ExprError::UnexpectedNumber);
+                }
+                let length = bytes[position..]
+                    .iter()
+                    .take_while(|byte| matches!(byte, b'.' | b'0'..=b'9'))
+                    .count();
+                operands.push(Number::Float(parse_float(
+                    &expression[position..position + length],
+                )));
+                position += length;
+                expecting_expression = false;
+                continue;
+            }
+            b'A'..=b'Z' | b'a'..=b'z' => {
+                let length = bytes[position..]
+                    .iter()
+                    .take_while(|byte| byte.is_ascii_alphabetic())
+                    .count();
+                let word = expression[position..position + length].to_ascii_lowercase();
+                position += length;
+                let operator = match Operator::from_word(&word) {
+                    Some(operator) => operator,
+                    None if word == "pi" => {
+                        if !expecting_expression {
+                            return Err(ExprError::UnexpectedNumber);
+                        }
+                        operands.push(Number::Float(::std::f64::consts::PI));
+                        expecting_expression = false;
+                        continue;
+                    }
+                    None => return Err(ExprError::UnrecognizedWord { word }),
+                };
+                match operator {
+                    Operator::Exponent if expecting_expression => {
+                        operands.push(Number::Float(::std::f64::consts::E));
+                        expecting_expression = false;
+                        continue;
+                    }
+                    Operator::Abs
+                    | Operator::Arccos
+                    | Operator::Arcsin
+                    | Operator::Arctan
+                    | Operator::Ceil
+                    | Operator::Cos
+                    | Operator::Exp
+                    | Operator::Floor
+                    | Operator::Ln
+                    | Operator::Not
+                    | Operator::Sin
+                    | Operator::Sqrt
+                    | Operator::Tan
+                    | Operator::Trunc => {
+                        if !expecting_expression {
+                            return Err(ExprError::UnexpectedOperator {
+                                operator: operator.name(),
+                            });
+                        }
+                        operators.push(operator);
+                        continue;
+                    }
+                    _ => operator,
+                }
+            }
+            b'!' if bytes.get(position + 1) == Some(&b'=') => {
+                position += 2;
+                Operator::NotEqual
+            }
+            b'<' if bytes.get(position + 1) == Some(&b'=') => {
+                position += 2;
+                Operator::LessEqual
+            }
+            b'<' if bytes.get(position + 1) == Some(&b'>') => {
+                position += 2;
+                Operator::NotEqual
+            }
+            b'>' if bytes.get(position + 1) == Some(&b'=') => {
+                position += 2;
+                Operator::GreaterEqual
+            }
+            b'+' | b'-' => {
+                let negative = bytes[position] == b'-';
+                position += 1;
+                if expecting_expression {
+                    operators.push(if negative {
+                        Operator::Negative
+                    } else {
+                        Operator::Positive
+                    });
+                    continue;
+                }
+                if negative {
+                    Operator::Minus
+                } else {
+                    Operator::Plus
+                }
+            }
+            b'(' => {
+                if !expecting_expression {
+                    return Err(ExprError::UnexpectedOperator { operator: "(" });
+                }
+                operators.push(Operator::Open);
+                position += 1;
+                continue;
+            }
+            b')' => {
+                loop {
+                    match operators.pop() {
+                        None => return Err(ExprError::UnexpectedClosingBracket),
+                        Some(Operator::Open) => break,
+                        Some(operator) => operate(operator, &mut operands)?,
+                    }
+                }
+                expecting_expression = false;
+                position += 1;
+                continue;
+            }
+            byte => {
+                position += 1;
+                match byte {
+                    b'*' => Operator::Times,
+                    b'/' => Operator::Divide,
+                    b'<' => Operator::Less,
+                    b'=' => Operator::Equality,
+                    b'>' => Operator::Greater,
+                    b'^' => Operator::Pow,
+                    _ => {
+                        return Err(ExprError::UnrecognizedPunctuation {
+                            character: expression[position - 1..]
+                                .chars()
+                                .next()
+                                .unwrap_or('\u{FFFD}'),
+                        })
+                    }
+                }
+            }
+        };
+        // The operator is a binary operator.
+        if expecting_expression {
+            return Err(ExprError::UnexpectedOperator {
+                operator: match operator {
+                    Operator::NotEqual if bytes[position - 2] == b'!' => "!=",
+                    operator => operator.name(),
+                },
+            });
+        }
+        while let Some(&last) = operators.last() {
+            if operator.precedence() > last.precedence() {
+                break;
+            }
+            operate(last, &mut operands)?;
+            operators.pop();
+        }
+        operators.push(operator);
+        expecting_expression = true;
+    }
+    while let Some(operator) = operators.pop() {
+        if operator == Operator::Open {
+            return Err(ExprError::UnclosedBracket);
+        }
+        operate(operator, &mut operands)?;
+    }
+    Ok(operands
+        .iter()
+        .map(Number::to_string)
+        .collect::<Vec<_>>()
+        .join("<br />\n"))
+}
+
+/// Converts a floating point number to an integer the same way as `(int)` in PHP, wrapping around numbers that are out of range.
+fn float_to_integer(value: f64) -> i64 {
+    if !value.is_finite() {
+        return 0;
+    }
+    let two_pow_63 = -(i64::MIN as f64);
+    if (-two_pow_63..two_pow_63).contains(&value) {
+        return value as i64;
+    }
+    let two_pow_64 = 2.0 * two_pow_63;
+    let mut modulo = value % two_pow_64;
+    if modulo < 0.0 {
+        modulo += two_pow_64;
+    }
+    if modulo >= two_pow_63 {
+        modulo -= two_pow_64;
+    }
+    modulo as i64
+}
+
+/// Formats a floating point number the same way as PHP with the default precision of 14 significant digits.
+pub fn format_float(value: f64) -> String {
+    if value.is_nan() {
+        return "NAN".to_string();
+    }
+    if value.is_infinite() {
+        return if value < 0.0 { "-INF" } else { "INF" }.to_string();
+    }
+    let mut output = String::new();
+    if value.is_sign_negative() {
+        output.push('-');
+    }
+    if value == 0.0 {
+        output.push('0');
+        return output;
+    }
+    let scientific = format!("{:.13e}", value.abs());
+    let exponent_position = scientific.find('e').unwrap_or(scientific.len());
+    let digits = scientific[..exponent_position]
+        .replace('.', "")
+        .trim_end_matches('0')
+        .to_string();
+    let exponent: i32 = scientific[exponent_position + 1..].parse().unwrap_or(0);
+    // The position of the decimal point relative to the first digit.
+    let point = exponent + 1;
+    if !(-3..=14).contains(&point) {
+        output += &digits[..1];
+        output.push('.');
+        output += if digits.len() > 1 { &digits[1..] } else { "0" };
+        output += &format!(
+            "E{}{}",
+            if exponent < 0 { '-' } else { '+' },
+            exponent.abs()
+        );
+    } else if point <= 0 {
+        output += "0.";
+        for _ in point..0 {
+            output.push('0');
+        }
+        output += &digits;
+    } else {
+        let point = point as usize;
+        if digits.len() > point {
+            output += &digits[..point];
+            output.push('.');
+            output += &digits[point..];
+        } else {
+            output += &digits;
+            for _ in digits.len()..point {
+                output.push('0');
+            }
+        }
+    }
+    output
+}
+
+fn operate(operator: Operator, operands: &mut Vec<Number>) -> Result<(), ExprError> {
+    let missing_operand = ExprError::MissingOperand {
+        operator: operator.name(),
+    };
+    let right = operands.pop().ok_or(missing_operand.clone())?;
+    let result = match operator {
+        Operator::Abs => match right {
+            Number::Integer(value) if value != i64::MIN => Number::Integer(value.abs()),
+            _ => Number::Float(right.to_float().abs()),
+        },
+        Operator::Arccos | Operator::Arcsin => {
+            let value = right.to_float();
+            if !(-1.0..=1.0).contains(&value) {
+                return Err(ExprError::InvalidArgument {
+                    operator: operator.name(),
+                });
+            }
+            Number::Float(if operator == Operator::Arccos {
+                value.acos()
+            } else {
+                value.asin()
+            })
+        }
+        Operator::Arctan => Number::Float(right.to_float().atan()),
+        Operator::Ceil => Number::Float(right.to_float().ceil()),
+        Operator::Cos => Number::Float(right.to_float().cos()),
+        Operator::Exp => Number::Float(right.to_float().exp()),
+        Operator::Floor => Number::Float(right.to_float().floor()),
+        Operator::Ln => {
+            let value = right.to_float();
+            if value <= 0.0 {
+                return Err(ExprError::InvalidArgumentLn);
+            }
+            Number::Float(value.ln())
+        }
+        Operator::Negative => match right {
+            Number::Integer(value) if value != i64::MIN => Number::Integer(-value),
+            _ => Number::Float(-right.to_float()),
+        },
+        Operator::Not => Number::Integer(!right.is_true() as i64),
+        Operator::Positive => right,
+        Operator::Sin => Number::Float(right.to_float().sin()),
+        Operator::Sqrt => {
+            let value = right.to_float().sqrt();
+            if value.is_nan() {
+                return Err(ExprError::NotANumber {
+                    operator: operator.name(),
+                });
+            }
+            Number::Float(value)
+        }
+        Operator::Tan => Number::Float(right.to_float().tan()),
+        Operator::Trunc => Number::Integer(right.to_integer()),
+        _ => {
+            let left = operands.pop().ok_or(missing_operand)?;
+            binary_operation(operator, left, right)?
+        }
+    };
+    operands.push(result);
+    Ok(())
+}
+
+fn binary_operation(operator: Operator, left: Number, right: Number) -> Result<Number, ExprError> {
+    let compare = |result: bool| Number::Integer(result as i64);
+    let integers = match (left, right) {
+        (Number::Integer(left), Number::Integer(right)) => Some((left, right)),
+        _ => None,
+    };
+    let (left_float, right_float) = (left.to_float(), right.to_float());
+    Ok(match operator {
+        Operator::And => compare(left.is_true() && right.is_true()),
+        Operator::Divide => {
+            if right_float == 0.0 {
+                return Err(ExprError::DivisionByZero);
+            }
+            match integers {
+                Some((left, right)) if left.checked_rem(right) == Some(0) => {
+                    Number::Integer(left / right)
+                }
+                _ => Number::Float(left_float / right_float),
+            }
+        }
+        Operator::Equality => compare(match integers {
+            Some((left, right)) => left == right,
+            None => left_float == right_float,
+        }),
+        Operator::Exponent => match power(Number::Integer(10), right) {
+            Number::Integer(power) => match left {
+                Number::Integer(left) => match left.checked_mul(power) {
+                    Some(product) => Number::Integer(product),
+                    None => Number::Float(left as f64 * power as f64),
+                },
+                Number::Float(left) => Number::Float(left * power as f64),
+            },
+            Number::Float(power) => Number::Float(left_float * power),
+        },
+        Operator::Fmod => {
+            if right_float == 0.0 {
+                return Err(ExprError::DivisionByZero);
+            }
+            Number::Float(left_float % right_float)
+        }
+        Operator::Greater => compare(match integers {
+            Some((left, right)) => left > right,
+            None => left_float > right_float,
+        }),
+        Operator::GreaterEqual => compare(match integers {
+            Some((left, right)) => left >= right,
+            None => left_float >= right_float,
+        }),
+        Operator::Less => compare(match integers {
+            Some((left, right)) => left < right,
+            None => left_float < right_float,
+        }),
+        Operator::LessEqual => compare(match integers {
+            Some((left, right)) => left <= right,
+            None => left_float <= right_float,
+        }),
+        Operator::Minus => match integers.and_then(|(left, right)| left.checked_sub(right)) {
+            Some(difference) => Number::Integer(difference),
+            None => Number::Float(left_float - right_float),
+        },
+        Operator::Mod => {
+            let (left, right) = (left.to_integer(), right.to_integer());
+            if right == 0 {
+                return Err(ExprError::DivisionByZero);
+            }
+            // Like in PHP, the result for -1 is 0 even for the integer that has no positive counterpart.
+            Number::Integer(if right == -1 { 0 } else { left % right })
+        }
+        Operator::NotEqual => compare(match integers {
+            Some((left, right)) => left != right,
+            None => left_float != right_float,
+        }),
+        Operator::Or => compare(left.is_true() || right.is_true()),
+        Operator::Plus => match integers.and_then(|(left, right)| left.checked_add(right)) {
+            Some(sum) => Number::Integer(sum),
+            None => Number::Float(left_float + right_float),
+        },
+        Operator::Pow => power(left, right),
+        Operator::Round => Number::Float(round(left_float, right.to_integer())),
+        Operator::Times => match integers.and_then(|(left, right)| left.checked_mul(right)) {
+            Some(product) => Number::Integer(product),
+            None => Number::Float(left_float * right_float),
+        },
+        _ => unreachable!(),
+    })
+}
+
+/// Converts the leading number of a text consisting of digits and decimal points to a floating point number, the same way as `(float)` in PHP.
+fn parse_float(text: &str) -> f64 {
+    let end = match text.find('.') {
+        None => text.len(),
+        Some(point) => text[point + 1..]
+            .find('.')
+            .map_or(text.len(), |second_point| point + 1 + second_point),
+    };
+    text[..end].parse().unwrap_or(0.0)
+}
+
+/// Raises a number to a power the same way as `pow` in PHP, giving an integer if both numbers are integers and the exponent is not negative, unless the result is out of range.
+fn power(base: Number, exponent: Number) -> Number {
+    if let (Number::Integer(base), Number::Integer(exponent)) = (base, exponent) {
+        if exponent >= 0 {
+            if let Some(result) = u32::try_from(exponent)
+                .ok()
+                .and_then(|exponent| base.checked_pow(exponent))
+            {
+                return Number::Integer(result);
+            }
+        }
+    }
+    Number::Float(base.to_float().powf(exponent.to_float()))
+}
+
+fn power_of_ten(power: i64) -> f64 {
+    10f64.powi(power.clamp(-400, 400) as i32)
+}
+
+/// Rounds a number to the given number of decimal places the same way as `round` in PHP, which rounds halfway cases away from zero after first rounding the number to the precision guaranteed by floating point numbers.
+fn round(value: f64, places: i64) -> f64 {
+    if !value.is_finite() || value == 0.0 {
+        return value;
+    }
+    let round_helper = |value: f64| {
+        if value >= 0.0 {
+            (value + 0.5).floor()
+        } else {
+            (value - 0.5).ceil()
+        }
+    };
+    let places = places.max(i32::MIN as i64 + 1);
+    let precision_places = 14 - value.abs().log10().floor() as i64;
+    let factor = power_of_ten(places.abs());
+    let mut rounded = if precision_places > places && precision_places - 15 < places {
+        let use_precision = precision_places.max(-60);
+        let pre_rounded = round_helper(if use_precision >= 0 {
+            value * power_of_ten(use_precision)
+        } else {
+            value / power_of_ten(-use_precision)
+        });
+        let use_precision = (places - use_precision).max(-60);
+        pre_rounded / power_of_ten(use_precision.abs())
+    } else {
+        let scaled = if places >= 0 {
+            value * factor
+        } else {
+            value / factor
+        };
+        if scaled.abs() >= 1e15 {
+            return value;
+        }
+        scaled
+    };
+    rounded = round_helper(rounded);
+    if places.abs() < 23 {
+        if places > 0 {
+            rounded / factor
+        } else {
+            rounded * factor
+        }
+    } else {
+        match format!("{:.6}e{}", rounded, -places).parse::<f64>() {
+            Ok(result) if result.is_finite() => result,
+            _ => value,
+        }
+    }
+}
@@ -12,7 +12,7 @@ pub fn parse_external_link_end<'a>(
     state.scan_position += 1;
     state.flushed_position = state.scan_position;
     let nodes = ::std::mem::replace(&mut state.nodes, nodes);
-    state.nodes.push(::Node::ExternalLink {
+    state.push_node(::Node::ExternalLink {
         end: state.scan_position,
         nodes,
         start: start_position,
@@ -21,7 +21,7 @@ pub fn parse_external_link_end<'a>(
 
 pub fn parse_external_link_end_of_line(state: &mut ::State) {
     let end = state.scan_position;
-    let open_node = state.stack.pop().unwrap();
+    let open_node = state.pop_open_node().unwrap();
     state.warnings.push(::Warning {
         end,
         message: ::WarningMessage::InvalidLinkSyntax,
@@ -45,3 +45,201 @@ pub fn parse_external_link_start(state: &mut ::State, configuration: &::Configur
         }
     }
 }
+
+/// Parses a free external link if a protocol starts at the scan position, which is at a letter.
+pub fn parse_free_external_link(state: &mut ::State, configuration: &::Configuration) {
+    let start_position = state.scan_position;
+    let bytes = state.wiki_text.as_bytes();
+    let word_length = bytes[start_position..]
+        .iter()
+        .take_while(|byte| byte.is_ascii_alphanumeric())
+        .count();
+    // Like in Mediawiki, the protocol must be at the beginning of a word, and is never recognized in the text of a link. This and the character following the letters and digits of the word are checked first, so that the protocols are looked up only where one can start.
+    let protocol_length = if start_position > 0
+        && matches!(
+            bytes[start_position - 1],
+            b'0'..=b'9' | b'A'..=b'Z' | b'_' | b'a'..=b'z'
+        )
+        || state.open_link_count > 0
+        || configuration
+            .protocol_word_ends
+            .as_ref()
+            .is_some_and(
+                |protocol_word_ends| match bytes.get(start_position + word_length) {
+                    None => true,
+                    Some(byte) => !protocol_word_ends.contains(byte),
+                },
+            ) {
+        None
+    } else {
+        configuration
+            .protocols
+            .find(&state.wiki_text[start_position..])
+            .ok()
+            .map(|(protocol_length, _)| protocol_length)
+    };
+    let protocol_length = match protocol_length {
+        None => {
+            // No protocol starts inside the rest of the word either.
+            state.scan_position += word_length;
+            return;
+        }
+        Some(protocol_length) => protocol_length,
+    };
+    state.scan_position += 1;
+    let address_start_position = start_position + protocol_length;
+    let end_position = find_url_end(state, address_start_position);
+    let end_position =
+        start_position + trim_url_end(&state.wiki_text[start_position..end_position]);
+    // Like in Mediawiki, there is no link when nothing follows the protocol.
+    if end_position <= address_start_position {
+        return;
+    }
+    state.flush(start_position);
+    state.flushed_position = end_position;
+    state.scan_position = end_position;
+    state.push_node(::Node::FreeExternalLink {
+        end: end_position,
+        start: start_position,
+        url: ::Cow::Borrowed(&state.wiki_text[start_position..end_position]),
+    });
+}
+
+/// Whether the text preceding `;` ends with the name or number of a character entity, so that the `;` is not trailing punctuation.
+fn ends_with_entity(text: &str) -> bool {
+    let bytes = text.as_bytes();
+    let count = |predicate: fn(&u8) -> bool| {
+        bytes
+            .iter()
+            .rev()
+            .take_while(|byte| predicate(byte))
+            .count()
+    };
+    let letters = count(u8::is_ascii_alphabetic);
+    let hexadecimal_digits = count(u8::is_ascii_hexdigit);
+    let digits = count(u8::is_ascii_digit);
+    letters > 0 && bytes[..bytes.len() - letters].ends_with(b"&")
+        || hexadecimal_digits > 0 && {
+            let rest = &bytes[..bytes.len() - hexadecimal_digits];
+            rest.ends_with(b"&#x") || rest.ends_with(b"&#X")
+        }
+        || digits > 0 && bytes[..bytes.len() - digits].ends_with(b"&#")
+}
+
+/// Finds the end of the characters that are allowed in a free external link, starting from the position following the protocol.
+fn find_url_end(state: &::State, mut position: usize) -> usize {
+    let bytes = state.wiki_text.as_bytes();
+    if bytes.get(position) == Some(&b'[') {
+        // The host can be an IPv6 address in brackets.
+        let length = bytes[position + 1..]
+            .iter()
+            .take_while(|byte| byte.is_ascii_hexdigit() || **byte == b':' || **byte == b'.')
+            .count();
+        if length > 0 && bytes.get(position + length + 1) == Some(&b']') {
+            position += length + 2;
+        }
+    }
+    for (offset, character) in state.wiki_text[position..].char_indices() {
+        let character_position = position + offset;
+        if match character {
+            '\0'..=' '
+            | '"'
+            | '<'
+            | '>'
+            | '['
+            | ']'
+            | '\u{7F}'
+            | '\u{A0}'
+            | '\u{1680}'
+            | '\u{2000}'..='\u{200A}'
+            | '\u{202F}'
+            | '\u{205F}'
+            | '\u{3000}'
+            | '\u{FFFD}' => true,
+            // Formatting and templates are parsed before free external links in Mediawiki, so the link ends where they start.
+            '!' | '\'' | '{' | '}' => bytes.get(character_position + 1) == Some(&(character as u8)),
+            '=' => match state.stack.last() {
+                Some(::OpenNode {
+                    type_: ::OpenNodeType::Heading { .. },
+                    ..
+                }) => {
+                    let rest = state.wiki_text[character_position..].trim_start_matches('=');
+                    let rest = rest.trim_start_matches(['\t', ' ']);
+                    rest.is_empty() || rest.starts_with('\n')
+                }
+                Some(::OpenNode {
+                    type_:
+                        ::OpenNodeType::Template {
+                            name: Some(_),
+                            parameters,
+                        },
+                    ..
+                }) => parameters
+                    .last()
+                    .is_some_and(|parameter| parameter.name.is_none()),
+                _ => false,
+            },
+            '|' => state.stack.last().is_some_and(|open_node| {
+                matches!(
+                    open_node.type_,
+                    ::OpenNodeType::Parameter { default: None, .. }
+                        | ::OpenNodeType::Link { .. }
+                        | ::OpenNodeType::Table(..)
+                        | ::OpenNodeType::Template { .. }
+                )
+            }),
+            _ => false,
+        } {
+            return character_position;
+        }
+    }
+    state.wiki_text.len()
+}
+
+/// Whether the text following `&` starts with a character entity for `<`, `>` or non-breaking space, which ends a free external link in Mediawiki.
+fn is_terminating_entity(text: &str) -> bool {
+    let (digits, radix) = if let Some(digits) = text.strip_prefix("#x") {
+        (digits, 16)
+    } else if let Some(digits) = text.strip_prefix('#') {
+        (digits, 10)
+    } else {
+        return text.starts_with("gt;") || text.starts_with("lt;") || text.starts_with("nbsp;");
+    };
+    let length = digits
+        .bytes()
+        .take_while(|byte| (*byte as char).is_digit(radix))
+        .count();
+    digits[length..].starts_with(';')
+        && matches!(
+            u32::from_str_radix(&digits[..length], radix),
+            Ok(0x3C) | Ok(0x3E) | Ok(0xA0)
+        )
+}
+
+/// Returns the length of the URL of a free external link after leaving out a terminating character entity and trailing punctuation the same way as Mediawiki.
+fn trim_url_end(url: &str) -> usize {
+    let url = match url
+        .match_indices('&')
+        .find(|(position, _)| is_terminating_entity(&url[position + 1..]))
+    {
+        None => url,
+        Some((position, _)) => &url[..position],
+    };
+    // A closing parenthesis is trailing punctuation only when the URL has no opening parenthesis.
+    let has_parenthesis = url.contains('(');
+    let mut length = url.len()
+        - url
+            .bytes()
+            .rev()
+            .take_while(|byte| match byte {
+                b'!' | b',' | b'.' | b':' | b';' | b'?' => true,
+                b')' => !has_parenthesis,
+                _ => false,
+            })
+            .count();
+    // The `;` at the end of a character entity is kept.
+    if length < url.len() && url.as_bytes()[length] == b';' && ends_with_entity(&url[..length]) {
+        length += 1;
+    }
+    length
+}
@@ -10,7 +10,7 @@ pub fn parse_heading_end(state: &mut ::State) {
             _ => break,
         }
     }
-    let open_node = state.stack.pop().unwrap();
+    let open_node = state.pop_open_node().unwrap();
     if state.get_byte(end_position - 1) != Some(b'=') || end_position < open_node.start + 3 {
         state.warnings.push(::Warning {
             end: end_position,
@@ -46,7 +46,7 @@ pub fn parse_heading_end(state: &mut ::State) {
             }
             Some(::Node::Text { end, start, value }) => {
                 *start = inner_start_position;
-                *value = &state.wiki_text[inner_start_position..*end];
+                *value = ::Cow::Borrowed(&state.wiki_text[inner_start_position..*end]);
                 false
             }
             Some(_) => true,
@@ -57,14 +57,14 @@ pub fn parse_heading_end(state: &mut ::State) {
                 ::Node::Text {
                     end,
                     start: inner_start_position,
-                    value: &state.wiki_text[inner_start_position..end],
+                    value: ::Cow::Borrowed(&state.wiki_text[inner_start_position..end]),
                 },
             );
         }
     }
     state.flush(position);
     let nodes = ::std::mem::replace(&mut state.nodes, open_node.nodes);
-    state.nodes.push(::Node::Heading {
+    state.push_node(::Node::Heading {
         end: end_position,
         level: end_level,
         nodes,
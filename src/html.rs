@@ -0,0 +1,746 @@
+// Copyright 2018 Fredrik Portström <https://portstrom.com>
+// This is free software distributed under the terms specified in
+// the file LICENSE at the top-level directory of this distribution.
+
+use std::collections::HashSet;
+
+/// Handler that resolves the parts of wiki text that can't be rendered to HTML from the wiki text alone.
+///
+/// Every method has a default implementation, so a handler only needs to implement the methods it wants to customize.
+pub trait HtmlHandler {
+    /// Renders an extension tag, writing the resulting HTML to `output`.
+    ///
+    /// By default the content of `nowiki` is rendered as text, the content of `pre` is rendered as preformatted text and all other extension tags are left out.
+    fn extension_tag(
+        &mut self,
+        name: &str,
+        attributes: &[::Attribute],
+        nodes: &[::Node],
+        output: &mut String,
+    ) {
+        match name {
+            "nowiki" => write_text_content(nodes, output),
+            "pre" => {
+                *output += "<pre";
+                write_attributes("pre", attributes, output);
+                *output += ">";
+                write_text_content(nodes, output);
+                *output += "</pre>";
+            }
+            _ => {}
+        }
+    }
+
+    /// Renders an image, writing the resulting HTML to `output`.
+    ///
+    /// The image options and caption are given as they appear in the wiki text after the file name. By default images are left out, because there is no way to know the URL of the image file without access to the file repository of the wiki.
+    fn image(&mut self, target: &str, text: &[::Node], output: &mut String) {
+        let _ = (target, text, output);
+    }
+
+    /// Returns the URL of the page on another wiki that an interwiki link with the given prefix and title refers to, or `None` to render the link as text.
+    ///
+    /// By default interwiki links are rendered as text, because there is no way to know the URLs of other wikis without access to the interwiki table of the wiki.
+    fn interwiki_url(&mut self, prefix: &str, target: &str) -> Option<String> {
+        let _ = (prefix, target);
+        None
+    }
+
+    /// Returns the URL of the page that a link or redirect with the given target refers to.
+    ///
+    /// By default the URL is a path relative to the root of the wiki in the form `/wiki/Page_name`, like in Wikipedia.
+    fn link_url(&mut self, target: &str) -> String {
+        let target = target.trim_start_matches(':');
+        let (title, fragment) = match target.find('#') {
+            None => (target, None),
+            Some(position) => (&target[..position], Some(&target[position + 1..])),
+        };
+        let mut url = String::new();
+        let title = title.trim();
+        if !title.is_empty() {
+            url += "/wiki/";
+            write_url_encoded(title, &mut url);
+        }
+        if let Some(fragment) = fragment {
+            url.push('#');
+            write_url_encoded(fragment.trim(), &mut url);
+        }
+        url
+    }
+
+    /// Renders a template, writing the resulting HTML to `output`.
+    ///
+    /// By default templates are left out, because there is no way to know what they expand to without access to the templates of the wiki.
+    fn template(&mut self, name: &[::Node], parameters: &[::Parameter], output: &mut String) {
+        let _ = (name, parameters, output);
+    }
+}
+
+/// HTML handler that uses the default implementation of every method.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct DefaultHtmlHandler;
+
+impl HtmlHandler for DefaultHtmlHandler {}
+
+#[derive(Clone, Copy, Eq, PartialEq)]
+enum Format {
+    Bold,
+    Italic,
+}
+
+struct Renderer<'a, H: HtmlHandler + ?Sized + 'a> {
+    block_tag_on_line: bool,
+    external_link_count: usize,
+    format_stack: Vec<Format>,
+    handler: &'a mut H,
+    heading_ids: HashSet<String>,
+    output: String,
+    paragraph_open: bool,
+}
+
+/// Renders parsed nodes to HTML in the style of the output of Mediawiki.
+///
+/// Headings get ids derived from their text, inline content is wrapped in paragraphs, and toggles of bold and italic text are balanced into properly nested `<b>` and `<i>` elements. HTML attributes are sanitized like by MediaWiki, keeping only the attributes that MediaWiki allows on each element and leaving out unsafe styles. Links, images, templates and extension tags are resolved by the given handler.
+///
+/// The handler may call this function recursively to render the content of an extension tag or the parameters of a template.
+#[must_use]
+pub fn render_html<H: HtmlHandler + ?Sized>(nodes: &[::Node], handler: &mut H) -> String {
+    let mut renderer = Renderer {
+        block_tag_on_line: false,
+        external_link_count: 0,
+        format_stack: vec![],
+        handler,
+        heading_ids: HashSet::new(),
+        output: String::new(),
+        paragraph_open: false,
+    };
+    renderer.write_nodes(nodes, true);
+    renderer.close_paragraph();
+    renderer.output
+}
+
+impl<'a, H: HtmlHandler + ?Sized> Renderer<'a, H> {
+    fn close_formats(&mut self) {
+        while let Some(format) = self.format_stack.pop() {
+            self.output += close_format_tag(format);
+        }
+    }
+
+    fn close_paragraph(&mut self) {
+        self.close_formats();
+        if self.paragraph_open {
+            self.output += "</p>\n";
+            self.paragraph_open = false;
+        }
+    }
+
+    fn open_paragraph(&mut self, wrap_paragraphs: bool) {
+        if wrap_paragraphs && !self.paragraph_open && !self.block_tag_on_line {
+            self.output += "<p>";
+            self.paragraph_open = true;
+        }
+    }
+
+    fn toggle_format(&mut self, format: Format) {
+        match self.format_stack.iter().position(|open| *open == format) {
+            None => {
+                self.output += open_format_tag(format);
+                self.format_stack.push(format);
+            }
+            Some(position) => {
+                let reopened = self.format_stack.split_off(position + 1);
+                for open in reopened.iter().rev() {
+                    self.output += close_format_tag(*open);
+                }
+                self.format_stack.pop();
+                self.output += close_format_tag(format);
+                for open in reopened {
+                    self.output += open_format_tag(open);
+                    self.format_stack.push(open);
+                }
+            }
+        }
+    }
+
+    fn write_block<F: FnOnce(&mut Self)>(&mut self, write: F) {
+        self.close_paragraph();
+        write(self);
+        self.block_tag_on_line = false;
+        self.output.push('\n');
+    }
+
+    fn write_heading_id(&mut self, nodes: &[::Node]) {
+        let mut text = String::new();
+        write_plain_text(nodes, &mut text);
+        let base_id = text.split_whitespace().collect::<Vec<_>>().join("_");
+        let mut id = base_id.clone();
+        let mut number = 1;
+        while self.heading_ids.contains(&id) {
+            number += 1;
+            id = format!("{}_{}", base_id, number);
+        }
+        write_escaped(&id, &mut self.output);
+        self.heading_ids.insert(id);
+    }
+
+    fn write_inline_nodes(&mut self, nodes: &[::Node]) {
+        let paragraph_open = ::std::mem::replace(&mut self.paragraph_open, false);
+        let format_stack = ::std::mem::take(&mut self.format_stack);
+        for (index, node) in nodes.iter().enumerate() {
+            self.write_node(node, &nodes[index + 1..], false);
+        }
+        self.close_formats();
+        self.format_stack = format_stack;
+        self.paragraph_open = paragraph_open;
+    }
+
+    fn write_nested_nodes(&mut self, nodes: &[::Node]) {
+        let paragraph_open = ::std::mem::replace(&mut self.paragraph_open, false);
+        let format_stack = ::std::mem::take(&mut self.format_stack);
+        self.write_nodes(nodes, false);
+        self.close_paragraph();
+        self.format_stack = format_stack;
+        self.paragraph_open = paragraph_open;
+    }
+
+    fn write_node(&mut self, node: &::Node, following_nodes: &[::Node], wrap_paragraphs: bool) {
+        match node {
+            ::Node::Bold { .. } => {
+                self.open_paragraph(wrap_paragraphs);
+                self.toggle_format(Format::Bold);
+            }
+            ::Node::BoldItalic { .. } => {
+                self.open_paragraph(wrap_paragraphs);
+                // When opening both, the one that is closed first must be the inner one.
+                let bold_inside = match self.format_stack.last() {
+                    None => following_nodes
+                        .iter()
+                        .filter_map(|node| match node {
+                            ::Node::Bold { .. } => Some(true),
+                            ::Node::Italic { .. } => Some(false),
+                            _ => None,
+                        })
+                        .next()
+                        .unwrap_or(false),
+                    Some(format) => *format == Format::Italic,
+                };
+                if bold_inside {
+                    self.toggle_format(Format::Italic);
+                    self.toggle_format(Format::Bold);
+                } else {
+                    self.toggle_format(Format::Bold);
+                    self.toggle_format(Format::Italic);
+                }
+            }
+            ::Node::Category { .. }
+            | ::Node::Comment { .. }
+            | ::Node::LanguageLink { .. }
+            | ::Node::MagicWord { .. } => {}
+            ::Node::CharacterEntity { character, .. } => {
+                self.open_paragraph(wrap_paragraphs);
+                match character {
+                    '\u{a0}' => self.output += "&#160;",
+                    _ => write_escaped(character.encode_utf8(&mut [0; 4]), &mut self.output),
+                }
+            }
+            ::Node::DefinitionList { items, .. } => self.write_block(|renderer| {
+                renderer.output += "<dl>";
+                for item in items {
+                    let name = match item.type_ {
+                        ::DefinitionListItemType::Details => "dd",
+                        ::DefinitionListItemType::Term => "dt",
+                    };
+                    renderer.output += &format!("<{}>", name);
+                    renderer.write_nested_nodes(&item.nodes);
+                    renderer.output += &format!("</{}>", name);
+                }
+                renderer.output += "</dl>";
+            }),
+            // The tags for transclusion and the text they leave out are not displayed.
+            ::Node::EndTag { name, .. }
+            | ::Node::StartTag { name, .. }
+            | ::Node::Tag { name, .. }
+                if ::transclusion::is_transclusion_tag(name) => {}
+            ::Node::EndTag { name, .. } => {
+                if is_block_tag(name) {
+                    // Like Mediawiki, don't start a paragraph on a line with a block level tag.
+                    self.close_paragraph();
+                    self.block_tag_on_line = true;
+                } else {
+                    self.open_paragraph(wrap_paragraphs);
+                }
+                self.output += "</";
+                self.output += name;
+                self.output += ">";
+            }
+            ::Node::ExternalLink { nodes, .. } => {
+                self.open_paragraph(wrap_paragraphs);
+                let (url, text, nodes) = match nodes.split_first() {
+                    Some((::Node::Text { value, .. }, nodes)) => match value.find(' ') {
+                        None => (value.as_ref(), "", nodes),
+                        Some(position) => (&value[..position], &value[position + 1..], nodes),
+                    },
+                    _ => ("", "", &nodes[..]),
+                };
+                let is_autonumber = text.trim().is_empty() && nodes.is_empty();
+                self.output += "<a rel=\"nofollow\" class=\"external ";
+                self.output += if is_autonumber { "autonumber" } else { "text" };
+                self.output += "\" href=\"";
+                write_escaped(url, &mut self.output);
+                self.output += "\">";
+                if is_autonumber {
+                    self.external_link_count += 1;
+                    self.output += &format!("[{}]", self.external_link_count);
+                } else {
+                    write_escaped(text, &mut self.output);
+                    self.write_inline_nodes(nodes);
+                }
+                self.output += "</a>";
+            }
+            ::Node::FreeExternalLink { url, .. } => {
+                self.open_paragraph(wrap_paragraphs);
+                self.output += "<a rel=\"nofollow\" class=\"external free\" href=\"";
+                write_escaped(url, &mut self.output);
+                self.output += "\">";
+                write_escaped(url, &mut self.output);
+                self.output += "</a>";
+            }
+            ::Node::Heading { level, nodes, .. } => self.write_block(|renderer| {
+                renderer.output += &format!("<h{}><span class=\"mw-headline\" id=\"", level);
+                renderer.write_heading_id(nodes);
+                renderer.output += "\">";
+                renderer.write_inline_nodes(nodes);
+                renderer.output += &format!("</span></h{}>", level);
+            }),
+            ::Node::HorizontalDivider { .. } => self.write_block(|renderer| {
+                renderer.output += "<hr />";
+            }),
+            ::Node::Image { target, text, .. } => {
+                let mut output = String::new();
+                self.handler.image(target, text, &mut output);
+                self.write_handler_output(&output, wrap_paragraphs);
+            }
+            ::Node::InterwikiLink {
+                prefix,
+                target,
+                text,
+                ..
+            } => {
+                self.open_paragraph(wrap_paragraphs);
+                match self.handler.interwiki_url(prefix, target) {
+                    None => self.write_inline_nodes(text),
+                    Some(url) => {
+                        self.output += "<a class=\"extiw\" href=\"";
+                        write_escaped(&url, &mut self.output);
+                        self.output += "\" title=\"";
+                        write_escaped(prefix, &mut self.output);
+                        self.output.push(':');
+                        write_escaped(target, &mut self.output);
+                        self.output += "\">";
+                        self.write_inline_nodes(text);
+                        self.output += "</a>";
+                    }
+                }
+            }
+            ::Node::Italic { .. } => {
+                self.open_paragraph(wrap_paragraphs);
+                self.toggle_format(Format::Italic);
+            }
+            ::Node::Link { target, text, .. } => {
+                self.open_paragraph(wrap_paragraphs);
+                self.output += "<a href=\"";
+                let url = self.handler.link_url(target);
+                write_escaped(&url, &mut self.output);
+                self.output += "\" title=\"";
+                write_escaped(target.trim_start_matches(':').trim(), &mut self.output);
+                self.output += "\">";
+                if text.is_empty() {
+                    write_escaped(target, &mut self.output);
+                } else {
+                    self.write_inline_nodes(text);
+                }
+                self.output += "</a>";
+            }
+            ::Node::OrderedList { items, .. } => self.write_list("ol", items),
+            ::Node::ParagraphBreak { .. } => {
+                self.close_paragraph();
+                self.block_tag_on_line = false;
+            }
+            ::Node::Parameter { default, name, .. } => match default {
+                None => {
+                    self.open_paragraph(wrap_paragraphs);
+                    self.output += "{{{";
+                    self.write_inline_nodes(name);
+                    self.output += "}}}";
+                }
+                Some(default) => self.write_nodes(default, wrap_paragraphs),
+            },
+            ::Node::Preformatted { nodes, .. } => self.write_block(|renderer| {
+                renderer.output += "<pre>";
+                renderer.write_inline_nodes(nodes);
+                renderer.output += "</pre>";
+            }),
+            ::Node::Redirect { target, .. } => self.write_block(|renderer| {
+                renderer.output +=
+                    "<div class=\"redirectMsg\"><p>Redirect to:</p><ul class=\"redirectText\"><li><a href=\"";
+                let url = renderer.handler.link_url(target);
+                write_escaped(&url, &mut renderer.output);
+                renderer.output += "\" title=\"";
+                write_escaped(target, &mut renderer.output);
+                renderer.output += "\">";
+                write_escaped(target, &mut renderer.output);
+                renderer.output += "</a></li></ul></div>";
+            }),
+            ::Node::StartTag {
+                attributes,
+                name,
+                self_closing,
+                ..
+            } => {
+                if is_block_tag(name) {
+                    self.close_paragraph();
+                    self.block_tag_on_line = true;
+                } else {
+                    self.open_paragraph(wrap_paragraphs);
+                }
+                self.output.push('<');
+                self.output += name;
+                write_attributes(name, attributes, &mut self.output);
+                self.output += if *self_closing { " />" } else { ">" };
+            }
+            ::Node::Table {
+                captions,
+                parsed_attributes,
+                rows,
+                ..
+            } => self.write_block(|renderer| {
+                renderer.output += "<table";
+                write_attributes("table", parsed_attributes, &mut renderer.output);
+                renderer.output += ">";
+                for caption in captions {
+                    renderer.output += "<caption";
+                    write_attributes("caption", &caption.parsed_attributes, &mut renderer.output);
+                    renderer.output += ">";
+                    renderer.write_nested_nodes(&caption.content);
+                    renderer.output += "</caption>";
+                }
+                if !rows.is_empty() {
+                    renderer.output += "<tbody>";
+                    for row in rows {
+                        renderer.output += "<tr";
+                        write_attributes("tr", &row.parsed_attributes, &mut renderer.output);
+                        renderer.output += ">";
+                        for cell in &row.cells {
+                            let name = match cell.type_ {
+                                ::TableCellType::Heading => "th",
+                                ::TableCellType::Ordinary => "td",
+                            };
+                            renderer.output.push('<');
+                            renderer.output += name;
+                            write_attributes(name, &cell.parsed_attributes, &mut renderer.output);
+                            renderer.output += ">";
+                            renderer.write_nested_nodes(&cell.content);
+                            renderer.output += "</";
+                            renderer.output += name;
+                            renderer.output += ">";
+                        }
+                        renderer.output += "</tr>";
+                    }
+                    renderer.output += "</tbody>";
+                }
+                renderer.output += "</table>";
+            }),
+            ::Node::Tag {
+                attributes,
+                name,
+                nodes,
+                ..
+            } => {
+                let mut output = String::new();
+                self.handler
+                    .extension_tag(name, attributes, nodes, &mut output);
+                self.write_handler_output(&output, wrap_paragraphs);
+            }
+            ::Node::Template {
+                name, parameters, ..
+            } => {
+                let mut output = String::new();
+                self.handler.template(name, parameters, &mut output);
+                self.write_handler_output(&output, wrap_paragraphs);
+            }
+            ::Node::Text { value, .. } => self.write_text(value, wrap_paragraphs),
+            ::Node::UnorderedList { items, .. } => self.write_list("ul", items),
+        }
+    }
+
+    fn write_handler_output(&mut self, output: &str, wrap_paragraphs: bool) {
+        if !output.is_empty() {
+            self.open_paragraph(wrap_paragraphs);
+            self.output += output;
+        }
+    }
+
+    fn write_list(&mut self, name: &str, items: &[::ListItem]) {
+        self.write_block(|renderer| {
+            renderer.output += &format!("<{}>", name);
+            for item in items {
+                renderer.output += "<li>";
+                renderer.write_nested_nodes(&item.nodes);
+                renderer.output += "</li>";
+            }
+            renderer.output += &format!("</{}>", name);
+        });
+    }
+
+    fn write_nodes(&mut self, nodes: &[::Node], mut wrap_paragraphs: bool) {
+        for (index, node) in nodes.iter().enumerate() {
+            self.write_node(node, &nodes[index + 1..], wrap_paragraphs);
+            if let ::Node::ParagraphBreak { .. } = node {
+                wrap_paragraphs = true;
+            }
+        }
+    }
+
+    fn write_text(&mut self, text: &str, wrap_paragraphs: bool) {
+        for (index, line) in text.split('\n').enumerate() {
+            if index > 0 {
+                // Mediawiki closes all bold and italic text at the end of each line.
+                self.close_formats();
+                self.block_tag_on_line = false;
+                self.output.push('\n');
+            }
+            if !line.trim().is_empty() {
+                self.open_paragraph(wrap_paragraphs);
+            }
+            write_escaped(line, &mut self.output);
+        }
+    }
+}
+
+fn close_format_tag(format: Format) -> &'static str {
+    match format {
+        Format::Bold => "</b>",
+        Format::Italic => "</i>",
+    }
+}
+
+fn decode_character_references(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(position) = rest.find('&') {
+        result += &rest[..position];
+        rest = &rest[position + 1..];
+        if let Some((length, character)) = decode_named_entity(rest)
+            .or_else(|| ::character_entity::decode_numeric_entity(rest))
+        {
+            result.push(character);
+            rest = &rest[length..];
+        } else {
+            result.push('&');
+        }
+    }
+    result + rest
+}
+
+fn decode_named_entity(text: &str) -> Option<(usize, char)> {
+    let length = text.find(';')?;
+    let index = ::html_entities::HTML_ENTITIES
+        .binary_search_by_key(&&text[..length], |(name, _)| name)
+        .ok()?;
+    Some((length + 1, ::html_entities::HTML_ENTITIES[index].1))
+}
+
+fn is_block_tag(name: &str) -> bool {
+    matches!(
+        name,
+        "blockquote" | "center" | "dd" | "div" | "dl" | "dt" | "h1" | "h2" | "h3" | "h4"
+            | "h5" | "h6" | "hr" | "li" | "ol" | "p" | "pre" | "table" | "td" | "th" | "tr"
+            | "ul"
+    )
+}
+
+/// Returns whether the attribute is allowed on the element, using the same lists of allowed attributes as the sanitizer of MediaWiki.
+fn is_allowed_attribute(element: &str, name: &str) -> bool {
+    if name.starts_with("data-") {
+        return !name.contains(':')
+            && !["data-mw", "data-ooui", "data-parsoid"]
+                .iter()
+                .any(|prefix| name.starts_with(prefix));
+    }
+    if matches!(
+        name,
+        "about"
+            | "aria-describedby"
+            | "aria-flowto"
+            | "aria-hidden"
+            | "aria-label"
+            | "aria-labelledby"
+            | "aria-level"
+            | "aria-owns"
+            | "class"
+            | "datatype"
+            | "dir"
+            | "id"
+            | "itemid"
+            | "itemprop"
+            | "itemref"
+            | "itemscope"
+            | "itemtype"
+            | "lang"
+            | "property"
+            | "resource"
+            | "role"
+            | "style"
+            | "tabindex"
+            | "title"
+            | "typeof"
+    ) {
+        return true;
+    }
+    match element {
+        "blockquote" | "q" => name == "cite",
+        "br" => name == "clear",
+        "caption" | "div" | "h1" | "h2" | "h3" | "h4" | "h5" | "h6" | "p" => name == "align",
+        "data" => name == "value",
+        "del" | "ins" => matches!(name, "cite" | "datetime"),
+        "font" => matches!(name, "color" | "face" | "size"),
+        "hr" | "pre" => name == "width",
+        "li" => matches!(name, "type" | "value"),
+        "ol" => matches!(name, "reversed" | "start" | "type"),
+        "table" => matches!(
+            name,
+            "align"
+                | "bgcolor"
+                | "border"
+                | "cellpadding"
+                | "cellspacing"
+                | "frame"
+                | "rules"
+                | "summary"
+                | "width"
+        ),
+        "td" | "th" => matches!(
+            name,
+            "abbr"
+                | "align"
+                | "axis"
+                | "bgcolor"
+                | "colspan"
+                | "headers"
+                | "height"
+                | "nowrap"
+                | "rowspan"
+                | "scope"
+                | "valign"
+                | "width"
+        ),
+        "time" => name == "datetime",
+        "tr" => matches!(name, "align" | "bgcolor" | "valign"),
+        "ul" => name == "type",
+        _ => false,
+    }
+}
+
+fn is_safe_attribute(element: &str, attribute: &::Attribute) -> bool {
+    if !is_allowed_attribute(element, &attribute.name) {
+        return false;
+    }
+    match &attribute.value {
+        Some(value) if attribute.name == "style" => {
+            let value = decode_character_references(&value.value).to_ascii_lowercase();
+            !["expression", "javascript:", "url(", "\\"]
+                .iter()
+                .any(|pattern| value.contains(pattern))
+        }
+        _ => true,
+    }
+}
+
+fn open_format_tag(format: Format) -> &'static str {
+    match format {
+        Format::Bold => "<b>",
+        Format::Italic => "<i>",
+    }
+}
+
+fn write_attributes(element: &str, attributes: &[::Attribute], output: &mut String) {
+    for (index, attribute) in attributes.iter().enumerate() {
+        if !is_safe_attribute(element, attribute)
+            || attributes[index + 1..]
+                .iter()
+                .any(|later_attribute| later_attribute.name == attribute.name)
+        {
+            continue;
+        }
+        output.push(' ');
+        *output += &attribute.name;
+        *output += "=\"";
+        if let Some(value) = &attribute.value {
+            write_escaped(&decode_character_references(&value.value), output);
+        }
+        output.push('"');
+    }
+}
+
+fn write_escaped(text: &str, output: &mut String) {
+    for character in text.chars() {
+        match character {
+            '"' => *output += "&quot;",
+            '&' => *output += "&amp;",
+            '<' => *output += "&lt;",
+            '>' => *output += "&gt;",
+            _ => output.push(character),
+        }
+    }
+}
+
+fn write_plain_text(nodes: &[::Node], output: &mut String) {
+    for node in nodes {
+        match node {
+            ::Node::CharacterEntity { character, .. } => output.push(*character),
+            ::Node::ExternalLink { nodes, .. } => match nodes.split_first() {
+                Some((::Node::Text { value, .. }, nodes)) => {
+                    if let Some(position) = value.find(' ') {
+                        *output += &value[position + 1..];
+                    }
+                    write_plain_text(nodes, output);
+                }
+                _ => write_plain_text(nodes, output),
+            },
+            ::Node::FreeExternalLink { url, .. } => *output += url,
+            ::Node::InterwikiLink { text, .. } => write_plain_text(text, output),
+            ::Node::Link { target, text, .. } => if text.is_empty() {
+                *output += target;
+            } else {
+                write_plain_text(text, output);
+            },
+            ::Node::Tag { name, nodes, .. } if name == "nowiki" => {
+                write_plain_text(nodes, output)
+            }
+            ::Node::Text { value, .. } => *output += value,
+            _ => {}
+        }
+    }
+}
+
+fn write_text_content(nodes: &[::Node], output: &mut String) {
+    for node in nodes {
+        if let ::Node::Text { value, .. } = node {
+            write_escaped(value, output);
+        }
+    }
+}
+
+fn write_url_encoded(text: &str, output: &mut String) {
+    for character in text.chars() {
+        match character {
+            ' ' => output.push('_'),
+            '!' | '$' | '(' | ')' | '*' | ',' | '-' | '.' | '/' | ':' | ';' | '@' | '_'
+            | '~' => output.push(character),
+            _ if character.is_ascii_alphanumeric() => output.push(character),
+            _ => for byte in character.encode_utf8(&mut [0; 4]).bytes() {
+                *output += &format!("%{:02X}", byte);
+            },
+        }
+    }
+}
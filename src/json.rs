@@ -0,0 +1,218 @@
+// Copyright 2018 Fredrik Portström <https://portstrom.com>
+// This is free software distributed under the terms specified in
+// the file LICENSE at the top-level directory of this distribution.
+
+/// The maximum nesting of arrays and objects, to bound the recursion when parsing.
+const MAX_DEPTH: usize = 64;
+
+#[derive(Debug)]
+pub enum Value {
+    Array(Vec<Value>),
+    Bool(bool),
+    Null,
+    Number(f64),
+    Object(Vec<(String, Value)>),
+    String(String),
+}
+
+struct Parser<'a> {
+    position: usize,
+    text: &'a [u8],
+}
+
+impl Value {
+    pub fn as_array(&self) -> Option<&[Value]> {
+        match self {
+            Value::Array(values) => Some(values),
+            _ => None,
+        }
+    }
+
+    pub fn as_object(&self) -> Option<&[(String, Value)]> {
+        match self {
+            Value::Object(members) => Some(members),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Value::String(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Value> {
+        self.as_object()?
+            .iter()
+            .find(|(member_name, _)| member_name == name)
+            .map(|(_, value)| value)
+    }
+}
+
+/// Parses a JSON text, or returns the byte position where the text is not valid JSON.
+pub fn parse(text: &str) -> Result<Value, usize> {
+    let mut parser = Parser {
+        position: 0,
+        text: text.as_bytes(),
+    };
+    let value = parser.parse_value(0)?;
+    parser.skip_whitespace();
+    if parser.position < parser.text.len() {
+        return Err(parser.position);
+    }
+    Ok(value)
+}
+
+impl<'a> Parser<'a> {
+    fn expect(&mut self, literal: &[u8]) -> Result<(), usize> {
+        if self.text[self.position..].starts_with(literal) {
+            self.position += literal.len();
+            Ok(())
+        } else {
+            Err(self.position)
+        }
+    }
+
+    fn parse_hex(&mut self) -> Result<u32, usize> {
+        let digits = self
+            .text
+            .get(self.position..self.position + 4)
+            .ok_or(self.position)?;
+        let mut value = 0;
+        for digit in digits {
+            value = value * 16 + (*digit as char).to_digit(16).ok_or(self.position)?;
+        }
+        self.position += 4;
+        Ok(value)
+    }
+
+    fn parse_number(&mut self) -> Result<Value, usize> {
+        let start = self.position;
+        while let Some(b'+') | Some(b'-') | Some(b'.') | Some(b'0'..=b'9') | Some(b'E')
+        | Some(b'e') = self.text.get(self.position)
+        {
+            self.position += 1;
+        }
+        ::std::str::from_utf8(&self.text[start..self.position])
+            .ok()
+            .and_then(|number| number.parse().ok())
+            .map(Value::Number)
+            .ok_or(start)
+    }
+
+    fn parse_string(&mut self) -> Result<String, usize> {
+        self.expect(b"\"")?;
+        let mut value = vec![];
+        loop {
+            match self.text.get(self.position).cloned() {
+                None => return Err(self.position),
+                Some(b'"') => break,
+                Some(b'\\') => {
+                    self.position += 1;
+                    let character = match self.text.get(self.position).cloned() {
+                        Some(b'"') => '"',
+                        Some(b'/') => '/',
+                        Some(b'\\') => '\\',
+                        Some(b'b') => '\u{8}',
+                        Some(b'f') => '\u{C}',
+                        Some(b'n') => '\n',
+                        Some(b'r') => '\r',
+                        Some(b't') => '\t',
+                        Some(b'u') => {
+                            self.position += 1;
+                            let mut code_point = self.parse_hex()?;
+                            if (0xD800..0xDC00).contains(&code_point)
+                                && self.text[self.position..].starts_with(b"\\u")
+                            {
+                                self.position += 2;
+                                let low = self.parse_hex()?;
+                                if (0xDC00..0xE000).contains(&low) {
+                                    code_point = 0x10000 + ((code_point - 0xD800) << 10) + low
+                                        - 0xDC00;
+                                }
+                            }
+                            let character =
+                                ::std::char::from_u32(code_point).unwrap_or('\u{FFFD}');
+                            let mut buffer = [0; 4];
+                            value.extend(character.encode_utf8(&mut buffer).as_bytes());
+                            continue;
+                        }
+                        _ => return Err(self.position),
+                    };
+                    value.push(character as u8);
+                }
+                Some(byte) if byte < b' ' => return Err(self.position),
+                Some(byte) => value.push(byte),
+            }
+            self.position += 1;
+        }
+        self.position += 1;
+        String::from_utf8(value).map_err(|_| self.position)
+    }
+
+    fn parse_value(&mut self, depth: usize) -> Result<Value, usize> {
+        self.skip_whitespace();
+        match self.text.get(self.position).cloned() {
+            Some(b'"') => self.parse_string().map(Value::String),
+            Some(b'-') | Some(b'0'..=b'9') => self.parse_number(),
+            Some(b'[') if depth < MAX_DEPTH => {
+                self.position += 1;
+                let mut values = vec![];
+                self.skip_whitespace();
+                if self.text.get(self.position) == Some(&b']') {
+                    self.position += 1;
+                    return Ok(Value::Array(values));
+                }
+                loop {
+                    values.push(self.parse_value(depth + 1)?);
+                    self.skip_whitespace();
+                    match self.text.get(self.position) {
+                        Some(b',') => self.position += 1,
+                        Some(b']') => {
+                            self.position += 1;
+                            return Ok(Value::Array(values));
+                        }
+                        _ => return Err(self.position),
+                    }
+                }
+            }
+            Some(b'f') => self.expect(b"false").map(|_| Value::Bool(false)),
+            Some(b'n') => self.expect(b"null").map(|_| Value::Null),
+            Some(b't') => self.expect(b"true").map(|_| Value::Bool(true)),
+            Some(b'{') if depth < MAX_DEPTH => {
+                self.position += 1;
+                let mut members = vec![];
+                self.skip_whitespace();
+                if self.text.get(self.position) == Some(&b'}') {
+                    self.position += 1;
+                    return Ok(Value::Object(members));
+                }
+                loop {
+                    self.skip_whitespace();
+                    let name = self.parse_string()?;
+                    self.skip_whitespace();
+                    self.expect(b":")?;
+                    members.push((name, self.parse_value(depth + 1)?));
+                    self.skip_whitespace();
+                    match self.text.get(self.position) {
+                        Some(b',') => self.position += 1,
+                        Some(b'}') => {
+                            self.position += 1;
+                            return Ok(Value::Object(members));
+                        }
+                        _ => return Err(self.position),
+                    }
+                }
+            }
+            _ => Err(self.position),
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        while let Some(b'\t') | Some(b'\n') | Some(b'\r') | Some(b' ') = self.text.get(self.position)
+        {
+            self.position += 1;
+        }
+    }
+}
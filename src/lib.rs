@@ -30,7 +30,7 @@
 //!
 //! ## Safety
 //!
-//! Parse Wiki Text is designed to work with untrusted inputs. If any input doesn't parse safely with reasonable resources, please report it as an issue. No unsafe code is used.
+//! Parse Wiki Text is designed to work with untrusted inputs. If any input doesn't parse safely with reasonable resources, please report it as an issue. No unsafe code is used. To bound the work done for an input, such as the size of the input, the nesting, the number of nodes or the time spent, parse with `Configuration::parse_with_limits`.
 //!
 //! ## Platform support
 //!
@@ -50,16 +50,16 @@
 //!
 //! Wiki text has plenty of features that are parsed in a way that depends on the configuration of the wiki. This means the configuration must be known before parsing.
 //!
-//! - External links are parsed only when the scheme of the URI of the link is in the configured list of valid protocols. When the scheme is not valid, the link is parsed as plain text.
+//! - External links are parsed only when the scheme of the URI of the link is in the configured list of valid protocols. When the scheme is not valid, the link is parsed as plain text. The same protocols are used for recognizing free external links, which are URLs written in the text without brackets.
 //! - Categories and images superficially look they same way as links, but are parsed differently. These can only be distinguished by knowing the namespace aliases from the configuration of the wiki.
 //! - Text matching the configured set of magic words is parsed as magic words.
 //! - Extension tags have the same syntax as HTML tags, but are parsed differently. The configuration tells which tag names are to be treated as extension tags.
 //!
-//! The configuration can be seen by making a request to the [site info](https://www.mediawiki.org/wiki/API:Siteinfo) resource on the wiki. The utility [Fetch site configuration](https://github.com/portstrom/fetch_site_configuration) fetches the parts of the configuration needed for parsing pages in the wiki, and outputs Rust code for instantiating a parser with that configuration. Parse Wiki Text contains a default configuration that can be used for testing.
+//! The configuration can be seen by making a request to the [site info](https://www.mediawiki.org/wiki/API:Siteinfo) resource on the wiki. The utility [Fetch site configuration](https://github.com/portstrom/fetch_site_configuration) fetches the parts of the configuration needed for parsing pages in the wiki, and outputs Rust code for instantiating a parser with that configuration. Alternatively, the JSON response from the site info resource can be saved and read with `Configuration::from_siteinfo_json`, which creates the configuration at runtime. Parse Wiki Text contains a default configuration that can be used for testing.
 //!
 //! # Limitations
 //!
-//! Wiki text was never designed to be possible to parse into a structured format. It's designed to be parsed in multiple passes, where each pass depends on the output on the previous pass. Most importantly, templates are expanded in an earlier pass and formatting codes are parsed in a later pass. This means the formatting codes you see in the original text are not necessarily the same as the parser will see after templates have been expanded. Luckily this is as bad for human editors as it is for computers, so people tend to avoid writing templates that cause formatting codes to be parsed in a way that differs from what they would expect from reading the original wiki text before expanding templates. Parse Wiki Text assumes that templates never change the meaning of formatting codes around them.
+//! Wiki text was never designed to be possible to parse into a structured format. It's designed to be parsed in multiple passes, where each pass depends on the output on the previous pass. Most importantly, templates are expanded in an earlier pass and formatting codes are parsed in a later pass. This means the formatting codes you see in the original text are not necessarily the same as the parser will see after templates have been expanded. Luckily this is as bad for human editors as it is for computers, so people tend to avoid writing templates that cause formatting codes to be parsed in a way that differs from what they would expect from reading the original wiki text before expanding templates. Parse Wiki Text assumes that templates never change the meaning of formatting codes around them. When the wiki text of the templates is available, the templates can instead be expanded before parsing with `Configuration::expand_templates`, or expanded and parsed in one step with `Configuration::parse_expanded`, which gives the structure of pages whose tables and lists are assembled by templates.
 //!
 //! # Sandbox
 //!
@@ -71,6 +71,26 @@
 //!
 //! That crate could possibly be improved to always report errors when a text isn't in the supported subset, but pages found in real wikis very often don't conform to the small subset of wiki text that can be parsed without weirdness, so it still wouldn't be useful. Improving that crate to correctly parse a large enough subset of wiki text would be as much effort as starting over from scratch, which is why Parse Wiki Text was made without taking anything from Mediawiki Parser. Parse Wiki Text aims to correctly parse all wiki text, not just a subset, and report warnings when encountering weirdness that should be avoided.
 //!
+//! # Serialization
+//!
+//! With the cargo feature `serde` enabled, the output of the parser can be serialized and deserialized with [Serde](https://serde.rs), for example to exchange parse results with programs written in other languages. Deserializing gives nodes that own their text, so the result doesn't borrow from the input.
+//!
+//! The serialized form follows the Rust types exactly:
+//!
+//! - Structs are maps with one entry for each field, using the name of the field in Rust, such as `parsed_attributes` and `type_`.
+//! - A node is a map with the entry `type` naming the type of node, such as `"Text"` or `"Template"`, followed by the fields of the node.
+//! - Enums without fields, such as `WarningMessage`, `AttributeQuote` and `TableCellType`, are strings naming the variant.
+//! - Optional values are `null` when absent.
+//! - Positions are byte offsets into the wiki text.
+//!
+//! The version of this layout is given by the constant `SERDE_SCHEMA_VERSION`. It's incremented whenever the layout changes, including when a type of node or a field is added, so consumers can detect output they don't know how to read.
+//!
+//! # Upgrading from version 0.1
+//!
+//! - The nodes of `Output` and `Document` are held in `Nodes`, which drops them without recursion, so deeply nested output from untrusted input can't overflow the stack when dropped. It dereferences to `Vec<Node>`, so most code reading the nodes works unchanged. Code that needs the vector itself can take it with `Nodes::into_vec`, and code that creates an `Output` can convert a vector with `into`.
+//! - The field `value` of `Node::Text` and the field `target` of `Node::Category`, `Node::Image`, `Node::Link` and `Node::Redirect` are `Cow<str>` instead of `&str`, so that nodes can own their text, as the nodes from `into_owned` and `Configuration::parse_document` do. The text is still borrowed from the wiki text after parsing, and a `Cow<str>` dereferences to `str`, so most code reading these fields works unchanged. Code that needs a `&str` can write `&value as &str`, code that matches them against string literals needs to match on `&*value` instead, and code that creates these nodes can convert a `&str` with `into`.
+//! - The fields `interwiki_prefixes`, `language_link_prefixes` and `namespaces` were added to `ConfigurationSource`. Code that creates a `ConfigurationSource` needs to give these fields, or end the struct expression with `..Default::default()` to leave them empty.
+//!
 //! # Examples
 //!
 //! The default configuration is used for testing purposes only.
@@ -106,53 +126,151 @@
 #![forbid(unsafe_code)]
 #![warn(missing_docs)]
 
+#[cfg(feature = "serde")]
+#[macro_use]
+extern crate serde;
+
+mod attribute;
 mod bold_italic;
 mod case_folding_simple;
 mod character_entity;
 mod comment;
 mod configuration;
+mod core_functions;
 mod default;
+mod drop;
+mod event;
+mod expand;
+mod expr;
 mod external_link;
 mod heading;
+mod html;
 mod html_entities;
+mod json;
+mod limits;
 mod line;
 mod link;
 mod list;
 mod magic_word;
+mod owned;
 mod parse;
+mod parser_functions;
+mod plain_text;
 mod positioned;
 mod redirect;
+mod reparse;
+mod section;
+mod serialize;
+mod siteinfo;
+mod source_map;
 mod state;
 mod table;
 mod tag;
 mod template;
+mod template_call;
+mod time;
+mod title;
+mod transclusion;
 mod trie;
+mod visit;
 mod warning;
 
-pub use configuration::ConfigurationSource;
+pub use configuration::{ConfigurationSource, NamespaceSource};
+pub use event::{Event, Events, PartKind};
+pub use expand::{ExpansionLimits, PageContext, TemplateProvider};
+pub use expr::{evaluate_expression, ExprError};
+pub use html::{render_html, DefaultHtmlHandler, HtmlHandler};
+pub use limits::ParseLimits;
+pub use plain_text::{to_plain_text, PlainTextProfile};
+pub use section::{replace_section, section_range, sections, Section};
+pub use siteinfo::SiteinfoError;
+pub use source_map::{ColumnUnit, LineColumn, SourceMap};
+pub use template_call::{Argument, TemplateCall};
+pub use title::{NamespaceData, Title, TitleError};
+pub use transclusion::TransclusionMode;
 use configuration::Namespace;
 use state::{OpenNode, OpenNodeType, State};
 use std::{
     borrow::Cow, collections::{HashMap, HashSet},
 };
 use trie::Trie;
+pub use visit::{
+    breadth_first, depth_first, walk_node, walk_node_mut, Ancestors, BreadthFirst, Children,
+    DepthFirst, Visitor, VisitorMut,
+};
 pub use warning::{Warning, WarningMessage};
 
+/// Version of the layout of the output when serialized with the cargo feature `serde`.
+pub const SERDE_SCHEMA_VERSION: u32 = 5;
+
+/// HTML attribute of a tag.
+#[derive(Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct Attribute<'a> {
+    /// The byte position in the wiki text where the element ends.
+    pub end: usize,
+
+    /// The name of the attribute, converted to lowercase.
+    pub name: Cow<'a, str>,
+
+    /// The byte position in the wiki text where the element starts.
+    pub start: usize,
+
+    /// The value of the attribute, if any.
+    pub value: Option<AttributeValue<'a>>,
+}
+
+/// Identifier for the type of quotes around the value of an attribute.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub enum AttributeQuote {
+    /// Parsed from a value starting with `"`.
+    Double,
+
+    /// Parsed from a value starting with `'`.
+    Single,
+
+    /// Parsed from a value not starting with a quote.
+    Unquoted,
+}
+
+/// Value of an HTML attribute.
+#[derive(Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct AttributeValue<'a> {
+    /// The byte position in the wiki text where the value ends, not including the closing quote.
+    pub end: usize,
+
+    /// The type of quotes around the value.
+    pub quote: AttributeQuote,
+
+    /// The byte position in the wiki text where the value starts, not including the opening quote.
+    pub start: usize,
+
+    /// The value as it appears in the wiki text, without quotes and without decoding character entities.
+    pub value: Cow<'a, str>,
+}
+
 /// Configuration for the parser.
 ///
 /// A configuration to correctly parse a real wiki can be created with `Configuration::new`. A configuration for testing and quick and dirty prototyping can be created with `Default::default`.
 pub struct Configuration {
     character_entities: Trie<char>,
+    interwiki_prefixes: HashSet<String>,
     link_trail_character_set: HashSet<char>,
     magic_words: Trie<()>,
+    namespace_data: Vec<NamespaceData>,
+    namespace_ids: HashMap<String, i32>,
     namespaces: Trie<::Namespace>,
+    protocol_word_ends: Option<Vec<u8>>,
     protocols: Trie<()>,
     redirect_magic_words: Trie<()>,
     tag_name_map: HashMap<String, ::TagClass>,
 }
 
 /// List item of a definition list.
-#[derive(Debug)]
+#[derive(Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 pub struct DefinitionListItem<'a> {
     /// The byte position in the wiki text where the element ends.
     pub end: usize,
@@ -169,6 +287,7 @@ pub struct DefinitionListItem<'a> {
 
 /// Identifier for the type of a definition list item.
 #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 pub enum DefinitionListItemType {
     /// Parsed from the code `:`.
     Details,
@@ -177,8 +296,25 @@ pub enum DefinitionListItemType {
     Term,
 }
 
+/// Output of parsing wiki text together with the wiki text, owning all its data.
+///
+/// Unlike `Output`, a document doesn't borrow from the wiki text it was parsed from, so it can be stored in long-lived data structures, sent to other threads or returned from a function that reads a file. It's created with `Configuration::parse_document`. The output of parsing borrowed wiki text can be converted to owned output with `Output::into_owned`.
+#[derive(Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct Document {
+    /// The top level of parsed nodes.
+    pub nodes: Nodes<'static>,
+
+    /// Warnings from the parser telling that something is not well-formed.
+    pub warnings: Vec<Warning>,
+
+    /// The wiki text that was parsed.
+    pub wiki_text: String,
+}
+
 /// List item of an ordered list or unordered list.
-#[derive(Debug)]
+#[derive(Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 pub struct ListItem<'a> {
     /// The byte position in the wiki text where the element ends.
     pub end: usize,
@@ -191,7 +327,9 @@ pub struct ListItem<'a> {
 }
 
 /// Parsed node.
-#[derive(Debug)]
+#[derive(Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[cfg_attr(feature = "serde", serde(tag = "type"))]
 pub enum Node<'a> {
     /// Toggle bold text. Parsed from the code `'''`.
     Bold {
@@ -223,7 +361,7 @@ pub enum Node<'a> {
         start: usize,
 
         /// The category referred to.
-        target: &'a str,
+        target: Cow<'a, str>,
     },
 
     /// Character entity. Parsed from code starting with `&` and ending with `;`.
@@ -283,6 +421,18 @@ pub enum Node<'a> {
         start: usize,
     },
 
+    /// Free external link, a URL written in the text without brackets. Parsed from code starting with a valid protocol that doesn't follow a letter, digit or `_`, and ending before the first character not allowed in the URL, leaving out trailing punctuation the same way as Mediawiki.
+    FreeExternalLink {
+        /// The byte position in the wiki text where the element ends.
+        end: usize,
+
+        /// The byte position in the wiki text where the element starts.
+        start: usize,
+
+        /// The URL of the link.
+        url: Cow<'a, str>,
+    },
+
     /// Heading. Parsed from code starting with `=` and ending with `=`.
     Heading {
         /// The byte position in the wiki text where the element ends.
@@ -316,12 +466,30 @@ pub enum Node<'a> {
         start: usize,
 
         /// The file name of the image.
-        target: &'a str,
+        target: Cow<'a, str>,
 
         /// Additional information for the image.
         text: Vec<Node<'a>>,
     },
 
+    /// Link to a page on another wiki. Parsed from code starting with `[[`, an interwiki prefix and `:`, or `[[:`, an interwiki or language prefix and `:`, and ending with `]]`.
+    InterwikiLink {
+        /// The byte position in the wiki text where the element ends.
+        end: usize,
+
+        /// The interwiki prefix, as it appears in the wiki text.
+        prefix: Cow<'a, str>,
+
+        /// The byte position in the wiki text where the element starts.
+        start: usize,
+
+        /// The title of the page on the other wiki, following the prefix.
+        target: Cow<'a, str>,
+
+        /// The text to display for the link.
+        text: Vec<Node<'a>>,
+    },
+
     /// Toggle italic text. Parsed from the code `''`.
     Italic {
         /// The byte position in the wiki text where the element ends.
@@ -331,6 +499,24 @@ pub enum Node<'a> {
         start: usize,
     },
 
+    /// Link to the same subject in another language. Parsed from code starting with `[[`, a language prefix and `:`, and ending with `]]`. Language links are metadata of the page, listed separately from its content rather than displayed where they appear.
+    LanguageLink {
+        /// The byte position in the wiki text where the element ends.
+        end: usize,
+
+        /// The language prefix, as it appears in the wiki text.
+        prefix: Cow<'a, str>,
+
+        /// The byte position in the wiki text where the element starts.
+        start: usize,
+
+        /// The title of the page in the other language, following the prefix.
+        target: Cow<'a, str>,
+
+        /// The text following `|`, if any, which is not displayed.
+        text: Vec<Node<'a>>,
+    },
+
     /// Link. Parsed from code starting with `[[` and ending with `]]`.
     Link {
         /// The byte position in the wiki text where the element ends.
@@ -340,7 +526,7 @@ pub enum Node<'a> {
         start: usize,
 
         /// The target of the link.
-        target: &'a str,
+        target: Cow<'a, str>,
 
         /// The text to display for the link.
         text: Vec<Node<'a>>,
@@ -409,7 +595,7 @@ pub enum Node<'a> {
         end: usize,
 
         /// The target of the redirect.
-        target: &'a str,
+        target: Cow<'a, str>,
 
         /// The byte position in the wiki text where the element starts.
         start: usize,
@@ -417,12 +603,18 @@ pub enum Node<'a> {
 
     /// Start tag. Parsed from code starting with `<` and a valid tag name.
     StartTag {
+        /// The attributes of the tag.
+        attributes: Vec<Attribute<'a>>,
+
         /// The byte position in the wiki text where the element ends.
         end: usize,
 
         /// The tag name.
         name: Cow<'a, str>,
 
+        /// Whether the tag ends with `/>`.
+        self_closing: bool,
+
         /// The byte position in the wiki text where the element starts.
         start: usize,
     },
@@ -438,6 +630,9 @@ pub enum Node<'a> {
         /// The byte position in the wiki text where the element ends.
         end: usize,
 
+        /// The HTML attributes of the element as a list of names and values.
+        parsed_attributes: Vec<Attribute<'a>>,
+
         /// The rows of the table.
         rows: Vec<TableRow<'a>>,
 
@@ -447,6 +642,9 @@ pub enum Node<'a> {
 
     /// Extension tag. Parsed from code starting with `<` and the tag name of a valid extension tag.
     Tag {
+        /// The attributes of the start tag.
+        attributes: Vec<Attribute<'a>>,
+
         /// The byte position in the wiki text where the element ends.
         end: usize,
 
@@ -484,7 +682,7 @@ pub enum Node<'a> {
         start: usize,
 
         /// The text.
-        value: &'a str,
+        value: Cow<'a, str>,
     },
 
     /// Unordered list. Parsed from code starting with `*`.
@@ -500,18 +698,118 @@ pub enum Node<'a> {
     },
 }
 
+/// Identifier for the type of a node.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub enum NodeKind {
+    /// Identifier for `Node::Bold`.
+    Bold,
+
+    /// Identifier for `Node::BoldItalic`.
+    BoldItalic,
+
+    /// Identifier for `Node::Category`.
+    Category,
+
+    /// Identifier for `Node::CharacterEntity`.
+    CharacterEntity,
+
+    /// Identifier for `Node::Comment`.
+    Comment,
+
+    /// Identifier for `Node::DefinitionList`.
+    DefinitionList,
+
+    /// Identifier for `Node::EndTag`.
+    EndTag,
+
+    /// Identifier for `Node::ExternalLink`.
+    ExternalLink,
+
+    /// Identifier for `Node::FreeExternalLink`.
+    FreeExternalLink,
+
+    /// Identifier for `Node::Heading`.
+    Heading,
+
+    /// Identifier for `Node::HorizontalDivider`.
+    HorizontalDivider,
+
+    /// Identifier for `Node::Image`.
+    Image,
+
+    /// Identifier for `Node::InterwikiLink`.
+    InterwikiLink,
+
+    /// Identifier for `Node::Italic`.
+    Italic,
+
+    /// Identifier for `Node::LanguageLink`.
+    LanguageLink,
+
+    /// Identifier for `Node::Link`.
+    Link,
+
+    /// Identifier for `Node::MagicWord`.
+    MagicWord,
+
+    /// Identifier for `Node::OrderedList`.
+    OrderedList,
+
+    /// Identifier for `Node::ParagraphBreak`.
+    ParagraphBreak,
+
+    /// Identifier for `Node::Parameter`.
+    Parameter,
+
+    /// Identifier for `Node::Preformatted`.
+    Preformatted,
+
+    /// Identifier for `Node::Redirect`.
+    Redirect,
+
+    /// Identifier for `Node::StartTag`.
+    StartTag,
+
+    /// Identifier for `Node::Table`.
+    Table,
+
+    /// Identifier for `Node::Tag`.
+    Tag,
+
+    /// Identifier for `Node::Template`.
+    Template,
+
+    /// Identifier for `Node::Text`.
+    Text,
+
+    /// Identifier for `Node::UnorderedList`.
+    UnorderedList,
+}
+
+/// Sequence of nodes that doesn't recurse into nested nodes when dropped, so it's safe to drop however deeply the nodes are nested.
+///
+/// It dereferences to a vector of nodes, so it can be used like one. The vector can be taken with `Nodes::into_vec`, but dropping the vector or any node taken out of it recurses once for each level of nesting.
+#[derive(Default, Eq, PartialEq)]
+#[cfg_attr(
+    feature = "serde", derive(Deserialize, Serialize), serde(transparent)
+)]
+pub struct Nodes<'a>(Vec<Node<'a>>);
+
 /// Output of parsing wiki text.
-#[derive(Debug)]
+#[derive(Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 pub struct Output<'a> {
     /// The top level of parsed nodes.
-    pub nodes: Vec<Node<'a>>,
+    pub nodes: Nodes<'a>,
 
     /// Warnings from the parser telling that something is not well-formed.
     pub warnings: Vec<Warning>,
 }
 
 /// Template parameter.
-#[derive(Debug)]
+#[derive(Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 pub struct Parameter<'a> {
     /// The byte position in the wiki text where the element ends.
     pub end: usize,
@@ -531,6 +829,22 @@ pub trait Positioned {
     /// The byte position in the wiki text where the element ends.
     fn end(&self) -> usize;
 
+    /// Returns the range of lines that the element spans, according to the given source map.
+    fn line_range(&self, source_map: &SourceMap) -> std::ops::Range<usize> {
+        let start_line = source_map.line(self.start());
+        let end_line = if self.end() > self.start() {
+            source_map.line(self.end().min(source_map.wiki_text().len()) - 1)
+        } else {
+            start_line
+        };
+        start_line..end_line + 1
+    }
+
+    /// Returns the part of the given wiki text that the element was parsed from.
+    fn span_text<'a>(&self, wiki_text: &'a str) -> &'a str {
+        &wiki_text[self.start().min(wiki_text.len())..self.end().min(wiki_text.len())]
+    }
+
     /// The byte position in the wiki text where the element starts.
     fn start(&self) -> usize;
 }
@@ -542,7 +856,8 @@ enum TagClass {
 }
 
 /// Table caption.
-#[derive(Debug)]
+#[derive(Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 pub struct TableCaption<'a> {
     /// The HTML attributes of the element.
     pub attributes: Option<Vec<Node<'a>>>,
@@ -553,12 +868,16 @@ pub struct TableCaption<'a> {
     /// The byte position in the wiki text where the element ends.
     pub end: usize,
 
+    /// The HTML attributes of the element as a list of names and values.
+    pub parsed_attributes: Vec<Attribute<'a>>,
+
     /// The byte position in the wiki text where the element starts.
     pub start: usize,
 }
 
 /// Table cell.
-#[derive(Debug)]
+#[derive(Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 pub struct TableCell<'a> {
     /// The HTML attributes of the element.
     pub attributes: Option<Vec<Node<'a>>>,
@@ -569,6 +888,9 @@ pub struct TableCell<'a> {
     /// The byte position in the wiki text where the element ends.
     pub end: usize,
 
+    /// The HTML attributes of the element as a list of names and values.
+    pub parsed_attributes: Vec<Attribute<'a>>,
+
     /// The byte position in the wiki text where the element starts.
     pub start: usize,
 
@@ -578,6 +900,7 @@ pub struct TableCell<'a> {
 
 /// Type of table cell.
 #[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 pub enum TableCellType {
     /// Heading cell.
     Heading,
@@ -587,7 +910,8 @@ pub enum TableCellType {
 }
 
 /// Table row.
-#[derive(Debug)]
+#[derive(Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 pub struct TableRow<'a> {
     /// The HTML attributes of the element.
     pub attributes: Vec<Node<'a>>,
@@ -598,6 +922,9 @@ pub struct TableRow<'a> {
     /// The byte position in the wiki text where the element ends.
     pub end: usize,
 
+    /// The HTML attributes of the element as a list of names and values.
+    pub parsed_attributes: Vec<Attribute<'a>>,
+
     /// The byte position in the wiki text where the element starts.
     pub start: usize,
 }
@@ -0,0 +1,126 @@
+// Copyright 2018 Fredrik Portström <https://portstrom.com>
+// This is free software distributed under the terms specified in
+// the file LICENSE at the top-level directory of this distribution.
+
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering}, Arc,
+    },
+    time::Instant,
+};
+
+/// Limits on the work done when parsing, for parsing wiki text from untrusted sources.
+///
+/// When the nesting, the number of nodes, the deadline or the cancellation flag stops the parser, it gives a warning telling which limit was reached, and the rest of the text from the outermost node that was open at that point becomes a single text node. When the size of the input is above its limit, the text isn't parsed at all, and the warning telling so is the only one. When the number of warnings is reached, parsing continues and further warnings are replaced by a single warning telling the limit was reached. The warnings telling that the other limits were reached are always given.
+#[derive(Clone, Debug, Default)]
+pub struct ParseLimits {
+    /// Flag that stops the parser when set, for cancelling parsing from another thread.
+    pub cancel: Option<Arc<AtomicBool>>,
+
+    /// Point in time after which the parser stops.
+    pub deadline: Option<Instant>,
+
+    /// Parsing stops when more nodes than this are open at the same time, each one nested in the previous one.
+    pub max_depth: Option<usize>,
+
+    /// Wiki text longer than this number of bytes is not parsed. The whole text becomes a single text node.
+    pub max_input_size: Option<usize>,
+
+    /// Parsing stops when more nodes than this have been created, counting nodes that were discarded when rewinding.
+    pub max_nodes: Option<usize>,
+
+    /// The maximum number of warnings to give, not including the warnings telling that limits were reached.
+    pub max_warnings: Option<usize>,
+}
+
+impl ::Configuration {
+    /// Parses wiki text into structured data, doing no more work than allowed by the given limits.
+    #[must_use]
+    pub fn parse_with_limits<'a>(&self, wiki_text: &'a str, limits: &ParseLimits) -> ::Output<'a> {
+        ::parse::parse(self, wiki_text, limits, ::TransclusionMode::View)
+    }
+}
+
+/// Whether any of the limits is set, so that they need to be checked while parsing.
+pub fn is_limited(limits: &ParseLimits) -> bool {
+    limits.cancel.is_some()
+        || limits.deadline.is_some()
+        || limits.max_depth.is_some()
+        || limits.max_input_size.is_some()
+        || limits.max_nodes.is_some()
+        || limits.max_warnings.is_some()
+}
+
+/// The number of steps of the parser between checking the time, the cancellation flag and the number of warnings.
+const CHECK_INTERVAL: usize = 1024;
+
+/// Checks the limits, but only when the nesting or the number of nodes is above its limit or at every `CHECK_INTERVAL` steps, to keep it cheap for the steps in between.
+pub fn check_limits(limits: &ParseLimits, state: &mut ::State, step: usize) -> bool {
+    if state.stack.len() <= limits.max_depth.unwrap_or(usize::MAX)
+        && state.node_count <= limits.max_nodes.unwrap_or(usize::MAX)
+        && step % CHECK_INTERVAL != 0
+    {
+        return false;
+    }
+    limit_warnings(limits, state);
+    let message = if limits
+        .max_depth
+        .is_some_and(|max_depth| state.stack.len() > max_depth)
+    {
+        ::WarningMessage::DepthLimitExceeded
+    } else if limits
+        .max_nodes
+        .is_some_and(|max_nodes| state.node_count > max_nodes)
+    {
+        ::WarningMessage::NodeLimitExceeded
+    } else if limits
+        .cancel
+        .as_ref()
+        .is_some_and(|cancel| cancel.load(Ordering::Relaxed))
+    {
+        ::WarningMessage::Cancelled
+    } else if limits
+        .deadline
+        .is_some_and(|deadline| Instant::now() >= deadline)
+    {
+        ::WarningMessage::DeadlineExceeded
+    } else {
+        return false;
+    };
+    state.warnings.push(::Warning {
+        end: state.wiki_text.len(),
+        message,
+        start: state.scan_position,
+    });
+    state.truncate_open_nodes(1);
+    if let Some(open_node) = state.pop_open_node() {
+        state.rewind(open_node.nodes, open_node.start);
+    }
+    state.scan_position = state.wiki_text.len();
+    true
+}
+
+/// Replaces the warnings after the maximum number of warnings with a single warning telling the limit was reached, keeping the warnings telling that other limits were reached.
+pub fn limit_warnings(limits: &ParseLimits, state: &mut ::State) {
+    let max_warnings = match limits.max_warnings {
+        Some(max_warnings) if state.warnings.len() > max_warnings => max_warnings,
+        _ => return,
+    };
+    let mut is_limit_warning_given = false;
+    for warning in state.warnings.split_off(max_warnings) {
+        match warning.message {
+            ::WarningMessage::Cancelled
+            | ::WarningMessage::DeadlineExceeded
+            | ::WarningMessage::DepthLimitExceeded
+            | ::WarningMessage::InputSizeLimitExceeded
+            | ::WarningMessage::NodeLimitExceeded => state.warnings.push(warning),
+            _ => if !is_limit_warning_given {
+                is_limit_warning_given = true;
+                state.warnings.push(::Warning {
+                    message: ::WarningMessage::WarningLimitExceeded,
+                    ..warning
+                });
+            },
+        }
+    }
+}
@@ -81,7 +81,7 @@ pub fn parse_beginning_of_line(state: &mut ::State, line_start_position: Option<
                     while state.get_byte(state.scan_position) == Some(b'-') {
                         state.scan_position += 1;
                     }
-                    state.nodes.push(::Node::HorizontalDivider {
+                    state.push_node(::Node::HorizontalDivider {
                         end: state.scan_position,
                         start,
                     });
@@ -123,7 +123,7 @@ pub fn parse_beginning_of_line(state: &mut ::State, line_start_position: Option<
         Some(position) => if has_line_break {
             let flush_position = state.skip_whitespace_backwards(position);
             state.flush(flush_position);
-            state.nodes.push(::Node::ParagraphBreak {
+            state.push_node(::Node::ParagraphBreak {
                 end: state.scan_position,
                 start: position,
             });
@@ -230,12 +230,12 @@ fn parse_preformatted_end_of_line(state: &mut ::State) {
             }
         }
     }
-    let open_node = state.stack.pop().unwrap();
+    let open_node = state.pop_open_node().unwrap();
     let position = state.skip_whitespace_backwards(state.scan_position);
     state.flush(position);
     state.scan_position += 1;
     let nodes = ::std::mem::replace(&mut state.nodes, open_node.nodes);
-    state.nodes.push(::Node::Preformatted {
+    state.push_node(::Node::Preformatted {
         end: state.scan_position,
         nodes,
         start: open_node.start,
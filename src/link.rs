@@ -17,8 +17,8 @@ pub fn parse_link_end<'a>(
     let mut text = ::std::mem::replace(&mut state.nodes, nodes);
     let end = state.scan_position;
     let start = start_position;
-    state.nodes.push(match namespace {
-        None => {
+    let node = match namespace {
+        None | Some(::Namespace::Interwiki) => {
             let mut trail_end_position = end;
             for character in state.wiki_text[end..].chars() {
                 if !configuration.link_trail_character_set.contains(&character) {
@@ -30,29 +30,54 @@ pub fn parse_link_end<'a>(
                 text.push(::Node::Text {
                     end: trail_end_position,
                     start: end,
-                    value: &state.wiki_text[end..trail_end_position],
+                    value: ::Cow::Borrowed(&state.wiki_text[end..trail_end_position]),
                 });
+                state.node_count += 1;
+                state.flushed_position = trail_end_position;
+                state.scan_position = trail_end_position;
             }
-            ::Node::Link {
-                end: trail_end_position,
-                start,
-                target,
-                text,
+            if namespace.is_none() {
+                ::Node::Link {
+                    end: trail_end_position,
+                    start,
+                    target: ::Cow::Borrowed(target),
+                    text,
+                }
+            } else {
+                let (prefix, target) = split_prefix(target);
+                ::Node::InterwikiLink {
+                    end: trail_end_position,
+                    prefix: ::Cow::Borrowed(prefix),
+                    start,
+                    target: ::Cow::Borrowed(target),
+                    text,
+                }
             }
         }
         Some(::Namespace::Category) => ::Node::Category {
             end,
             ordinal: text,
             start,
-            target,
+            target: ::Cow::Borrowed(target),
         },
         Some(::Namespace::File) => ::Node::Image {
             end,
             start,
-            target,
+            target: ::Cow::Borrowed(target),
             text,
         },
-    });
+        Some(::Namespace::Language) => {
+            let (prefix, target) = split_prefix(target);
+            ::Node::LanguageLink {
+                end,
+                prefix: ::Cow::Borrowed(prefix),
+                start,
+                target: ::Cow::Borrowed(target),
+                text,
+            }
+        }
+    };
+    state.push_node(node);
 }
 
 pub fn parse_link_start(state: &mut ::State, configuration: &::Configuration) {
@@ -63,7 +88,7 @@ pub fn parse_link_start(state: &mut ::State, configuration: &::Configuration) {
         }) => *namespace != Some(::Namespace::File),
         _ => false,
     } {
-        let open_node = state.stack.pop().unwrap();
+        let open_node = state.pop_open_node().unwrap();
         state.warnings.push(::Warning {
             end: state.scan_position,
             message: ::WarningMessage::InvalidLinkSyntax,
@@ -74,7 +99,7 @@ pub fn parse_link_start(state: &mut ::State, configuration: &::Configuration) {
     }
     let mut target_end_position;
     let target_start_position = state.skip_whitespace_forwards(state.scan_position + 2);
-    let namespace = match configuration
+    let mut namespace = match configuration
         .namespaces
         .find(&state.wiki_text[target_start_position..])
     {
@@ -87,6 +112,17 @@ pub fn parse_link_start(state: &mut ::State, configuration: &::Configuration) {
             Some(namespace)
         }
     };
+    // With a leading colon, a language prefix makes an interwiki link, and namespaces make ordinary links.
+    if state.get_byte(target_start_position) == Some(b':') {
+        if let Ok((match_length, ::Namespace::Interwiki))
+        | Ok((match_length, ::Namespace::Language)) = configuration
+            .namespaces
+            .find(&state.wiki_text[target_start_position + 1..])
+        {
+            target_end_position = match_length + target_start_position + 1;
+            namespace = Some(::Namespace::Interwiki);
+        }
+    }
     loop {
         match state.get_byte(target_end_position) {
             None | Some(b'\n') | Some(b'[') | Some(b'{') | Some(b'}') => {
@@ -135,32 +171,55 @@ fn parse_end(
     let mut trail_end_position = trail_start_position;
     match namespace {
         Some(::Namespace::Category) => {
-            state.nodes.push(::Node::Category {
+            state.push_node(::Node::Category {
                 end: trail_end_position,
                 ordinal: vec![],
                 start: state.scan_position,
-                target: state.wiki_text[target_start_position..target_end_position].trim_right(),
+                target: ::Cow::Borrowed(
+                    state.wiki_text[target_start_position..target_end_position].trim_right(),
+                ),
             });
         }
         Some(::Namespace::File) => {
-            state.nodes.push(::Node::Image {
+            state.push_node(::Node::Image {
+                end: trail_end_position,
+                start: state.scan_position,
+                target: ::Cow::Borrowed(
+                    state.wiki_text[target_start_position..target_end_position].trim_right(),
+                ),
+                text: vec![],
+            });
+        }
+        Some(::Namespace::Language) => {
+            let (prefix, target) =
+                split_prefix(state.wiki_text[target_start_position..target_end_position].trim_end());
+            state.push_node(::Node::LanguageLink {
                 end: trail_end_position,
+                prefix: ::Cow::Borrowed(prefix),
                 start: state.scan_position,
-                target: state.wiki_text[target_start_position..target_end_position].trim_right(),
+                target: ::Cow::Borrowed(target),
                 text: vec![],
             });
         }
-        None => {
+        None | Some(::Namespace::Interwiki) => {
             for character in state.wiki_text[trail_start_position..].chars() {
                 if !configuration.link_trail_character_set.contains(&character) {
                     break;
                 }
                 trail_end_position += character.len_utf8();
             }
+            // The text of an interwiki link with a leading colon is displayed without the colon.
+            let text_start_position = if namespace.is_some()
+                && state.get_byte(target_start_position) == Some(b':')
+            {
+                target_start_position + 1
+            } else {
+                target_start_position
+            };
             let target_text = ::Node::Text {
                 end: target_end_position,
-                start: target_start_position,
-                value: &state.wiki_text[target_start_position..target_end_position],
+                start: text_start_position,
+                value: ::Cow::Borrowed(&state.wiki_text[text_start_position..target_end_position]),
             };
             let text = if trail_end_position > trail_start_position {
                 vec![
@@ -168,24 +227,46 @@ fn parse_end(
                     ::Node::Text {
                         end: trail_end_position,
                         start: trail_start_position,
-                        value: &state.wiki_text[trail_start_position..trail_end_position],
+                        value: ::Cow::Borrowed(&state.wiki_text[trail_start_position..trail_end_position]),
                     },
                 ]
             } else {
                 vec![target_text]
             };
-            state.nodes.push(::Node::Link {
-                end: trail_end_position,
-                start: state.scan_position,
-                target: &state.wiki_text[target_start_position..target_end_position].trim_right(),
-                text,
-            });
+            let target = state.wiki_text[target_start_position..target_end_position].trim_end();
+            let node = if namespace.is_none() {
+                ::Node::Link {
+                    end: trail_end_position,
+                    start: state.scan_position,
+                    target: ::Cow::Borrowed(target),
+                    text,
+                }
+            } else {
+                let (prefix, target) = split_prefix(target);
+                ::Node::InterwikiLink {
+                    end: trail_end_position,
+                    prefix: ::Cow::Borrowed(prefix),
+                    start: state.scan_position,
+                    target: ::Cow::Borrowed(target),
+                    text,
+                }
+            };
+            state.push_node(node);
         }
     }
     state.flushed_position = trail_end_position;
     state.scan_position = trail_end_position;
 }
 
+/// Splits the target of an interwiki or language link into the prefix and the title on the other wiki.
+fn split_prefix(target: &str) -> (&str, &str) {
+    let target = target.trim_start_matches(':');
+    match target.find(':') {
+        None => (target, ""),
+        Some(position) => (&target[..position], target[position + 1..].trim()),
+    }
+}
+
 fn parse_unexpected_end(state: &mut ::State, target_end_position: usize) {
     state.warnings.push(::Warning {
         end: target_end_position,
@@ -51,7 +51,7 @@ pub fn parse_list_end_of_line(state: &mut ::State) {
         }
     }
     while level < state.stack.len() {
-        let open_node = state.stack.pop().unwrap();
+        let open_node = state.pop_open_node().unwrap();
         let node = match open_node.type_ {
             ::OpenNodeType::DefinitionList { mut items } => {
                 {
@@ -94,7 +94,7 @@ pub fn parse_list_end_of_line(state: &mut ::State) {
             }
             _ => unreachable!(),
         };
-        state.nodes.push(node);
+        state.push_node(node);
     }
     state.flushed_position = state.scan_position;
     if parse_list_item_start(state) {
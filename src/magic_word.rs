@@ -14,7 +14,7 @@ pub fn parse_magic_word(state: &mut ::State, configuration: &::Configuration) {
             let scan_position = state.scan_position;
             state.flush(scan_position);
             state.flushed_position = end_position + 2;
-            state.nodes.push(::Node::MagicWord {
+            state.push_node(::Node::MagicWord {
                 end: state.flushed_position,
                 start: state.scan_position,
             });
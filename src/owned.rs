@@ -0,0 +1,428 @@
+// Copyright 2018 Fredrik Portström <https://portstrom.com>
+// This is free software distributed under the terms specified in
+// the file LICENSE at the top-level directory of this distribution.
+
+impl ::Configuration {
+    /// Parses wiki text into structured data that owns the wiki text, so it doesn't borrow from anything.
+    #[must_use]
+    pub fn parse_document(&self, wiki_text: String) -> ::Document {
+        let ::Output { nodes, warnings } = self.parse(&wiki_text).into_owned();
+        ::Document {
+            nodes,
+            warnings,
+            wiki_text,
+        }
+    }
+}
+
+impl<'a> ::Attribute<'a> {
+    /// Converts the attribute to one that owns all its text.
+    #[must_use]
+    pub fn into_owned(self) -> ::Attribute<'static> {
+        ::Attribute {
+            end: self.end,
+            name: owned(self.name),
+            start: self.start,
+            value: self.value.map(::AttributeValue::into_owned),
+        }
+    }
+}
+
+impl<'a> ::AttributeValue<'a> {
+    /// Converts the value to one that owns all its text.
+    #[must_use]
+    pub fn into_owned(self) -> ::AttributeValue<'static> {
+        ::AttributeValue {
+            end: self.end,
+            quote: self.quote,
+            start: self.start,
+            value: owned(self.value),
+        }
+    }
+}
+
+impl<'a> ::DefinitionListItem<'a> {
+    /// Converts the list item to one that owns all its text.
+    #[must_use]
+    pub fn into_owned(self) -> ::DefinitionListItem<'static> {
+        ::DefinitionListItem {
+            end: self.end,
+            nodes: owned_nodes(self.nodes),
+            start: self.start,
+            type_: self.type_,
+        }
+    }
+}
+
+impl<'a> ::ListItem<'a> {
+    /// Converts the list item to one that owns all its text.
+    #[must_use]
+    pub fn into_owned(self) -> ::ListItem<'static> {
+        ::ListItem {
+            end: self.end,
+            nodes: owned_nodes(self.nodes),
+            start: self.start,
+        }
+    }
+}
+
+impl<'a> ::Node<'a> {
+    /// Converts the node to one that owns all its text, so it doesn't borrow from the wiki text.
+    #[must_use]
+    pub fn into_owned(self) -> ::Node<'static> {
+        let mut nodes = owned_nodes(vec![self]);
+        nodes.pop().unwrap()
+    }
+}
+
+impl<'a> ::Output<'a> {
+    /// Converts the output to one that owns all its text, so it doesn't borrow from the wiki text.
+    #[must_use]
+    pub fn into_owned(self) -> ::Output<'static> {
+        ::Output {
+            nodes: owned_nodes(self.nodes.into_vec()).into(),
+            warnings: self.warnings,
+        }
+    }
+}
+
+impl<'a> ::Parameter<'a> {
+    /// Converts the parameter to one that owns all its text.
+    #[must_use]
+    pub fn into_owned(self) -> ::Parameter<'static> {
+        ::Parameter {
+            end: self.end,
+            name: self.name.map(owned_nodes),
+            start: self.start,
+            value: owned_nodes(self.value),
+        }
+    }
+}
+
+impl<'a> ::TableCaption<'a> {
+    /// Converts the caption to one that owns all its text.
+    #[must_use]
+    pub fn into_owned(self) -> ::TableCaption<'static> {
+        ::TableCaption {
+            attributes: self.attributes.map(owned_nodes),
+            content: owned_nodes(self.content),
+            end: self.end,
+            parsed_attributes: owned_attributes(self.parsed_attributes),
+            start: self.start,
+        }
+    }
+}
+
+impl<'a> ::TableCell<'a> {
+    /// Converts the cell to one that owns all its text.
+    #[must_use]
+    pub fn into_owned(self) -> ::TableCell<'static> {
+        ::TableCell {
+            attributes: self.attributes.map(owned_nodes),
+            content: owned_nodes(self.content),
+            end: self.end,
+            parsed_attributes: owned_attributes(self.parsed_attributes),
+            start: self.start,
+            type_: self.type_,
+        }
+    }
+}
+
+impl<'a> ::TableRow<'a> {
+    /// Converts the row to one that owns all its text.
+    #[must_use]
+    pub fn into_owned(self) -> ::TableRow<'static> {
+        ::TableRow {
+            attributes: owned_nodes(self.attributes),
+            cells: self.cells.into_iter().map(::TableCell::into_owned).collect(),
+            end: self.end,
+            parsed_attributes: owned_attributes(self.parsed_attributes),
+            start: self.start,
+        }
+    }
+}
+
+/// A node whose child nodes are being converted.
+struct PendingNode<'a, 'b> {
+    child_sequences: ::std::vec::IntoIter<Vec<::Node<'a>>>,
+    converted_sequences: Vec<Vec<::Node<'b>>>,
+    node: ::Node<'b>,
+    parent_sequence: (::std::vec::IntoIter<::Node<'a>>, Vec<::Node<'b>>),
+}
+
+fn owned(text: ::Cow<str>) -> ::Cow<'static, str> {
+    ::Cow::Owned(text.into_owned())
+}
+
+fn owned_attributes(attributes: Vec<::Attribute>) -> Vec<::Attribute<'static>> {
+    attributes.into_iter().map(::Attribute::into_owned).collect()
+}
+
+/// Converts a node whose child nodes have been taken out of it.
+fn owned_node(node: ::Node) -> ::Node<'static> {
+    match node {
+        ::Node::Bold { end, start } => ::Node::Bold { end, start },
+        ::Node::BoldItalic { end, start } => ::Node::BoldItalic { end, start },
+        ::Node::Category {
+            end,
+            ordinal,
+            start,
+            target,
+        } => ::Node::Category {
+            end,
+            ordinal: owned_nodes(ordinal),
+            start,
+            target: owned(target),
+        },
+        ::Node::CharacterEntity {
+            character,
+            end,
+            start,
+        } => ::Node::CharacterEntity {
+            character,
+            end,
+            start,
+        },
+        ::Node::Comment { end, start } => ::Node::Comment { end, start },
+        ::Node::DefinitionList { end, items, start } => ::Node::DefinitionList {
+            end,
+            items: items
+                .into_iter()
+                .map(::DefinitionListItem::into_owned)
+                .collect(),
+            start,
+        },
+        ::Node::EndTag { end, name, start } => ::Node::EndTag {
+            end,
+            name: owned(name),
+            start,
+        },
+        ::Node::ExternalLink { end, nodes, start } => ::Node::ExternalLink {
+            end,
+            nodes: owned_nodes(nodes),
+            start,
+        },
+        ::Node::FreeExternalLink { end, start, url } => ::Node::FreeExternalLink {
+            end,
+            start,
+            url: owned(url),
+        },
+        ::Node::Heading {
+            end,
+            level,
+            nodes,
+            start,
+        } => ::Node::Heading {
+            end,
+            level,
+            nodes: owned_nodes(nodes),
+            start,
+        },
+        ::Node::HorizontalDivider { end, start } => ::Node::HorizontalDivider { end, start },
+        ::Node::Image {
+            end,
+            start,
+            target,
+            text,
+        } => ::Node::Image {
+            end,
+            start,
+            target: owned(target),
+            text: owned_nodes(text),
+        },
+        ::Node::InterwikiLink {
+            end,
+            prefix,
+            start,
+            target,
+            text,
+        } => ::Node::InterwikiLink {
+            end,
+            prefix: owned(prefix),
+            start,
+            target: owned(target),
+            text: owned_nodes(text),
+        },
+        ::Node::Italic { end, start } => ::Node::Italic { end, start },
+        ::Node::LanguageLink {
+            end,
+            prefix,
+            start,
+            target,
+            text,
+        } => ::Node::LanguageLink {
+            end,
+            prefix: owned(prefix),
+            start,
+            target: owned(target),
+            text: owned_nodes(text),
+        },
+        ::Node::Link {
+            end,
+            start,
+            target,
+            text,
+        } => ::Node::Link {
+            end,
+            start,
+            target: owned(target),
+            text: owned_nodes(text),
+        },
+        ::Node::MagicWord { end, start } => ::Node::MagicWord { end, start },
+        ::Node::OrderedList { end, items, start } => ::Node::OrderedList {
+            end,
+            items: items.into_iter().map(::ListItem::into_owned).collect(),
+            start,
+        },
+        ::Node::ParagraphBreak { end, start } => ::Node::ParagraphBreak { end, start },
+        ::Node::Parameter {
+            default,
+            end,
+            name,
+            start,
+        } => ::Node::Parameter {
+            default: default.map(owned_nodes),
+            end,
+            name: owned_nodes(name),
+            start,
+        },
+        ::Node::Preformatted { end, nodes, start } => ::Node::Preformatted {
+            end,
+            nodes: owned_nodes(nodes),
+            start,
+        },
+        ::Node::Redirect { end, target, start } => ::Node::Redirect {
+            end,
+            target: owned(target),
+            start,
+        },
+        ::Node::StartTag {
+            attributes,
+            end,
+            name,
+            self_closing,
+            start,
+        } => ::Node::StartTag {
+            attributes: owned_attributes(attributes),
+            end,
+            name: owned(name),
+            self_closing,
+            start,
+        },
+        ::Node::Table {
+            attributes,
+            captions,
+            end,
+            parsed_attributes,
+            rows,
+            start,
+        } => ::Node::Table {
+            attributes: owned_nodes(attributes),
+            captions: captions
+                .into_iter()
+                .map(::TableCaption::into_owned)
+                .collect(),
+            end,
+            parsed_attributes: owned_attributes(parsed_attributes),
+            rows: rows.into_iter().map(::TableRow::into_owned).collect(),
+            start,
+        },
+        ::Node::Tag {
+            attributes,
+            end,
+            name,
+            nodes,
+            start,
+        } => ::Node::Tag {
+            attributes: owned_attributes(attributes),
+            end,
+            name: owned(name),
+            nodes: owned_nodes(nodes),
+            start,
+        },
+        ::Node::Template {
+            end,
+            name,
+            parameters,
+            start,
+        } => ::Node::Template {
+            end,
+            name: owned_nodes(name),
+            parameters: parameters
+                .into_iter()
+                .map(::Parameter::into_owned)
+                .collect(),
+            start,
+        },
+        ::Node::Text { end, start, value } => ::Node::Text {
+            end,
+            start,
+            value: owned(value),
+        },
+        ::Node::UnorderedList { end, items, start } => ::Node::UnorderedList {
+            end,
+            items: items.into_iter().map(::ListItem::into_owned).collect(),
+            start,
+        },
+    }
+}
+
+fn owned_nodes(nodes: Vec<::Node>) -> Vec<::Node<'static>> {
+    convert_nodes(nodes, owned_node)
+}
+
+/// Converts nodes with a function that converts a node whose child nodes have been taken out of it.
+///
+/// Converting nested nodes the obvious way recurses once for each level of nesting, which overflows the stack for deeply nested wiki text, so the nodes are converted with an explicit stack instead.
+pub fn convert_nodes<'a, 'b, F: FnMut(::Node<'a>) -> ::Node<'b>>(
+    nodes: Vec<::Node<'a>>,
+    mut convert_node: F,
+) -> Vec<::Node<'b>> {
+    let mut sequence = (nodes.into_iter(), vec![]);
+    let mut stack: Vec<PendingNode> = vec![];
+    loop {
+        if let Some(mut node) = sequence.0.next() {
+            let mut child_sequences = ::visit::child_sequences_mut(&mut node)
+                .into_iter()
+                .map(::std::mem::take)
+                .collect::<Vec<_>>()
+                .into_iter();
+            let node = convert_node(node);
+            match child_sequences.next() {
+                None => sequence.1.push(node),
+                Some(nodes) => stack.push(PendingNode {
+                    child_sequences,
+                    converted_sequences: vec![],
+                    node,
+                    parent_sequence: ::std::mem::replace(
+                        &mut sequence,
+                        (nodes.into_iter(), vec![]),
+                    ),
+                }),
+            }
+            continue;
+        }
+        match stack.last_mut() {
+            None => return sequence.1,
+            Some(pending_node) => {
+                pending_node
+                    .converted_sequences
+                    .push(::std::mem::take(&mut sequence.1));
+                if let Some(nodes) = pending_node.child_sequences.next() {
+                    sequence.0 = nodes.into_iter();
+                    continue;
+                }
+            }
+        }
+        let mut pending_node = stack.pop().unwrap();
+        for (child_nodes, converted_nodes) in
+            ::visit::child_sequences_mut(&mut pending_node.node)
+                .into_iter()
+                .zip(pending_node.converted_sequences)
+        {
+            *child_nodes = converted_nodes;
+        }
+        sequence = pending_node.parent_sequence;
+        sequence.1.push(pending_node.node);
+    }
+}
@@ -3,51 +3,99 @@
 // the file LICENSE at the top-level directory of this distribution.
 
 #[must_use]
-pub fn parse<'a>(configuration: &::Configuration, wiki_text: &'a str) -> ::Output<'a> {
+pub fn parse<'a>(
+    configuration: &::Configuration,
+    wiki_text: &'a str,
+    limits: &::ParseLimits,
+    mode: ::TransclusionMode,
+) -> ::Output<'a> {
     let mut state = ::State {
         flushed_position: 0,
+        is_only_include: ::transclusion::is_only_include(wiki_text, mode),
+        mode,
+        node_count: 0,
         nodes: vec![],
+        open_link_count: 0,
         scan_position: 0,
         stack: vec![],
         warnings: vec![],
         wiki_text,
     };
+    if limits
+        .max_input_size
+        .is_some_and(|max_input_size| wiki_text.len() > max_input_size)
     {
-        let mut has_line_break = false;
-        let mut position = 0;
-        loop {
-            match state.get_byte(position) {
-                Some(b'\n') => {
-                    if has_line_break {
-                        state.warnings.push(::Warning {
-                            end: position + 1,
-                            message: ::WarningMessage::RepeatedEmptyLine,
-                            start: position,
-                        });
-                    }
-                    has_line_break = true;
-                    position += 1;
-                    state.flushed_position = position;
-                    state.scan_position = position;
-                }
-                Some(b' ') => position += 1,
-                Some(b'#') => {
-                    ::redirect::parse_redirect(&mut state, configuration, position);
-                    break;
+        state.warnings.push(::Warning {
+            end: wiki_text.len(),
+            message: ::WarningMessage::InputSizeLimitExceeded,
+            start: 0,
+        });
+    } else {
+        if ::transclusion::parse_beginning_of_text(&mut state) {
+            ::line::parse_beginning_of_line(&mut state, None);
+        } else {
+            parse_beginning_of_text(configuration, &mut state);
+        }
+        parse_until(configuration, limits, &mut state, |_| false);
+    }
+    let end_position = state.skip_whitespace_backwards(wiki_text.len());
+    state.flush(end_position);
+    ::Output {
+        nodes: state.nodes.into(),
+        warnings: state.warnings,
+    }
+}
+
+pub fn parse_beginning_of_text(configuration: &::Configuration, state: &mut ::State) {
+    let mut has_line_break = false;
+    let mut position = 0;
+    loop {
+        match state.get_byte(position) {
+            Some(b'\n') => {
+                if has_line_break {
+                    state.warnings.push(::Warning {
+                        end: position + 1,
+                        message: ::WarningMessage::RepeatedEmptyLine,
+                        start: position,
+                    });
                 }
-                _ => break,
+                has_line_break = true;
+                position += 1;
+                state.flushed_position = position;
+                state.scan_position = position;
             }
+            Some(b' ') => position += 1,
+            Some(b'#') => {
+                ::redirect::parse_redirect(state, configuration, position);
+                break;
+            }
+            _ => break,
         }
     }
-    ::line::parse_beginning_of_line(&mut state, None);
+    ::line::parse_beginning_of_line(state, None);
+}
+
+/// Parses until the end of the wiki text, or until `stop` returns true after the end of a line. Returns whether parsing was stopped before the end.
+pub fn parse_until<'a, F: FnMut(&::State<'a>) -> bool>(
+    configuration: &::Configuration,
+    limits: &::ParseLimits,
+    state: &mut ::State<'a>,
+    mut stop: F,
+) -> bool {
+    let is_limited = ::limits::is_limited(limits);
+    let mut step = 0;
     loop {
+        if is_limited && ::limits::check_limits(limits, state, step) {
+            return false;
+        }
+        step += 1;
         match state.get_byte(state.scan_position) {
             None => {
-                ::line::parse_end_of_line(&mut state);
+                ::line::parse_end_of_line(state);
                 if state.scan_position < state.wiki_text.len() {
                     continue;
                 }
-                if let Some(::OpenNode { nodes, start, .. }) = state.stack.pop() {
+                if let Some(::OpenNode { nodes, start, .. }) = state.pop_open_node() {
                     state.warnings.push(::Warning {
                         end: state.scan_position,
                         message: ::WarningMessage::MissingEndTagRewinding,
@@ -55,6 +103,7 @@ pub fn parse<'a>(configuration: &::Configuration, wiki_text: &'a str) -> ::Outpu
                     });
                     state.rewind(nodes, start);
                 } else {
+                    ::limits::limit_warnings(limits, state);
                     break;
                 }
             }
@@ -71,14 +120,17 @@ pub fn parse<'a>(configuration: &::Configuration, wiki_text: &'a str) -> ::Outpu
                 state.scan_position += 1;
             }
             Some(b'\n') => {
-                ::line::parse_end_of_line(&mut state);
+                ::line::parse_end_of_line(state);
+                if stop(state) {
+                    return true;
+                }
             }
             Some(b'!') if state.get_byte(state.scan_position + 1) == Some(b'!') => {
-                ::table::parse_heading_cell(&mut state);
+                ::table::parse_heading_cell(state);
             }
-            Some(b'&') => ::character_entity::parse_character_entity(&mut state, configuration),
+            Some(b'&') => ::character_entity::parse_character_entity(state, configuration),
             Some(b'\'') => if state.get_byte(state.scan_position + 1) == Some(b'\'') {
-                ::bold_italic::parse_bold_italic(&mut state);
+                ::bold_italic::parse_bold_italic(state);
             } else {
                 state.scan_position += 1;
             },
@@ -87,27 +139,30 @@ pub fn parse<'a>(configuration: &::Configuration, wiki_text: &'a str) -> ::Outpu
                     if state.get_byte(state.scan_position + 2) == Some(b'-')
                         && state.get_byte(state.scan_position + 3) == Some(b'-') =>
                 {
-                    ::comment::parse_comment(&mut state)
+                    ::comment::parse_comment(state)
                 }
-                Some(b'/') => ::tag::parse_end_tag(&mut state, configuration),
-                _ => ::tag::parse_start_tag(&mut state, configuration),
+                Some(b'/') => ::tag::parse_end_tag(state, configuration),
+                _ => ::tag::parse_start_tag(state, configuration),
             },
             Some(b'=') => {
-                ::template::parse_parameter_name_end(&mut state);
+                ::template::parse_parameter_name_end(state);
+            }
+            Some(b'A'..=b'Z') | Some(b'a'..=b'z') => {
+                ::external_link::parse_free_external_link(state, configuration);
             }
             Some(b'[') => if state.get_byte(state.scan_position + 1) == Some(b'[') {
-                ::link::parse_link_start(&mut state, configuration);
+                ::link::parse_link_start(state, configuration);
             } else {
-                ::external_link::parse_external_link_start(&mut state, configuration);
+                ::external_link::parse_external_link_start(state, configuration);
             },
-            Some(b']') => match state.stack.pop() {
+            Some(b']') => match state.pop_open_node() {
                 None => state.scan_position += 1,
                 Some(::OpenNode {
                     nodes,
                     start,
                     type_: ::OpenNodeType::ExternalLink,
                 }) => {
-                    ::external_link::parse_external_link_end(&mut state, start, nodes);
+                    ::external_link::parse_external_link_end(state, start, nodes);
                 }
                 Some(::OpenNode {
                     nodes,
@@ -115,7 +170,7 @@ pub fn parse<'a>(configuration: &::Configuration, wiki_text: &'a str) -> ::Outpu
                     type_: ::OpenNodeType::Link { namespace, target },
                 }) => if state.get_byte(state.scan_position + 1) == Some(b']') {
                     ::link::parse_link_end(
-                        &mut state,
+                        state,
                         &configuration,
                         start,
                         nodes,
@@ -124,7 +179,7 @@ pub fn parse<'a>(configuration: &::Configuration, wiki_text: &'a str) -> ::Outpu
                     );
                 } else {
                     state.scan_position += 1;
-                    state.stack.push(::OpenNode {
+                    state.restore_open_node(::OpenNode {
                         nodes,
                         start,
                         type_: ::OpenNodeType::Link { namespace, target },
@@ -132,16 +187,16 @@ pub fn parse<'a>(configuration: &::Configuration, wiki_text: &'a str) -> ::Outpu
                 },
                 Some(open_node) => {
                     state.scan_position += 1;
-                    state.stack.push(open_node);
+                    state.restore_open_node(open_node);
                 }
             },
             Some(b'_') => if state.get_byte(state.scan_position + 1) == Some(b'_') {
-                ::magic_word::parse_magic_word(&mut state, configuration);
+                ::magic_word::parse_magic_word(state, configuration);
             } else {
                 state.scan_position += 1;
             },
             Some(b'{') => if state.get_byte(state.scan_position + 1) == Some(b'{') {
-                ::template::parse_template_start(&mut state);
+                ::template::parse_template_start(state);
             } else {
                 state.scan_position += 1;
             },
@@ -150,24 +205,24 @@ pub fn parse<'a>(configuration: &::Configuration, wiki_text: &'a str) -> ::Outpu
                     type_: ::OpenNodeType::Parameter { default: None, .. },
                     ..
                 }) => {
-                    ::template::parse_parameter_separator(&mut state);
+                    ::template::parse_parameter_separator(state);
                 }
                 Some(::OpenNode {
                     type_: ::OpenNodeType::Table(..),
                     ..
                 }) => {
-                    ::table::parse_inline_token(&mut state);
+                    ::table::parse_inline_token(state);
                 }
                 Some(::OpenNode {
                     type_: ::OpenNodeType::Template { .. },
                     ..
                 }) => {
-                    ::template::parse_template_separator(&mut state);
+                    ::template::parse_template_separator(state);
                 }
                 _ => state.scan_position += 1,
             },
             Some(b'}') => if state.get_byte(state.scan_position + 1) == Some(b'}') {
-                ::template::parse_template_end(&mut state);
+                ::template::parse_template_end(state);
             } else {
                 state.scan_position += 1;
             },
@@ -176,10 +231,5 @@ pub fn parse<'a>(configuration: &::Configuration, wiki_text: &'a str) -> ::Outpu
             }
         }
     }
-    let end_position = state.skip_whitespace_backwards(wiki_text.len());
-    state.flush(end_position);
-    ::Output {
-        nodes: state.nodes,
-        warnings: state.warnings,
-    }
+    false
 }
@@ -0,0 +1,298 @@
+// Copyright 2018 Fredrik Portström <https://portstrom.com>
+// This is free software distributed under the terms specified in
+// the file LICENSE at the top-level directory of this distribution.
+
+use expand::{Expander, Frame, TemplateProvider};
+
+impl<'a, P: TemplateProvider + ?Sized> Expander<'a, P> {
+    /// Evaluates the parser function with the given name, or returns `None` if there is no parser function with the name.
+    ///
+    /// The first argument is the text following the colon in the name of the template, and the remaining arguments are the parameters of the template, which are expanded only when used.
+    pub fn call_parser_function(
+        &mut self,
+        frame: &Frame,
+        wiki_text: &str,
+        name: &str,
+        first_argument: &str,
+        parameters: &[::Parameter],
+    ) -> Option<String> {
+        let argument = |expander: &mut Self, index: usize| match parameters.get(index) {
+            None => String::new(),
+            Some(parameter) => expander
+                .expand_parameter(frame, wiki_text, parameter)
+                .trim()
+                .to_string(),
+        };
+        Some(match &name.to_lowercase() as &str {
+            "#expr" => match ::expr::evaluate_expression(first_argument) {
+                Ok(text) => text,
+                Err(error) => error_message(&error),
+            },
+            "#if" => argument(self, if first_argument.is_empty() { 1 } else { 0 }),
+            "#ifeq" => {
+                let right = argument(self, 0);
+                let index = if loose_equals(first_argument, &right) {
+                    1
+                } else {
+                    2
+                };
+                argument(self, index)
+            }
+            "#iferror" => {
+                if is_error(first_argument) {
+                    argument(self, 0)
+                } else if parameters.len() < 2 {
+                    first_argument.to_string()
+                } else {
+                    argument(self, 1)
+                }
+            }
+            "#ifexist" => {
+                if let Err(message) = self.count_expensive_function() {
+                    return Some(message);
+                }
+                let exists = match self.configuration.parse_title(first_argument, 0) {
+                    Ok(title) => title.interwiki.is_none() && self.provider.page_exists(&title),
+                    Err(_) => false,
+                };
+                argument(self, if exists { 0 } else { 1 })
+            }
+            "#ifexpr" => match ::expr::evaluate_expression(first_argument) {
+                Ok(result) => {
+                    // Like in PHP, a numeric result is true unless it's zero, and any other result is true unless it's empty.
+                    let is_true = match php_number(&result) {
+                        Some(number) => number != 0.0,
+                        None => !result.is_empty() && result != "0",
+                    };
+                    argument(self, if is_true { 0 } else { 1 })
+                }
+                Err(error) => error_message(&error),
+            },
+            "#switch" => self.switch(frame, wiki_text, first_argument, parameters),
+            "#time" => {
+                let date = argument(self, 0);
+                let now = self.provider.current_time();
+                match ::time::format_time(first_argument, &date, now) {
+                    Ok(text) => text,
+                    Err(message) => format!("<strong class=\"error\">{}</strong>", message),
+                }
+            }
+            "#titleparts" => {
+                let parts = argument(self, 0);
+                let offset = argument(self, 1);
+                self.title_parts(first_argument, php_int(&parts), php_int(&offset))
+            }
+            _ => return None,
+        })
+    }
+
+    fn switch(
+        &mut self,
+        frame: &Frame,
+        wiki_text: &str,
+        primary: &str,
+        parameters: &[::Parameter],
+    ) -> String {
+        let mut default = None;
+        let mut default_found = false;
+        let mut found = false;
+        let mut last_item = None;
+        for parameter in parameters {
+            match &parameter.name {
+                Some(name) => {
+                    last_item = None;
+                    if !found {
+                        let test = self.expand_sequence(frame, wiki_text, name);
+                        let test = test.trim();
+                        if loose_equals(test, primary) {
+                            found = true;
+                        } else if default_found || test.eq_ignore_ascii_case("#default") {
+                            default = Some(&parameter.value);
+                            default_found = false;
+                        }
+                    }
+                    if found {
+                        return self
+                            .expand_sequence(frame, wiki_text, &parameter.value)
+                            .trim()
+                            .to_string();
+                    }
+                }
+                None => {
+                    // A value without `=` either falls through to the next result, or is the default result if it's the last one.
+                    let test = self
+                        .expand_parameter(frame, wiki_text, parameter)
+                        .trim()
+                        .to_string();
+                    if loose_equals(&test, primary) {
+                        found = true;
+                    } else if test.eq_ignore_ascii_case("#default") {
+                        default_found = true;
+                    }
+                    last_item = Some(test);
+                }
+            }
+        }
+        match (last_item, default) {
+            (Some(last_item), _) => last_item,
+            (None, Some(default)) => self
+                .expand_sequence(frame, wiki_text, default)
+                .trim()
+                .to_string(),
+            (None, None) => String::new(),
+        }
+    }
+
+    fn title_parts(&self, text: &str, parts: i64, offset: i64) -> String {
+        let title = match self.configuration.parse_title(text, 0) {
+            Ok(title) => title,
+            Err(_) => return text.to_string(),
+        };
+        let full_text = title.full_text();
+        let segments: Vec<&str> = full_text.splitn(25, '/').collect();
+        let length = segments.len() as i64;
+        // Like `array_slice` in PHP, a negative offset counts from the end, and a negative number of parts leaves out parts at the end.
+        let start = match offset {
+            0 => 0,
+            offset if offset > 0 => (offset - 1).min(length),
+            offset => (length + offset).max(0),
+        };
+        let end = match parts {
+            0 => length,
+            parts if parts > 0 => (start + parts).min(length),
+            parts => (length + parts).max(start),
+        };
+        segments[start as usize..end as usize].join("/")
+    }
+}
+
+/// Formats an error from `#expr` the same way as Mediawiki, as escaped text in a `strong` element with the class `error`.
+fn error_message(error: &::ExprError) -> String {
+    let mut output = "<strong class=\"error\">".to_string();
+    for character in error.to_string().chars() {
+        match character {
+            '"' => output += "&quot;",
+            '&' => output += "&amp;",
+            '\'' => output += "&#039;",
+            '<' => output += "&lt;",
+            '>' => output += "&gt;",
+            _ => output.push(character),
+        }
+    }
+    output += "</strong>";
+    output
+}
+
+/// Whether the text contains an error message from a parser function, in the same way as tested by `#iferror`.
+fn is_error(text: &str) -> bool {
+    let is_space =
+        |character: char| matches!(character, ' ' | '\t' | '\n' | '\r' | '\u{B}' | '\u{C}');
+    for (position, _) in text.match_indices('<') {
+        let rest = &text[position + 1..];
+        let rest = match ["strong", "span", "p", "div"]
+            .iter()
+            .find(|name| rest.starts_with(*name))
+        {
+            Some(name) => &rest[name.len()..],
+            None => continue,
+        };
+        if !rest.starts_with(is_space) {
+            continue;
+        }
+        let tag = match rest.find('>') {
+            Some(end) => &rest[..end],
+            None => rest,
+        };
+        // The class attribute must follow whitespace and contain the word `error`.
+        let mut search_position = 0;
+        while let Some(class_position) = tag[search_position..].find("class=\"") {
+            let class_position = search_position + class_position;
+            search_position = class_position + 1;
+            if !tag[..class_position].ends_with(is_space) {
+                continue;
+            }
+            let value = &tag[class_position + 7..];
+            if let Some(end) = value.find('"') {
+                if value[..end].split(is_space).any(|word| word == "error") {
+                    return true;
+                }
+            }
+        }
+    }
+    false
+}
+
+/// Compares two texts the same way as `==` in PHP, as numbers if both are numeric, otherwise as text.
+pub fn loose_equals(left: &str, right: &str) -> bool {
+    match (php_number(left), php_number(right)) {
+        (Some(left), Some(right)) => left == right,
+        _ => left == right,
+    }
+}
+
+/// Converts a text to an integer the same way as `intval` in PHP, from the leading digits of the text.
+pub fn php_int(text: &str) -> i64 {
+    let text = text.trim_start();
+    let (negative, digits) = match text.as_bytes().first() {
+        Some(b'-') => (true, &text[1..]),
+        Some(b'+') => (false, &text[1..]),
+        _ => (false, text),
+    };
+    let mut value: i64 = 0;
+    for digit in digits.bytes().take_while(u8::is_ascii_digit) {
+        value = value
+            .saturating_mul(10)
+            .saturating_add((digit - b'0') as i64);
+    }
+    if negative {
+        -value
+    } else {
+        value
+    }
+}
+
+/// Converts a text to a number if it's a numeric string as defined by PHP.
+pub fn php_number(text: &str) -> Option<f64> {
+    let text = text.trim_matches(|character| {
+        matches!(character, ' ' | '\t' | '\n' | '\r' | '\u{B}' | '\u{C}')
+    });
+    let bytes = text.as_bytes();
+    let mut position = 0;
+    if let Some(b'+') | Some(b'-') = bytes.first() {
+        position += 1;
+    }
+    let integer_digits = bytes[position..]
+        .iter()
+        .take_while(|byte| byte.is_ascii_digit())
+        .count();
+    position += integer_digits;
+    let mut fraction_digits = 0;
+    if bytes.get(position) == Some(&b'.') {
+        position += 1;
+        fraction_digits = bytes[position..]
+            .iter()
+            .take_while(|byte| byte.is_ascii_digit())
+            .count();
+        position += fraction_digits;
+    }
+    if integer_digits + fraction_digits == 0 {
+        return None;
+    }
+    if let Some(b'E') | Some(b'e') = bytes.get(position) {
+        let mut exponent_position = position + 1;
+        if let Some(b'+') | Some(b'-') = bytes.get(exponent_position) {
+            exponent_position += 1;
+        }
+        let exponent_digits = bytes[exponent_position..]
+            .iter()
+            .take_while(|byte| byte.is_ascii_digit())
+            .count();
+        if exponent_digits > 0 {
+            position = exponent_position + exponent_digits;
+        }
+    }
+    if position < bytes.len() {
+        return None;
+    }
+    text.parse().ok()
+}
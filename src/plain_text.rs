@@ -0,0 +1,180 @@
+// Copyright 2018 Fredrik Portström <https://portstrom.com>
+// This is free software distributed under the terms specified in
+// the file LICENSE at the top-level directory of this distribution.
+
+/// Profile telling what to keep when converting parsed nodes to plain text.
+///
+/// The default profile gives prose suitable for natural language processing: references and tables are stripped and templates are dropped.
+#[derive(Clone, Copy, Default)]
+pub struct PlainTextProfile<'a> {
+    /// Whether to keep the content of `ref` extension tags. When false, references are stripped.
+    pub keep_references: bool,
+
+    /// Whether to keep the text of tables, with each caption and row on its own line and the cells of a row separated by tab characters. When false, tables are stripped.
+    pub keep_tables: bool,
+
+    /// Function that converts a template node to plain text. When `None`, templates are dropped.
+    pub template: Option<&'a dyn Fn(&::Node) -> String>,
+}
+
+struct Converter<'a, 'b: 'a> {
+    list_depth: usize,
+    output: String,
+    profile: &'a PlainTextProfile<'b>,
+}
+
+/// Converts parsed nodes to readable plain text.
+///
+/// Links are replaced by the text displayed for them, including any link trail. Categories, comments, images and magic words are removed, as is markup for formatting. Paragraphs are separated by an empty line, and headings, list items, definition list items and lines of preformatted text are put on lines of their own. What to do with templates, tables and references is determined by the profile.
+#[must_use]
+pub fn to_plain_text(nodes: &[::Node], profile: &PlainTextProfile) -> String {
+    let mut converter = Converter {
+        list_depth: 0,
+        output: String::new(),
+        profile,
+    };
+    converter.write_nodes(nodes);
+    let mut output = String::with_capacity(converter.output.len());
+    let mut line_break_count = 0;
+    for line in converter.output.trim().split('\n') {
+        let line = line.trim();
+        if line.is_empty() {
+            line_break_count += 1;
+            continue;
+        }
+        if !output.is_empty() {
+            output += if line_break_count > 0 { "\n\n" } else { "\n" };
+        }
+        line_break_count = 0;
+        output += line;
+    }
+    output
+}
+
+impl<'a, 'b> Converter<'a, 'b> {
+    fn line_break(&mut self, count: usize) {
+        let length = self.output.trim_end().len();
+        if length == 0 {
+            return;
+        }
+        let existing_count = self.output[length..].matches('\n').count();
+        self.output.truncate(length);
+        for _ in 0..count.max(existing_count) {
+            self.output.push('\n');
+        }
+    }
+
+    fn write_list_items<'c, I: IntoIterator<Item = &'c [::Node<'c>]>>(&mut self, items: I) {
+        // Nested lists are not separated from the surrounding list items by empty lines.
+        let line_break_count = if self.list_depth == 0 { 2 } else { 1 };
+        self.line_break(line_break_count);
+        self.list_depth += 1;
+        for nodes in items {
+            self.line_break(1);
+            self.write_nodes(nodes);
+        }
+        self.list_depth -= 1;
+        self.line_break(line_break_count);
+    }
+
+    fn write_node(&mut self, node: &::Node) {
+        match node {
+            ::Node::Bold { .. }
+            | ::Node::BoldItalic { .. }
+            | ::Node::Category { .. }
+            | ::Node::Comment { .. }
+            | ::Node::EndTag { .. }
+            | ::Node::Image { .. }
+            | ::Node::Italic { .. }
+            | ::Node::LanguageLink { .. }
+            | ::Node::MagicWord { .. }
+            | ::Node::Redirect { .. } => {}
+            ::Node::CharacterEntity { character, .. } => self.output.push(*character),
+            ::Node::DefinitionList { items, .. } => {
+                self.write_list_items(items.iter().map(|item| &item.nodes as &[_]))
+            }
+            ::Node::ExternalLink { nodes, .. } => match nodes.split_first() {
+                Some((::Node::Text { value, .. }, nodes)) => {
+                    if let Some(position) = value.find(' ') {
+                        self.output += &value[position + 1..];
+                    }
+                    self.write_nodes(nodes);
+                }
+                _ => self.write_nodes(nodes),
+            },
+            ::Node::FreeExternalLink { url, .. } => self.output += url,
+            ::Node::Heading { nodes, .. } => {
+                self.line_break(2);
+                self.write_nodes(nodes);
+                self.line_break(2);
+            }
+            ::Node::HorizontalDivider { .. } | ::Node::ParagraphBreak { .. } => {
+                self.line_break(2)
+            }
+            ::Node::InterwikiLink { text, .. } => self.write_nodes(text),
+            ::Node::Link { target, text, .. } => if text.is_empty() {
+                self.output += target;
+            } else {
+                self.write_nodes(text);
+            },
+            ::Node::OrderedList { items, .. } | ::Node::UnorderedList { items, .. } => {
+                self.write_list_items(items.iter().map(|item| &item.nodes as &[_]))
+            }
+            ::Node::Parameter { default, .. } => if let Some(default) = default {
+                self.write_nodes(default);
+            },
+            ::Node::Preformatted { nodes, .. } => {
+                self.line_break(2);
+                self.write_nodes(nodes);
+                self.line_break(2);
+            }
+            ::Node::StartTag { name, .. } => if name == "br" {
+                self.line_break(1);
+            },
+            ::Node::Table { captions, rows, .. } => if self.profile.keep_tables {
+                self.line_break(2);
+                for caption in captions {
+                    self.line_break(1);
+                    self.write_nodes(&caption.content);
+                }
+                for row in rows {
+                    self.line_break(1);
+                    for (index, cell) in row.cells.iter().enumerate() {
+                        if index > 0 {
+                            self.output.push('\t');
+                        }
+                        let start = self.output.len();
+                        self.write_nodes(&cell.content);
+                        let cell_text = self.output[start..]
+                            .split_whitespace()
+                            .collect::<Vec<_>>()
+                            .join(" ");
+                        self.output.truncate(start);
+                        self.output += &cell_text;
+                    }
+                }
+                self.line_break(2);
+            },
+            ::Node::Tag { name, nodes, .. } => match name.as_ref() {
+                "nowiki" | "poem" => self.write_nodes(nodes),
+                "pre" => {
+                    self.line_break(2);
+                    self.write_nodes(nodes);
+                    self.line_break(2);
+                }
+                "ref" if self.profile.keep_references => self.write_nodes(nodes),
+                _ => {}
+            },
+            ::Node::Template { .. } => if let Some(template) = self.profile.template {
+                self.output += &template(node);
+            },
+            ::Node::Text { value, .. } => self.output += value,
+        }
+    }
+
+    fn write_nodes(&mut self, nodes: &[::Node]) {
+        for node in nodes {
+            self.write_node(node);
+        }
+    }
+}
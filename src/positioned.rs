@@ -16,6 +16,8 @@ macro_rules! impl_positioned {
     };
 }
 
+impl_positioned!(Attribute);
+impl_positioned!(AttributeValue);
 impl_positioned!(DefinitionListItem);
 impl_positioned!(ListItem);
 impl_positioned!(Parameter);
@@ -34,10 +36,13 @@ impl<'a> ::Positioned for ::Node<'a> {
             ::Node::DefinitionList { end, .. } => end,
             ::Node::EndTag { end, .. } => end,
             ::Node::ExternalLink { end, .. } => end,
+            ::Node::FreeExternalLink { end, .. } => end,
             ::Node::Heading { end, .. } => end,
             ::Node::HorizontalDivider { end, .. } => end,
             ::Node::Image { end, .. } => end,
+            ::Node::InterwikiLink { end, .. } => end,
             ::Node::Italic { end, .. } => end,
+            ::Node::LanguageLink { end, .. } => end,
             ::Node::Link { end, .. } => end,
             ::Node::MagicWord { end, .. } => end,
             ::Node::OrderedList { end, .. } => end,
@@ -64,10 +69,13 @@ impl<'a> ::Positioned for ::Node<'a> {
             ::Node::DefinitionList { start, .. } => start,
             ::Node::EndTag { start, .. } => start,
             ::Node::ExternalLink { start, .. } => start,
+            ::Node::FreeExternalLink { start, .. } => start,
             ::Node::Heading { start, .. } => start,
             ::Node::HorizontalDivider { start, .. } => start,
             ::Node::Image { start, .. } => start,
+            ::Node::InterwikiLink { start, .. } => start,
             ::Node::Italic { start, .. } => start,
+            ::Node::LanguageLink { start, .. } => start,
             ::Node::Link { start, .. } => start,
             ::Node::MagicWord { start, .. } => start,
             ::Node::OrderedList { start, .. } => start,
@@ -84,3 +92,13 @@ impl<'a> ::Positioned for ::Node<'a> {
         }
     }
 }
+
+impl ::Positioned for ::Warning {
+    fn end(&self) -> usize {
+        self.end
+    }
+
+    fn start(&self) -> usize {
+        self.start
+    }
+}
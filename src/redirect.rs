@@ -63,10 +63,10 @@ pub fn parse_redirect(state: &mut ::State, configuration: &::Configuration, star
     }
     if state.get_byte(position + 1) == Some(b']') {
         position += 2;
-        state.nodes.push(::Node::Redirect {
+        state.push_node(::Node::Redirect {
             end: position,
             start: start_position,
-            target: &state.wiki_text[target_start_position..target_end_position],
+            target: ::Cow::Borrowed(&state.wiki_text[target_start_position..target_end_position]),
         });
         state.flushed_position = state.skip_whitespace_forwards(position);
         state.scan_position = state.flushed_position;
@@ -0,0 +1,564 @@
+// Copyright 2018 Fredrik Portström <https://portstrom.com>
+// This is free software distributed under the terms specified in
+// the file LICENSE at the top-level directory of this distribution.
+
+use std::{cell::Cell, ops::Range};
+use Positioned;
+
+impl ::Configuration {
+    /// Parses wiki text that was made by replacing a range of previously parsed wiki text, reusing the previous output for the parts of the text that are not affected by the change.
+    ///
+    /// `previous_output` must be the output of parsing `previous_wiki_text`, and `replaced_range` is the byte range in `previous_wiki_text` that was replaced to make `wiki_text`. The result is the same as from parsing `wiki_text` with `parse`.
+    ///
+    /// Parsing starts at the last paragraph break before the change, and stops at the first paragraph break after the change where the parser is back in the same state as when parsing the previous wiki text. When the change affects the structure of the text further away, for example by opening a table or template that is closed much later, more of the text is parsed, up to all of it. When the range doesn't describe how the previous wiki text was changed into the new one, the whole text is parsed.
+    #[must_use]
+    pub fn reparse<'a>(
+        &self,
+        previous_output: ::Output,
+        previous_wiki_text: &str,
+        replaced_range: Range<usize>,
+        wiki_text: &'a str,
+    ) -> ::Output<'a> {
+        let suffix_length = match previous_wiki_text.len().checked_sub(replaced_range.end) {
+            None => return self.parse(wiki_text),
+            Some(suffix_length) => suffix_length,
+        };
+        if replaced_range.start > replaced_range.end
+            || replaced_range.start + suffix_length > wiki_text.len()
+            || previous_wiki_text.as_bytes()[..replaced_range.start]
+                != wiki_text.as_bytes()[..replaced_range.start]
+            || previous_wiki_text.as_bytes()[replaced_range.end..]
+                != wiki_text.as_bytes()[wiki_text.len() - suffix_length..]
+        {
+            return self.parse(wiki_text);
+        }
+        let ::Output {
+            nodes: previous_nodes,
+            warnings: previous_warnings,
+        } = previous_output;
+        let synchronization_indexes = find_synchronization_indexes(
+            &previous_nodes,
+            &previous_warnings,
+            previous_wiki_text,
+        );
+        let left_index = synchronization_indexes
+            .iter()
+            .cloned()
+            .rev()
+            .find(|index| {
+                let end = previous_nodes[*index].end();
+                let line_end = previous_wiki_text[end..]
+                    .find('\n')
+                    .map_or(previous_wiki_text.len(), |position| end + position);
+                line_end < replaced_range.start
+            });
+        let changed_end = wiki_text.len() - suffix_length;
+        let right_candidates = synchronization_indexes
+            .iter()
+            .cloned()
+            .filter(|index| previous_nodes[*index].start() >= replaced_range.end)
+            .map(|index| {
+                let node = &previous_nodes[index];
+                (
+                    node.start() - replaced_range.end + changed_end,
+                    node.end() - replaced_range.end + changed_end,
+                    index,
+                )
+            })
+            .collect::<Vec<_>>();
+        let start_position = left_index.map_or(0, |index| previous_nodes[index].end());
+        let mut state = ::State {
+            flushed_position: start_position,
+            is_only_include: false,
+            mode: ::TransclusionMode::View,
+            node_count: 0,
+            nodes: vec![],
+            open_link_count: 0,
+            scan_position: start_position,
+            stack: vec![],
+            warnings: vec![],
+            wiki_text,
+        };
+        if left_index.is_none() {
+            ::parse::parse_beginning_of_text(self, &mut state);
+        }
+        let mut right_index = None;
+        let stopped = ::parse::parse_until(self, &::ParseLimits::default(), &mut state, |state| {
+            if !state.stack.is_empty() || state.flushed_position != state.scan_position {
+                return false;
+            }
+            if let Some(::Node::ParagraphBreak { end, start }) = state.nodes.last() {
+                if *end == state.scan_position {
+                    if let Ok(candidate_index) = right_candidates
+                        .binary_search_by_key(&(*start, *end), |&(start, end, _)| (start, end))
+                    {
+                        right_index = Some(right_candidates[candidate_index].2);
+                        return true;
+                    }
+                }
+            }
+            false
+        });
+        if !stopped {
+            let end_position = state.skip_whitespace_backwards(wiki_text.len());
+            state.flush(end_position);
+        }
+        // Only the positions after the replaced range are shifted, since the reused nodes and warnings before it are before the replaced range in both texts.
+        let rebase = Rebase {
+            added: changed_end,
+            depth: Cell::new(0),
+            previous_wiki_text,
+            removed: replaced_range.end,
+            wiki_text,
+        };
+        let reused_count = left_index.map_or(0, |index| index + 1);
+        let mut previous_nodes = previous_nodes.into_vec();
+        // The nodes that were parsed again are dropped without recursion.
+        drop(::Nodes::from(
+            previous_nodes
+                .drain(reused_count..right_index.map_or(previous_nodes.len(), |index| index + 1))
+                .collect::<Vec<_>>(),
+        ));
+        let mut nodes = rebase.nodes(previous_nodes);
+        nodes.splice(reused_count..reused_count, state.nodes);
+        let mut warnings = vec![];
+        if left_index.is_some() {
+            warnings.extend(
+                previous_warnings
+                    .iter()
+                    .filter(|warning| warning.start < start_position)
+                    .map(|warning| rebase.warning(warning)),
+            );
+        }
+        warnings.append(&mut state.warnings);
+        if right_index.is_some() {
+            let resume_position = state.scan_position - changed_end + replaced_range.end;
+            warnings.extend(
+                previous_warnings
+                    .iter()
+                    .filter(|warning| warning.start >= resume_position)
+                    .map(|warning| rebase.warning(warning)),
+            );
+        }
+        ::Output {
+            nodes: nodes.into(),
+            warnings,
+        }
+    }
+}
+
+/// The number of levels of nesting of nodes converted by recursion when reusing nodes.
+const MAX_RECURSION_DEPTH: usize = 100;
+
+struct Rebase<'a, 'b> {
+    added: usize,
+    depth: Cell<usize>,
+    previous_wiki_text: &'b str,
+    removed: usize,
+    wiki_text: &'a str,
+}
+
+impl<'a, 'b> Rebase<'a, 'b> {
+    fn attributes(&self, attributes: Vec<::Attribute>) -> Vec<::Attribute<'a>> {
+        attributes
+            .into_iter()
+            .map(|attribute| ::Attribute {
+                end: self.position(attribute.end),
+                name: self.text(attribute.name),
+                start: self.position(attribute.start),
+                value: attribute.value.map(|value| ::AttributeValue {
+                    end: self.position(value.end),
+                    quote: value.quote,
+                    start: self.position(value.start),
+                    value: self.text(value.value),
+                }),
+            })
+            .collect()
+    }
+
+    fn list_items(&self, items: Vec<::ListItem>) -> Vec<::ListItem<'a>> {
+        items
+            .into_iter()
+            .map(|item| ::ListItem {
+                end: self.position(item.end),
+                nodes: self.nodes(item.nodes),
+                start: self.position(item.start),
+            })
+            .collect()
+    }
+
+    fn node(&self, node: ::Node) -> ::Node<'a> {
+        match node {
+            ::Node::Bold { end, start } => ::Node::Bold {
+                end: self.position(end),
+                start: self.position(start),
+            },
+            ::Node::BoldItalic { end, start } => ::Node::BoldItalic {
+                end: self.position(end),
+                start: self.position(start),
+            },
+            ::Node::Category {
+                end,
+                ordinal,
+                start,
+                target,
+            } => ::Node::Category {
+                end: self.position(end),
+                ordinal: self.nodes(ordinal),
+                start: self.position(start),
+                target: self.text(target),
+            },
+            ::Node::CharacterEntity {
+                character,
+                end,
+                start,
+            } => ::Node::CharacterEntity {
+                character,
+                end: self.position(end),
+                start: self.position(start),
+            },
+            ::Node::Comment { end, start } => ::Node::Comment {
+                end: self.position(end),
+                start: self.position(start),
+            },
+            ::Node::DefinitionList { end, items, start } => ::Node::DefinitionList {
+                end: self.position(end),
+                items: items
+                    .into_iter()
+                    .map(|item| ::DefinitionListItem {
+                        end: self.position(item.end),
+                        nodes: self.nodes(item.nodes),
+                        start: self.position(item.start),
+                        type_: item.type_,
+                    })
+                    .collect(),
+                start: self.position(start),
+            },
+            ::Node::EndTag { end, name, start } => ::Node::EndTag {
+                end: self.position(end),
+                name: self.text(name),
+                start: self.position(start),
+            },
+            ::Node::ExternalLink { end, nodes, start } => ::Node::ExternalLink {
+                end: self.position(end),
+                nodes: self.nodes(nodes),
+                start: self.position(start),
+            },
+            ::Node::FreeExternalLink { end, start, url } => ::Node::FreeExternalLink {
+                end: self.position(end),
+                start: self.position(start),
+                url: self.text(url),
+            },
+            ::Node::Heading {
+                end,
+                level,
+                nodes,
+                start,
+            } => ::Node::Heading {
+                end: self.position(end),
+                level,
+                nodes: self.nodes(nodes),
+                start: self.position(start),
+            },
+            ::Node::HorizontalDivider { end, start } => ::Node::HorizontalDivider {
+                end: self.position(end),
+                start: self.position(start),
+            },
+            ::Node::Image {
+                end,
+                start,
+                target,
+                text,
+            } => ::Node::Image {
+                end: self.position(end),
+                start: self.position(start),
+                target: self.text(target),
+                text: self.nodes(text),
+            },
+            ::Node::InterwikiLink {
+                end,
+                prefix,
+                start,
+                target,
+                text,
+            } => ::Node::InterwikiLink {
+                end: self.position(end),
+                prefix: self.text(prefix),
+                start: self.position(start),
+                target: self.text(target),
+                text: self.nodes(text),
+            },
+            ::Node::Italic { end, start } => ::Node::Italic {
+                end: self.position(end),
+                start: self.position(start),
+            },
+            ::Node::LanguageLink {
+                end,
+                prefix,
+                start,
+                target,
+                text,
+            } => ::Node::LanguageLink {
+                end: self.position(end),
+                prefix: self.text(prefix),
+                start: self.position(start),
+                target: self.text(target),
+                text: self.nodes(text),
+            },
+            ::Node::Link {
+                end,
+                start,
+                target,
+                text,
+            } => ::Node::Link {
+                end: self.position(end),
+                start: self.position(start),
+                target: self.text(target),
+                text: self.nodes(text),
+            },
+            ::Node::MagicWord { end, start } => ::Node::MagicWord {
+                end: self.position(end),
+                start: self.position(start),
+            },
+            ::Node::OrderedList { end, items, start } => ::Node::OrderedList {
+                end: self.position(end),
+                items: self.list_items(items),
+                start: self.position(start),
+            },
+            ::Node::ParagraphBreak { end, start } => ::Node::ParagraphBreak {
+                end: self.position(end),
+                start: self.position(start),
+            },
+            ::Node::Parameter {
+                default,
+                end,
+                name,
+                start,
+            } => ::Node::Parameter {
+                default: default.map(|default| self.nodes(default)),
+                end: self.position(end),
+                name: self.nodes(name),
+                start: self.position(start),
+            },
+            ::Node::Preformatted { end, nodes, start } => ::Node::Preformatted {
+                end: self.position(end),
+                nodes: self.nodes(nodes),
+                start: self.position(start),
+            },
+            ::Node::Redirect { end, target, start } => ::Node::Redirect {
+                end: self.position(end),
+                target: self.text(target),
+                start: self.position(start),
+            },
+            ::Node::StartTag {
+                attributes,
+                end,
+                name,
+                self_closing,
+                start,
+            } => ::Node::StartTag {
+                attributes: self.attributes(attributes),
+                end: self.position(end),
+                name: self.text(name),
+                self_closing,
+                start: self.position(start),
+            },
+            ::Node::Table {
+                attributes,
+                captions,
+                end,
+                parsed_attributes,
+                rows,
+                start,
+            } => ::Node::Table {
+                attributes: self.nodes(attributes),
+                captions: captions
+                    .into_iter()
+                    .map(|caption| ::TableCaption {
+                        attributes: caption.attributes.map(|nodes| self.nodes(nodes)),
+                        content: self.nodes(caption.content),
+                        end: self.position(caption.end),
+                        parsed_attributes: self.attributes(caption.parsed_attributes),
+                        start: self.position(caption.start),
+                    })
+                    .collect(),
+                end: self.position(end),
+                parsed_attributes: self.attributes(parsed_attributes),
+                rows: rows
+                    .into_iter()
+                    .map(|row| ::TableRow {
+                        attributes: self.nodes(row.attributes),
+                        cells: row
+                            .cells
+                            .into_iter()
+                            .map(|cell| ::TableCell {
+                                attributes: cell.attributes.map(|nodes| self.nodes(nodes)),
+                                content: self.nodes(cell.content),
+                                end: self.position(cell.end),
+                                parsed_attributes: self.attributes(cell.parsed_attributes),
+                                start: self.position(cell.start),
+                                type_: cell.type_,
+                            })
+                            .collect(),
+                        end: self.position(row.end),
+                        parsed_attributes: self.attributes(row.parsed_attributes),
+                        start: self.position(row.start),
+                    })
+                    .collect(),
+                start: self.position(start),
+            },
+            ::Node::Tag {
+                attributes,
+                end,
+                name,
+                nodes,
+                start,
+            } => ::Node::Tag {
+                attributes: self.attributes(attributes),
+                end: self.position(end),
+                name: self.text(name),
+                nodes: self.nodes(nodes),
+                start: self.position(start),
+            },
+            ::Node::Template {
+                end,
+                name,
+                parameters,
+                start,
+            } => ::Node::Template {
+                end: self.position(end),
+                name: self.nodes(name),
+                parameters: parameters
+                    .into_iter()
+                    .map(|parameter| ::Parameter {
+                        end: self.position(parameter.end),
+                        name: parameter.name.map(|nodes| self.nodes(nodes)),
+                        start: self.position(parameter.start),
+                        value: self.nodes(parameter.value),
+                    })
+                    .collect(),
+                start: self.position(start),
+            },
+            ::Node::Text { end, start, value } => ::Node::Text {
+                end: self.position(end),
+                start: self.position(start),
+                value: self.text(value),
+            },
+            ::Node::UnorderedList { end, items, start } => ::Node::UnorderedList {
+                end: self.position(end),
+                items: self.list_items(items),
+                start: self.position(start),
+            },
+        }
+    }
+
+    fn nodes(&self, nodes: Vec<::Node>) -> Vec<::Node<'a>> {
+        // Nested nodes are converted by recursion, which is the fastest, except at deeper levels of nesting, where they are converted with an explicit stack instead so that the stack doesn't overflow.
+        let depth = self.depth.get();
+        if depth >= MAX_RECURSION_DEPTH {
+            return ::owned::convert_nodes(nodes, |node| self.node(node));
+        }
+        self.depth.set(depth + 1);
+        let nodes = nodes.into_iter().map(|node| self.node(node)).collect();
+        self.depth.set(depth);
+        nodes
+    }
+
+    fn position(&self, position: usize) -> usize {
+        if position < self.removed {
+            position
+        } else {
+            position + self.added - self.removed
+        }
+    }
+
+    fn text(&self, text: ::Cow<str>) -> ::Cow<'a, str> {
+        if let ::Cow::Borrowed(slice) = text {
+            // Text borrowed from the previous wiki text is borrowed from the same position in the new wiki text.
+            let base_address = self.previous_wiki_text.as_ptr() as usize;
+            let address = slice.as_ptr() as usize;
+            if address >= base_address
+                && address + slice.len() <= base_address + self.previous_wiki_text.len()
+            {
+                let start = self.position(address - base_address);
+                if let Some(slice) = self.wiki_text.get(start..start + slice.len()) {
+                    return ::Cow::Borrowed(slice);
+                }
+            }
+        }
+        ::Cow::Owned(text.into_owned())
+    }
+
+    fn warning(&self, warning: &::Warning) -> ::Warning {
+        ::Warning {
+            // A warning about the text after a redirect covers the rest of the text.
+            end: if warning.end == self.previous_wiki_text.len() {
+                self.wiki_text.len()
+            } else {
+                self.position(warning.end)
+            },
+            message: warning.message,
+            start: self.position(warning.start),
+        }
+    }
+}
+
+fn find_synchronization_indexes(
+    nodes: &[::Node],
+    warnings: &[::Warning],
+    wiki_text: &str,
+) -> Vec<usize> {
+    // A paragraph break is a point where the parser has no open nodes, unless it's among the nodes put before a table, which come before the table node but have positions within the table. It's also not a point to start or stop at if the parser got there by rewinding to a node that was open at that point, or if it looked for the end of a tag past that point without finding it, since then the text after the point affects the nodes before it.
+    let mut looked_past_ranges = warnings
+        .iter()
+        .filter_map(|warning| looked_past_range(warning, wiki_text))
+        .collect::<Vec<_>>();
+    looked_past_ranges.sort_by_key(|range| range.start);
+    // The ranges are merged so that they are sorted by both start and end, for finding the range covering a position by binary search.
+    let mut merged_ranges: Vec<Range<usize>> = vec![];
+    for range in looked_past_ranges {
+        match merged_ranges.last_mut() {
+            Some(last) if range.start <= last.end => last.end = last.end.max(range.end),
+            _ => merged_ranges.push(range),
+        }
+    }
+    let mut indexes = vec![];
+    let mut minimum_start = usize::MAX;
+    for (index, node) in nodes.iter().enumerate().rev() {
+        if let ::Node::ParagraphBreak { end, .. } = node {
+            let range_index = merged_ranges.partition_point(|range| range.end <= *end);
+            if *end <= minimum_start
+                && merged_ranges
+                    .get(range_index)
+                    .map_or(true, |range| range.start > *end)
+            {
+                indexes.push(index);
+            }
+        }
+        minimum_start = minimum_start.min(node.start());
+    }
+    indexes.reverse();
+    indexes
+}
+
+/// Returns the range of positions that the parser looked past when it gave the warning.
+fn looked_past_range(warning: &::Warning, wiki_text: &str) -> Option<Range<usize>> {
+    let text = &wiki_text.as_bytes()[warning.start..];
+    // The warning starts at the node that was rewound to, except when it starts at the end tag that caused the rewinding, in which case the node could be anywhere before.
+    let is_at_end_tag = text.starts_with(b"</") || text.starts_with(b"}}");
+    match warning.message {
+        ::WarningMessage::InvalidTagSyntax => Some(warning.start + 1..usize::MAX),
+        ::WarningMessage::MissingEndTagRewinding if !is_at_end_tag => {
+            Some(warning.start + 1..usize::MAX)
+        }
+        ::WarningMessage::InvalidHeadingSyntaxRewinding
+        | ::WarningMessage::InvalidLinkSyntax
+        | ::WarningMessage::MissingEndTagRewinding
+        | ::WarningMessage::UnexpectedEndTagRewinding => Some(if is_at_end_tag {
+            0..warning.end + 1
+        } else {
+            warning.start + 1..warning.end + 1
+        }),
+        _ => None,
+    }
+}
@@ -0,0 +1,181 @@
+// Copyright 2018 Fredrik Portström <https://portstrom.com>
+// This is free software distributed under the terms specified in
+// the file LICENSE at the top-level directory of this distribution.
+
+use std::ops::Range;
+use Positioned;
+
+/// Section of wiki text, numbered the same way as by MediaWiki when editing a section.
+#[derive(Debug)]
+pub struct Section<'a, 'b> {
+    /// The byte position in the wiki text where the section ends, including its subsections. This is where the next heading of the same or a higher level starts, or the end of the wiki text.
+    pub end: usize,
+
+    /// The nodes of the heading of the section, or an empty slice for the lead section.
+    pub heading: &'b [::Node<'a>],
+
+    /// The number of the section, the same as in `action=edit&section=` in MediaWiki. The lead section is number 0, and the sections after it are numbered in the order their headings appear in the wiki text.
+    pub index: usize,
+
+    /// The level of the heading of the section, from 1 to 6, or 0 for the lead section.
+    pub level: u8,
+
+    /// The top-level nodes in the section after the heading and before the first subsection.
+    pub nodes: &'b [::Node<'a>],
+
+    /// The subsections, those following the section whose headings are of a lower level than the heading of the section, up to the next heading of the same or a higher level.
+    pub sections: Vec<Section<'a, 'b>>,
+
+    /// The byte position in the wiki text where the section starts. This is where the heading starts, or 0 for the lead section.
+    pub start: usize,
+}
+
+/// Groups the top-level nodes of parsed wiki text into a tree of sections.
+///
+/// The first section is the lead section, which has no heading and no subsections and ends where the first heading starts. The other sections follow, each one with its subsections. When the level of headings skips a level, the section with the lower level is still a subsection of the section before it, and a section never has a lower level than the sections it comes before in the returned list or in its parent's subsections.
+///
+/// Like in MediaWiki, headings at the start of a line in a table are sections, even though the table is a single node that can't be divided between sections. Such a section has no nodes before the end of the table. Headings in other nodes, such as templates and extension tags, aren't sections.
+#[must_use]
+pub fn sections<'a, 'b>(nodes: &'b [::Node<'a>], wiki_text: &str) -> Vec<Section<'a, 'b>> {
+    let mut headings = vec![];
+    find_headings(nodes, &mut headings);
+    let mut node_index = 0;
+    let mut body = |start: usize, end: usize| {
+        while node_index < nodes.len() && nodes[node_index].start() < start {
+            node_index += 1;
+        }
+        let body_start = node_index;
+        while node_index < nodes.len() && nodes[node_index].start() < end {
+            node_index += 1;
+        }
+        &nodes[body_start..node_index]
+    };
+    let mut sections = vec![];
+    let mut stack: Vec<Section> = vec![];
+    let lead_end = headings
+        .first()
+        .map_or(wiki_text.len(), |heading| heading.start());
+    stack.push(Section {
+        end: lead_end,
+        heading: &[],
+        index: 0,
+        level: 0,
+        nodes: body(0, lead_end),
+        sections: vec![],
+        start: 0,
+    });
+    for (heading_index, heading) in headings.iter().enumerate() {
+        if let ::Node::Heading {
+            end,
+            level,
+            nodes: heading_nodes,
+            start,
+        } = heading
+        {
+            while let Some(section) = stack.pop() {
+                if section.level != 0 && section.level < *level {
+                    stack.push(section);
+                    break;
+                }
+                close_section(&mut stack, &mut sections, section, *start);
+            }
+            let body_end = headings
+                .get(heading_index + 1)
+                .map_or(wiki_text.len(), |heading| heading.start());
+            stack.push(Section {
+                end: wiki_text.len(),
+                heading: heading_nodes,
+                index: heading_index + 1,
+                level: *level,
+                nodes: body(*end, body_end),
+                sections: vec![],
+                start: *start,
+            });
+        }
+    }
+    while let Some(section) = stack.pop() {
+        close_section(&mut stack, &mut sections, section, wiki_text.len());
+    }
+    sections
+}
+
+/// Replaces the section with the given number in the wiki text, giving the new wiki text, or `None` if there is no such section.
+///
+/// The section is numbered the same way as in `sections`, and the text that is replaced is the range given by `section_range`, including the subsections. Like when editing a section in MediaWiki, whitespace at the end of the replacement is removed, and if the replacement isn't empty and there is text after the section, the replacement is followed by an empty line to separate it from the next heading.
+#[must_use]
+pub fn replace_section(
+    nodes: &[::Node],
+    wiki_text: &str,
+    index: usize,
+    replacement: &str,
+) -> Option<String> {
+    let range = section_range(nodes, wiki_text, index)?;
+    let replacement = replacement.trim_end();
+    let mut result = String::with_capacity(wiki_text.len() - range.len() + replacement.len() + 2);
+    result.push_str(&wiki_text[..range.start]);
+    result.push_str(replacement);
+    if !replacement.is_empty() && range.end < wiki_text.len() {
+        result.push_str("\n\n");
+    }
+    result.push_str(&wiki_text[range.end..]);
+    Some(result)
+}
+
+/// Finds the byte range in the wiki text of the section with the given number, or `None` if there is no such section.
+///
+/// The section is numbered the same way as in `sections` and in `action=edit&section=` in MediaWiki, and the range is the same as the one of the section returned by `sections`, including the subsections. Section 0 is the lead section, which may be empty.
+#[must_use]
+pub fn section_range(nodes: &[::Node], wiki_text: &str, index: usize) -> Option<Range<usize>> {
+    let mut headings = vec![];
+    find_headings(nodes, &mut headings);
+    let start_level = match index.checked_sub(1) {
+        None => 0,
+        Some(heading_index) => match headings.get(heading_index)? {
+            ::Node::Heading { level, .. } => *level,
+            _ => unreachable!(),
+        },
+    };
+    let start = index
+        .checked_sub(1)
+        .map_or(0, |heading_index| headings[heading_index].start());
+    let end = headings[index..]
+        .iter()
+        .find(|heading| match heading {
+            ::Node::Heading { level, .. } => start_level == 0 || *level <= start_level,
+            _ => unreachable!(),
+        })
+        .map_or(wiki_text.len(), |heading| heading.start());
+    Some(start..end)
+}
+
+fn close_section<'a, 'b>(
+    stack: &mut Vec<Section<'a, 'b>>,
+    sections: &mut Vec<Section<'a, 'b>>,
+    mut section: Section<'a, 'b>,
+    end: usize,
+) {
+    section.end = end;
+    match stack.last_mut() {
+        None => sections.push(section),
+        Some(parent) => parent.sections.push(section),
+    }
+}
+
+fn find_headings<'a, 'b>(nodes: &'b [::Node<'a>], headings: &mut Vec<&'b ::Node<'a>>) {
+    for node in nodes {
+        match node {
+            ::Node::Heading { .. } => headings.push(node),
+            ::Node::Table { captions, rows, .. } => {
+                for caption in captions {
+                    find_headings(&caption.content, headings);
+                }
+                for row in rows {
+                    for cell in &row.cells {
+                        find_headings(&cell.content, headings);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,840 @@
+// Copyright 2018 Fredrik Portström <https://portstrom.com>
+// This is free software distributed under the terms specified in
+// the file LICENSE at the top-level directory of this distribution.
+
+use std::mem::discriminant;
+use Positioned;
+
+type Index<'a, 'b> = ::HashMap<(usize, usize, ::NodeKind), (&'b ::Node<'a>, usize, usize)>;
+
+/// Unfinished writing of a node or a sequence of nodes.
+enum Frame<'n, 'm: 'n> {
+    Sequence(Sequence<'n, 'm>),
+    Steps(::std::vec::IntoIter<Step<'n, 'm>>),
+}
+
+#[derive(Clone, Copy)]
+struct PreviousNode {
+    end: usize,
+    is_block: bool,
+    node_match: Option<(usize, usize)>,
+}
+
+/// Sequence of nodes that is being written.
+struct Sequence<'n, 'm: 'n> {
+    index: usize,
+    node_matches: Vec<Option<(usize, usize)>>,
+    nodes: &'n [::Node<'m>],
+    previous: Option<PreviousNode>,
+}
+
+struct Serializer<'a, 'b, 'c> {
+    index: Index<'a, 'b>,
+    list_prefix: String,
+    matches: ::HashMap<usize, (&'b ::Node<'a>, usize, usize)>,
+    output: String,
+    saved_outputs: Vec<String>,
+    sequences: Vec<&'b [::Node<'a>]>,
+    wiki_text: &'c str,
+}
+
+/// Step in writing a node in canonical syntax.
+enum Step<'n, 'm: 'n> {
+    Attributes(&'n [::Attribute<'m>]),
+    EndListItem,
+    EndPreformatted,
+    LineBreak(usize),
+    Nodes(&'n [::Node<'m>]),
+    StartListItem(char),
+    StartPreformatted,
+    Text(::Cow<'n, str>),
+}
+
+impl ::Configuration {
+    /// Serializes nodes back to wiki text.
+    ///
+    /// The wiki text and the nodes must be the wiki text and nodes of an output from parsing with the same configuration, possibly with modifications made to the nodes. Nodes that are unchanged, meaning equal to the node of the same type at the same position in the output of parsing the wiki text, are copied byte for byte from the wiki text, together with the text between unchanged nodes that were adjacent in the original wiki text. Nodes that have been added or modified are written in canonical syntax, so serializing unmodified nodes gives the original wiki text.
+    ///
+    /// The attributes of tables, table captions, table rows and table cells are written in canonical syntax from their `parsed_attributes`, so code among the attributes that isn't an attribute, such as a template, is left out.
+    ///
+    /// Comments and magic words don't contain their text, so when one of them is written in canonical syntax, its text is taken from the wiki text at its position if possible.
+    #[must_use]
+    pub fn serialize(&self, wiki_text: &str, nodes: &[::Node]) -> String {
+        let original = self.parse(wiki_text);
+        let mut serializer = Serializer {
+            index: ::HashMap::new(),
+            list_prefix: String::new(),
+            matches: ::HashMap::new(),
+            output: String::new(),
+            saved_outputs: vec![],
+            sequences: vec![],
+            wiki_text,
+        };
+        index_nodes(&mut serializer, &original.nodes);
+        serializer.match_nodes(nodes);
+        if nodes.len() == original.nodes.len()
+            && nodes
+                .iter()
+                .zip(original.nodes.iter())
+                .all(|(node, original_node)| serializer.is_match(node, original_node))
+        {
+            return wiki_text.to_string();
+        }
+        if let Some(first_node) = nodes.first() {
+            if serializer.is_successor(None, first_node) {
+                let start = nodes
+                    .iter()
+                    .take_while(|node| node.start() < first_node.end())
+                    .map(|node| serializer.span(node).0)
+                    .min()
+                    .unwrap();
+                serializer.output += serializer.source(0, start);
+            }
+        }
+        serializer.write_nodes(nodes);
+        if let (Some(last_node), Some(original_last_node)) = (nodes.last(), original.nodes.last()) {
+            if last_node.start() == original_last_node.start()
+                && discriminant(last_node) == discriminant(original_last_node)
+            {
+                let end = serializer.span(original_last_node).1;
+                serializer.output += serializer.source(end, wiki_text.len());
+            }
+        }
+        serializer.output
+    }
+}
+
+impl<'a, 'b, 'c> Serializer<'a, 'b, 'c> {
+    /// Finds the original node that the node was parsed as, if the node is unchanged.
+    fn find(&self, node: &::Node) -> Option<(usize, usize)> {
+        self.matches
+            .get(&address(node))
+            .map(|(_, sequence, position)| (*sequence, *position))
+    }
+
+    fn is_match(&self, node: &::Node, original_node: &::Node) -> bool {
+        match self.matches.get(&address(node)) {
+            Some((matched_node, ..)) => ::std::ptr::eq(*matched_node, original_node),
+            None => false,
+        }
+    }
+
+    fn is_successor(&self, previous_match: Option<(usize, usize)>, node: &::Node) -> bool {
+        let (sequence, position) = match previous_match {
+            None => (0, 0),
+            Some((sequence, position)) => (sequence, position + 1),
+        };
+        match self.sequences[sequence].get(position) {
+            None => false,
+            Some(original_node) => {
+                original_node.start() == node.start()
+                    && discriminant(original_node) == discriminant(node)
+            }
+        }
+    }
+
+    fn line_break(&mut self, count: usize) {
+        if self.output.is_empty() {
+            return;
+        }
+        let existing = self.output.len() - self.output.trim_end_matches('\n').len();
+        for _ in existing..count {
+            self.output.push('\n');
+        }
+    }
+
+    /// Matches each unchanged node to the original node that it was parsed as. The original node is the one with the same position and type, so a node is never matched to another node that happens to be equal to it.
+    ///
+    /// A node is unchanged if it's equal to the original node apart from the child nodes, and each child node is matched to the child node at the same place in the original node. The nodes are matched in reverse depth first order, so the child nodes of a node are matched before the node, and comparing nested nodes doesn't recurse.
+    fn match_nodes(&mut self, nodes: &[::Node]) {
+        let nodes = ::depth_first(nodes).collect::<Vec<_>>();
+        for node in nodes.into_iter().rev() {
+            if let Some(&(original_node, sequence, position)) =
+                self.index.get(&(node.start(), node.end(), node.kind()))
+            {
+                if shallow_eq(node, original_node)
+                    && ::visit::child_sequences(node)
+                        .into_iter()
+                        .zip(::visit::child_sequences(original_node))
+                        .all(|(child_nodes, original_child_nodes)| {
+                            child_nodes.len() == original_child_nodes.len()
+                                && child_nodes
+                                    .iter()
+                                    .zip(original_child_nodes)
+                                    .all(|(child_node, original_child_node)| {
+                                        self.is_match(child_node, original_child_node)
+                                    })
+                        }) {
+                    self.matches
+                        .insert(address(node), (original_node, sequence, position));
+                }
+            }
+        }
+    }
+
+    /// Returns the next node of the sequence to write in canonical syntax, after copying the nodes before it that are unchanged from the wiki text.
+    fn next_node<'n, 'm>(&mut self, sequence: &mut Sequence<'n, 'm>) -> Option<&'n ::Node<'m>> {
+        let nodes = sequence.nodes;
+        while sequence.index < nodes.len() {
+            let node_index = sequence.index;
+            sequence.index += 1;
+            let node = &nodes[node_index];
+            let node_match = sequence.node_matches[node_index];
+            let (start, end) = self.span(node);
+            if node_match.is_some()
+                && nodes[node_index + 1..]
+                    .iter()
+                    .zip(&sequence.node_matches[node_index + 1..])
+                    .take_while(|(next_node, _)| next_node.start() < node.end())
+                    .any(|(next_node, next_match)| {
+                        next_node.start() <= node.start() && next_node.end() >= node.end()
+                            && next_match.is_some()
+                    }) {
+                // The text between the nodes is copied only when the previous node was copied from the wiki text.
+                sequence.previous = Some(match sequence.previous {
+                    None => PreviousNode {
+                        end: start,
+                        is_block: false,
+                        node_match,
+                    },
+                    Some(previous) => PreviousNode {
+                        node_match: previous.node_match.and(node_match),
+                        ..previous
+                    },
+                });
+                continue;
+            }
+            let is_block = is_block(node);
+            let is_list = is_list(node) && !self.list_prefix.is_empty();
+            // Text before a table is on its own line inside the code of the table.
+            let is_before_table = nodes[node_index + 1..].iter().any(|next_node| {
+                next_node.start() <= node.start() && next_node.end() >= node.end()
+            });
+            match sequence.previous {
+                Some(PreviousNode {
+                    end: previous_end,
+                    node_match: Some(previous_match),
+                    ..
+                }) if !is_list && previous_end <= start
+                    && self.is_successor(Some(previous_match), node)
+                    && !is_before_table =>
+                {
+                    self.output += self.source(previous_end, start);
+                }
+                Some(PreviousNode {
+                    is_block: previous_is_block,
+                    ..
+                }) => if previous_is_block || is_block || is_before_table {
+                    self.line_break(1);
+                },
+                None => if is_block {
+                    self.line_break(1);
+                },
+            }
+            sequence.previous = Some(PreviousNode {
+                end,
+                is_block,
+                node_match,
+            });
+            match node_match {
+                Some(_) if !is_list => {
+                    self.output += self.source(start, end);
+                }
+                _ => return Some(node),
+            }
+        }
+        None
+    }
+
+    /// Returns the steps in writing the node in canonical syntax.
+    fn node_steps<'n, 'm>(&self, node: &'n ::Node<'m>) -> Vec<Step<'n, 'm>> {
+        let mut steps = vec![];
+        match node {
+            ::Node::Bold { .. } => steps.push(text("'''")),
+            ::Node::BoldItalic { .. } => steps.push(text("'''''")),
+            ::Node::Category {
+                ordinal, target, ..
+            } => {
+                steps.push(text("[["));
+                steps.push(text(target));
+                if !ordinal.is_empty() {
+                    steps.push(text("|"));
+                    steps.push(Step::Nodes(ordinal));
+                }
+                steps.push(text("]]"));
+            }
+            ::Node::CharacterEntity { character, .. } => {
+                steps.push(Step::Text(
+                    match ::html_entities::HTML_ENTITIES
+                        .iter()
+                        .find(|(_, entity_character)| entity_character == character)
+                    {
+                        None => character.to_string(),
+                        Some((name, _)) => format!("&{};", name),
+                    }.into(),
+                ));
+            }
+            ::Node::Comment { end, start } => {
+                steps.push(match self.wiki_text.get(*start..*end) {
+                    Some(text) if text.starts_with("<!--") => Step::Text(text.to_string().into()),
+                    _ => self::text("<!---->"),
+                });
+            }
+            ::Node::DefinitionList { items, .. } => {
+                for (item_index, item) in items.iter().enumerate() {
+                    let marker = match item.type_ {
+                        ::DefinitionListItemType::Details => ':',
+                        ::DefinitionListItemType::Term => ';',
+                    };
+                    push_list_item(&mut steps, marker, item_index == 0, &item.nodes);
+                }
+            }
+            ::Node::EndTag { name, .. } => {
+                steps.push(text("</"));
+                steps.push(text(name));
+                steps.push(text(">"));
+            }
+            ::Node::ExternalLink { nodes, .. } => {
+                steps.push(text("["));
+                steps.push(Step::Nodes(nodes));
+                steps.push(text("]"));
+            }
+            ::Node::FreeExternalLink { url, .. } => steps.push(text(url)),
+            ::Node::Heading { level, nodes, .. } => {
+                let equals_signs = "=".repeat(*level as usize);
+                steps.push(Step::Text(equals_signs.clone().into()));
+                steps.push(Step::Nodes(nodes));
+                steps.push(Step::Text(equals_signs.into()));
+            }
+            ::Node::HorizontalDivider { .. } => steps.push(text("----")),
+            ::Node::Image { target, text, .. } | ::Node::Link { target, text, .. } => {
+                steps.push(self::text("[["));
+                steps.push(self::text(target));
+                match text.as_slice() {
+                    [::Node::Text { value, .. }] if value == target => {}
+                    _ => {
+                        steps.push(self::text("|"));
+                        // The text between the nodes isn't copied, because the text of a link with a link trail spans the closing brackets.
+                        for node in text {
+                            steps.push(Step::Nodes(::std::slice::from_ref(node)));
+                        }
+                    }
+                }
+                steps.push(self::text("]]"));
+            }
+            ::Node::InterwikiLink {
+                prefix,
+                target,
+                text,
+                ..
+            } => {
+                // The leading colon keeps a language prefix from making a language link.
+                steps.push(self::text("[[:"));
+                steps.push(self::text(prefix));
+                steps.push(self::text(":"));
+                steps.push(self::text(target));
+                match text.as_slice() {
+                    [::Node::Text { value, .. }]
+                        if value.trim_start_matches(':') == format!("{}:{}", prefix, target) => {}
+                    _ => {
+                        steps.push(self::text("|"));
+                        for node in text {
+                            steps.push(Step::Nodes(::std::slice::from_ref(node)));
+                        }
+                    }
+                }
+                steps.push(self::text("]]"));
+            }
+            ::Node::Italic { .. } => steps.push(text("''")),
+            ::Node::LanguageLink {
+                prefix,
+                target,
+                text,
+                ..
+            } => {
+                steps.push(self::text("[["));
+                steps.push(self::text(prefix));
+                steps.push(self::text(":"));
+                steps.push(self::text(target));
+                if !text.is_empty() {
+                    steps.push(self::text("|"));
+                    steps.push(Step::Nodes(text));
+                }
+                steps.push(self::text("]]"));
+            }
+            ::Node::MagicWord { end, start } => {
+                if let Some(text) = self.wiki_text.get(*start..*end) {
+                    if text.starts_with("__") {
+                        steps.push(Step::Text(text.to_string().into()));
+                    }
+                }
+            }
+            ::Node::OrderedList { items, .. } => {
+                for (item_index, item) in items.iter().enumerate() {
+                    push_list_item(&mut steps, '#', item_index == 0, &item.nodes);
+                }
+            }
+            ::Node::ParagraphBreak { .. } => steps.push(Step::LineBreak(2)),
+            ::Node::Parameter { default, name, .. } => {
+                steps.push(text("{{{"));
+                steps.push(Step::Nodes(name));
+                if let Some(default) = default {
+                    steps.push(text("|"));
+                    steps.push(Step::Nodes(default));
+                }
+                steps.push(text("}}}"));
+            }
+            ::Node::Preformatted { nodes, .. } => {
+                steps.push(Step::StartPreformatted);
+                steps.push(Step::Nodes(nodes));
+                steps.push(Step::EndPreformatted);
+            }
+            ::Node::Redirect { target, .. } => {
+                steps.push(text("#REDIRECT [["));
+                steps.push(text(target));
+                steps.push(text("]]"));
+            }
+            ::Node::StartTag {
+                attributes,
+                name,
+                self_closing,
+                ..
+            } => {
+                steps.push(text("<"));
+                steps.push(text(name));
+                steps.push(Step::Attributes(attributes));
+                if *self_closing {
+                    steps.push(text(" /"));
+                }
+                steps.push(text(">"));
+            }
+            // The attributes of the table and its parts are written from the parsed attributes, so changes to them are kept.
+            ::Node::Table {
+                captions,
+                parsed_attributes,
+                rows,
+                ..
+            } => {
+                steps.push(text("{|"));
+                steps.push(Step::Attributes(parsed_attributes));
+                for caption in captions {
+                    steps.push(text("\n|+"));
+                    push_table_cell(&mut steps, &caption.parsed_attributes, &caption.content);
+                }
+                for (row_index, row) in rows.iter().enumerate() {
+                    if row_index > 0 || !row.parsed_attributes.is_empty() {
+                        steps.push(text("\n|-"));
+                        steps.push(Step::Attributes(&row.parsed_attributes));
+                    }
+                    for cell in &row.cells {
+                        steps.push(text(match cell.type_ {
+                            ::TableCellType::Heading => "\n!",
+                            ::TableCellType::Ordinary => "\n|",
+                        }));
+                        push_table_cell(&mut steps, &cell.parsed_attributes, &cell.content);
+                    }
+                }
+                steps.push(text("\n|}"));
+            }
+            ::Node::Tag {
+                attributes,
+                name,
+                nodes,
+                ..
+            } => {
+                steps.push(text("<"));
+                steps.push(text(name));
+                steps.push(Step::Attributes(attributes));
+                if nodes.is_empty() {
+                    steps.push(text(" />"));
+                } else {
+                    steps.push(text(">"));
+                    steps.push(Step::Nodes(nodes));
+                    steps.push(text("</"));
+                    steps.push(text(name));
+                    steps.push(text(">"));
+                }
+            }
+            ::Node::Template {
+                name, parameters, ..
+            } => {
+                steps.push(text("{{"));
+                steps.push(Step::Nodes(name));
+                for parameter in parameters {
+                    steps.push(text("|"));
+                    if let Some(name) = &parameter.name {
+                        steps.push(Step::Nodes(name));
+                        steps.push(text("="));
+                    }
+                    steps.push(Step::Nodes(&parameter.value));
+                }
+                steps.push(text("}}"));
+            }
+            ::Node::Text { value, .. } => steps.push(text(value)),
+            ::Node::UnorderedList { items, .. } => {
+                for (item_index, item) in items.iter().enumerate() {
+                    push_list_item(&mut steps, '*', item_index == 0, &item.nodes);
+                }
+            }
+        }
+        steps
+    }
+
+    fn sequence<'n, 'm>(&self, nodes: &'n [::Node<'m>]) -> Sequence<'n, 'm> {
+        let mut node_matches: Vec<_> = nodes.iter().map(|node| self.find(node)).collect();
+        // Text before a table is parsed as nodes preceding the table even though it's inside the code of the table, so a table is written in canonical syntax when any of those nodes has been changed.
+        for (node_index, node) in nodes.iter().enumerate() {
+            if node_matches[node_index].is_some()
+                && nodes[..node_index]
+                    .iter()
+                    .zip(&node_matches)
+                    .any(|(previous_node, previous_match)| {
+                        previous_match.is_none()
+                            && previous_node.start() >= node.start()
+                            && previous_node.end() <= node.end()
+                    }) {
+                node_matches[node_index] = None;
+            }
+        }
+        Sequence {
+            index: 0,
+            node_matches,
+            nodes,
+            previous: None,
+        }
+    }
+
+    fn source(&self, start: usize, end: usize) -> &'c str {
+        let length = self.wiki_text.len();
+        self.wiki_text
+            .get(start.min(length)..end.min(length))
+            .unwrap_or("")
+    }
+
+    /// Returns the positions in the wiki text where the code of the node starts and ends, which for some types of nodes include more than the positions of the node.
+    fn span(&self, node: &::Node) -> (usize, usize) {
+        match node {
+            // The end of a parameter is before the closing braces.
+            ::Node::Parameter { end, start, .. } if self.source(*end, *end + 3) == "}}}" => {
+                (*start, end + 3)
+            }
+            // The start of a preformatted block is after the space that starts it.
+            ::Node::Preformatted { end, start, .. }
+                if *start > 0 && self.source(start - 1, *start) == " " =>
+            {
+                (start - 1, *end)
+            }
+            _ => (node.start(), node.end()),
+        }
+    }
+
+    fn write_attributes(&mut self, attributes: &[::Attribute]) {
+        for attribute in attributes {
+            self.output.push(' ');
+            self.output += &attribute.name;
+            if let Some(value) = &attribute.value {
+                self.output.push('=');
+                let quote = match value.quote {
+                    ::AttributeQuote::Double => Some('"'),
+                    ::AttributeQuote::Single if !value.value.contains('\'') => Some('\''),
+                    ::AttributeQuote::Single => Some('"'),
+                    ::AttributeQuote::Unquoted => if value.value.is_empty()
+                        || value.value.contains(|character| {
+                            matches!(character, '\t' | '\n' | '\r' | ' ' | '"' | '\'' | '/' | '>')
+                        }) {
+                        Some('"')
+                    } else {
+                        None
+                    },
+                };
+                match quote {
+                    None => self.output += &value.value,
+                    Some(quote) => {
+                        self.output.push(quote);
+                        if quote == '"' {
+                            self.output += &value.value.replace('"', "&quot;");
+                        } else {
+                            self.output += &value.value;
+                        }
+                        self.output.push(quote);
+                    }
+                }
+            }
+        }
+    }
+
+    fn write_nodes(&mut self, nodes: &[::Node]) {
+        // The nodes are written with an explicit stack instead of recursion, since recursing once for each level of nesting overflows the stack for deeply nested nodes.
+        let mut frames = vec![Frame::Sequence(self.sequence(nodes))];
+        while let Some(frame) = frames.last_mut() {
+            let next_frame = match frame {
+                Frame::Sequence(sequence) => self
+                    .next_node(sequence)
+                    .map(|node| Frame::Steps(self.node_steps(node).into_iter())),
+                Frame::Steps(steps) => match steps.next() {
+                    None => None,
+                    Some(Step::Nodes(nodes)) => Some(Frame::Sequence(self.sequence(nodes))),
+                    Some(step) => {
+                        self.write_step(step);
+                        continue;
+                    }
+                },
+            };
+            match next_frame {
+                None => {
+                    frames.pop();
+                }
+                Some(next_frame) => frames.push(next_frame),
+            }
+        }
+    }
+
+    fn write_step(&mut self, step: Step) {
+        match step {
+            Step::Attributes(attributes) => self.write_attributes(attributes),
+            Step::EndListItem => {
+                self.list_prefix.pop();
+            }
+            Step::EndPreformatted => {
+                let output = self.saved_outputs.pop().unwrap();
+                let content = ::std::mem::replace(&mut self.output, output);
+                self.output.push(' ');
+                self.output += &content.replace('\n', "\n ");
+            }
+            Step::LineBreak(count) => self.line_break(count),
+            Step::Nodes(_) => unreachable!(),
+            Step::StartListItem(marker) => {
+                self.list_prefix.push(marker);
+                self.output += &self.list_prefix;
+            }
+            Step::StartPreformatted => {
+                let output = ::std::mem::take(&mut self.output);
+                self.saved_outputs.push(output);
+            }
+            Step::Text(text) => self.output += &text,
+        }
+    }
+}
+
+/// Returns a number that identifies the node by where it is in memory.
+fn address(node: &::Node) -> usize {
+    node as *const ::Node as usize
+}
+
+fn index_nodes<'a, 'b, 'c>(serializer: &mut Serializer<'a, 'b, 'c>, nodes: &'b [::Node<'a>]) {
+    let mut stack = vec![nodes];
+    while let Some(nodes) = stack.pop() {
+        let sequence = serializer.sequences.len();
+        serializer.sequences.push(nodes);
+        for (position, node) in nodes.iter().enumerate() {
+            // A node is indexed before the nodes it contains, so a node that contains another node with the same position and type takes precedence.
+            serializer
+                .index
+                .entry((node.start(), node.end(), node.kind()))
+                .or_insert((node, sequence, position));
+        }
+        for node in nodes.iter().rev() {
+            stack.extend(::visit::child_sequences(node).into_iter().rev());
+        }
+    }
+}
+
+fn is_block(node: &::Node) -> bool {
+    matches!(
+        node,
+        ::Node::DefinitionList { .. }
+            | ::Node::Heading { .. }
+            | ::Node::HorizontalDivider { .. }
+            | ::Node::OrderedList { .. }
+            | ::Node::Preformatted { .. }
+            | ::Node::Redirect { .. }
+            | ::Node::Table { .. }
+            | ::Node::UnorderedList { .. }
+    )
+}
+
+fn is_list(node: &::Node) -> bool {
+    matches!(
+        node,
+        ::Node::DefinitionList { .. } | ::Node::OrderedList { .. } | ::Node::UnorderedList { .. }
+    )
+}
+
+fn push_list_item<'n, 'm>(
+    steps: &mut Vec<Step<'n, 'm>>,
+    marker: char,
+    is_first: bool,
+    nodes: &'n [::Node<'m>],
+) {
+    if !is_first {
+        steps.push(text("\n"));
+    }
+    steps.push(Step::StartListItem(marker));
+    steps.push(Step::Nodes(nodes));
+    steps.push(Step::EndListItem);
+}
+
+fn push_table_cell<'n, 'm>(
+    steps: &mut Vec<Step<'n, 'm>>,
+    attributes: &'n [::Attribute<'m>],
+    content: &'n [::Node<'m>],
+) {
+    if !attributes.is_empty() {
+        steps.push(Step::Attributes(attributes));
+        steps.push(text(" |"));
+    }
+    steps.push(text(" "));
+    steps.push(Step::Nodes(content));
+}
+
+/// Returns whether the nodes are equal apart from their child nodes. Nodes that are equal this way have the same number of sequences of child nodes.
+fn shallow_eq(node: &::Node, other: &::Node) -> bool {
+    if node.start() != other.start() || node.end() != other.end() {
+        return false;
+    }
+    match (node, other) {
+        (::Node::Bold { .. }, ::Node::Bold { .. })
+        | (::Node::BoldItalic { .. }, ::Node::BoldItalic { .. })
+        | (::Node::Comment { .. }, ::Node::Comment { .. })
+        | (::Node::ExternalLink { .. }, ::Node::ExternalLink { .. })
+        | (::Node::HorizontalDivider { .. }, ::Node::HorizontalDivider { .. })
+        | (::Node::Italic { .. }, ::Node::Italic { .. })
+        | (::Node::MagicWord { .. }, ::Node::MagicWord { .. })
+        | (::Node::ParagraphBreak { .. }, ::Node::ParagraphBreak { .. })
+        | (::Node::Preformatted { .. }, ::Node::Preformatted { .. }) => true,
+        (::Node::Category { target, .. }, ::Node::Category { target: other, .. })
+        | (::Node::Image { target, .. }, ::Node::Image { target: other, .. })
+        | (::Node::Link { target, .. }, ::Node::Link { target: other, .. })
+        | (::Node::Redirect { target, .. }, ::Node::Redirect { target: other, .. }) => {
+            target == other
+        }
+        (
+            ::Node::CharacterEntity { character, .. },
+            ::Node::CharacterEntity {
+                character: other, ..
+            },
+        ) => character == other,
+        (::Node::DefinitionList { items, .. }, ::Node::DefinitionList { items: other, .. }) => {
+            items.len() == other.len()
+                && items.iter().zip(other).all(|(item, other)| {
+                    item.end == other.end && item.start == other.start && item.type_ == other.type_
+                })
+        }
+        (::Node::EndTag { name, .. }, ::Node::EndTag { name: other, .. }) => name == other,
+        (::Node::FreeExternalLink { url, .. }, ::Node::FreeExternalLink { url: other, .. }) => {
+            url == other
+        }
+        (::Node::Heading { level, .. }, ::Node::Heading { level: other, .. }) => level == other,
+        (
+            ::Node::InterwikiLink { prefix, target, .. },
+            ::Node::InterwikiLink {
+                prefix: other_prefix,
+                target: other_target,
+                ..
+            },
+        )
+        | (
+            ::Node::LanguageLink { prefix, target, .. },
+            ::Node::LanguageLink {
+                prefix: other_prefix,
+                target: other_target,
+                ..
+            },
+        ) => prefix == other_prefix && target == other_target,
+        (::Node::OrderedList { items, .. }, ::Node::OrderedList { items: other, .. })
+        | (::Node::UnorderedList { items, .. }, ::Node::UnorderedList { items: other, .. }) => {
+            items.len() == other.len()
+                && items
+                    .iter()
+                    .zip(other)
+                    .all(|(item, other)| item.end == other.end && item.start == other.start)
+        }
+        (::Node::Parameter { default, .. }, ::Node::Parameter { default: other, .. }) => {
+            default.is_some() == other.is_some()
+        }
+        (
+            ::Node::StartTag {
+                attributes,
+                name,
+                self_closing,
+                ..
+            },
+            ::Node::StartTag {
+                attributes: other_attributes,
+                name: other_name,
+                self_closing: other_self_closing,
+                ..
+            },
+        ) => {
+            attributes == other_attributes
+                && name == other_name
+                && self_closing == other_self_closing
+        }
+        (
+            ::Node::Table {
+                captions,
+                parsed_attributes,
+                rows,
+                ..
+            },
+            ::Node::Table {
+                captions: other_captions,
+                parsed_attributes: other_parsed_attributes,
+                rows: other_rows,
+                ..
+            },
+        ) => {
+            parsed_attributes == other_parsed_attributes
+                && captions.len() == other_captions.len()
+                && captions.iter().zip(other_captions).all(|(caption, other)| {
+                    caption.attributes.is_some() == other.attributes.is_some()
+                        && caption.end == other.end
+                        && caption.parsed_attributes == other.parsed_attributes
+                        && caption.start == other.start
+                })
+                && rows.len() == other_rows.len()
+                && rows.iter().zip(other_rows).all(|(row, other)| {
+                    row.cells.len() == other.cells.len()
+                        && row.end == other.end
+                        && row.parsed_attributes == other.parsed_attributes
+                        && row.start == other.start
+                        && row.cells.iter().zip(&other.cells).all(|(cell, other)| {
+                            cell.attributes.is_some() == other.attributes.is_some()
+                                && cell.end == other.end
+                                && cell.parsed_attributes == other.parsed_attributes
+                                && cell.start == other.start
+                                && cell.type_ == other.type_
+                        })
+                })
+        }
+        (
+            ::Node::Tag {
+                attributes, name, ..
+            },
+            ::Node::Tag {
+                attributes: other_attributes,
+                name: other_name,
+                ..
+            },
+        ) => attributes == other_attributes && name == other_name,
+        (
+            ::Node::Template { parameters, .. },
+            ::Node::Template {
+                parameters: other, ..
+            },
+        ) => {
+            parameters.len() == other.len()
+                && parameters.iter().zip(other).all(|(parameter, other)| {
+                    parameter.end == other.end
+                        && parameter.name.is_some() == other.name.is_some()
+                        && parameter.start == other.start
+                })
+        }
+        (::Node::Text { value, .. }, ::Node::Text { value: other, .. }) => value == other,
+        _ => false,
+    }
+}
+
+fn text<'n, 'm>(text: &'n str) -> Step<'n, 'm> {
+    Step::Text(::Cow::Borrowed(text))
+}
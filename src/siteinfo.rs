@@ -0,0 +1,302 @@
+// Copyright 2018 Fredrik Portström <https://portstrom.com>
+// This is free software distributed under the terms specified in
+// the file LICENSE at the top-level directory of this distribution.
+
+use json::Value;
+use std::fmt;
+
+/// Reason the site info could not be read.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum SiteinfoError {
+    /// The text is not valid JSON.
+    InvalidJson {
+        /// The byte position in the text where the error was found.
+        position: usize,
+    },
+
+    /// A property of the site info doesn't have the structure given by the API, for example a string where an array is expected.
+    InvalidProperty {
+        /// The name of the property, such as `namespaces`.
+        name: &'static str,
+    },
+}
+
+impl fmt::Display for SiteinfoError {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SiteinfoError::InvalidJson { position } => {
+                write!(formatter, "Invalid JSON at position {}.", position)
+            }
+            SiteinfoError::InvalidProperty { name } => {
+                write!(formatter, "Invalid site info property {}.", name)
+            }
+        }
+    }
+}
+
+struct Namespace<'a> {
+    aliases: Vec<&'a str>,
+    canonical_name: &'a str,
+    case_sensitive: bool,
+    id: i32,
+    name: &'a str,
+    subpages: bool,
+}
+
+impl ::Configuration {
+    /// Creates a configuration from the site info of a wiki in JSON format.
+    ///
+    /// The JSON is the response to the API request `action=query&meta=siteinfo&siprop=general|namespaces|namespacealiases|magicwords|extensiontags|protocols|interwikimap`, in either format version. Both the whole response and only the value of its `query` property are accepted. Properties that are missing from the site info are treated as empty, so the namespaces that exist in every wiki are used if `namespaces` is missing.
+    pub fn from_siteinfo_json(json: &str) -> Result<Self, SiteinfoError> {
+        let root = ::json::parse(json).map_err(|position| SiteinfoError::InvalidJson { position })?;
+        let query = root.get("query").unwrap_or(&root);
+        if query.as_object().is_none() {
+            return Err(SiteinfoError::InvalidProperty { name: "query" });
+        }
+        let mut extension_tags = get_array(query, "extensiontags")?
+            .iter()
+            .map(|tag| {
+                tag.as_str()
+                    .map(|tag| tag.trim_start_matches('<').trim_end_matches('>'))
+                    .ok_or(SiteinfoError::InvalidProperty {
+                        name: "extensiontags",
+                    })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        // The tags for transclusion are handled by Mediawiki itself rather than by extensions, so they are not in the site info.
+        extension_tags.extend_from_slice(&["includeonly", "noinclude", "onlyinclude"]);
+        let mut interwiki_prefixes = vec![];
+        let mut language_link_prefixes = vec![];
+        for interwiki in get_array(query, "interwikimap")? {
+            let prefix = interwiki
+                .get("prefix")
+                .and_then(Value::as_str)
+                .ok_or(SiteinfoError::InvalidProperty {
+                    name: "interwikimap",
+                })?;
+            // Like in MediaWiki, links to local interwikis whose prefix is a language code are language links, as are links with the extra language link prefixes of the wiki.
+            if interwiki.get("language").is_some() && get_flag(interwiki, "local")
+                || get_flag(interwiki, "extralanglink")
+            {
+                language_link_prefixes.push(prefix);
+            } else {
+                interwiki_prefixes.push(prefix);
+            }
+        }
+        let link_trail = match query.get("general") {
+            None => String::new(),
+            Some(general) => match general.get("linktrail") {
+                None => String::new(),
+                Some(link_trail) => parse_link_trail(link_trail.as_str().ok_or(
+                    SiteinfoError::InvalidProperty { name: "general" },
+                )?),
+            },
+        };
+        let mut magic_words = vec![];
+        let mut redirect_magic_words = vec![];
+        for magic_word in get_array(query, "magicwords")? {
+            let invalid = SiteinfoError::InvalidProperty { name: "magicwords" };
+            let name = magic_word
+                .get("name")
+                .and_then(Value::as_str)
+                .ok_or(invalid)?;
+            for alias in magic_word
+                .get("aliases")
+                .and_then(Value::as_array)
+                .ok_or(invalid)?
+            {
+                let alias = alias.as_str().ok_or(invalid)?;
+                if name == "redirect" {
+                    redirect_magic_words.push(alias.trim_start_matches('#'));
+                } else if alias.len() > 4 && alias.starts_with("__") && alias.ends_with("__") {
+                    magic_words.push(&alias[2..alias.len() - 2]);
+                }
+            }
+        }
+        let mut namespaces = vec![];
+        if let Some(namespace_map) = query.get("namespaces") {
+            let invalid = SiteinfoError::InvalidProperty { name: "namespaces" };
+            for (_, namespace) in namespace_map.as_object().ok_or(invalid)? {
+                let id = match namespace.get("id") {
+                    Some(Value::Number(id)) => *id as i32,
+                    _ => return Err(invalid),
+                };
+                namespaces.push(Namespace {
+                    aliases: vec![],
+                    canonical_name: namespace
+                        .get("canonical")
+                        .and_then(Value::as_str)
+                        .unwrap_or(""),
+                    case_sensitive: namespace.get("case").and_then(Value::as_str)
+                        == Some("case-sensitive"),
+                    id,
+                    name: namespace
+                        .get("name")
+                        .or_else(|| namespace.get("*"))
+                        .and_then(Value::as_str)
+                        .ok_or(invalid)?,
+                    subpages: get_flag(namespace, "subpages"),
+                });
+            }
+        }
+        for alias in get_array(query, "namespacealiases")? {
+            let invalid = SiteinfoError::InvalidProperty {
+                name: "namespacealiases",
+            };
+            let id = match alias.get("id") {
+                Some(Value::Number(id)) => *id as i32,
+                _ => return Err(invalid),
+            };
+            let alias = alias
+                .get("alias")
+                .or_else(|| alias.get("*"))
+                .and_then(Value::as_str)
+                .ok_or(invalid)?;
+            if let Some(namespace) = namespaces.iter_mut().find(|namespace| namespace.id == id) {
+                namespace.aliases.push(alias);
+            }
+        }
+        let protocols = get_array(query, "protocols")?
+            .iter()
+            .map(|protocol| {
+                protocol
+                    .as_str()
+                    .ok_or(SiteinfoError::InvalidProperty { name: "protocols" })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let namespace_sources = namespaces
+            .iter()
+            .map(|namespace| ::NamespaceSource {
+                aliases: &namespace.aliases,
+                canonical_name: namespace.canonical_name,
+                case_sensitive: namespace.case_sensitive,
+                id: namespace.id,
+                name: namespace.name,
+                subpages: namespace.subpages,
+            })
+            .collect::<Vec<_>>();
+        Ok(::Configuration::new(&::ConfigurationSource {
+            category_namespaces: &[],
+            extension_tags: &extension_tags,
+            file_namespaces: &[],
+            interwiki_prefixes: &interwiki_prefixes,
+            language_link_prefixes: &language_link_prefixes,
+            link_trail: &link_trail,
+            magic_words: &magic_words,
+            namespaces: &namespace_sources,
+            protocols: &protocols,
+            redirect_magic_words: &redirect_magic_words,
+        }))
+    }
+}
+
+/// Gets a property that is a flag. In format version 1 the property is an empty string when true and absent when false.
+fn get_flag(value: &Value, name: &str) -> bool {
+    !matches!(value.get(name), None | Some(Value::Bool(false)))
+}
+
+fn get_array<'a>(query: &'a Value, name: &'static str) -> Result<&'a [Value], SiteinfoError> {
+    match query.get(name) {
+        None => Ok(&[]),
+        Some(value) => value
+            .as_array()
+            .ok_or(SiteinfoError::InvalidProperty { name }),
+    }
+}
+
+/// Gets the characters matched by the regular expression for link trails, such as `/^([a-z]+)(.*)$/sD`.
+fn parse_link_trail(pattern: &str) -> String {
+    let pattern = pattern.trim_start_matches("/^(");
+    let pattern = match pattern.find("(.*)") {
+        None => pattern,
+        Some(position) => &pattern[..position],
+    };
+    let mut characters = String::new();
+    let mut is_in_class = false;
+    let mut previous = None;
+    let mut iterator = pattern.chars();
+    while let Some(character) = iterator.next() {
+        let character = match character {
+            '[' if !is_in_class => {
+                is_in_class = true;
+                continue;
+            }
+            ']' if is_in_class => {
+                is_in_class = false;
+                previous = None;
+                continue;
+            }
+            '-' if is_in_class && previous.is_some() => {
+                match iterator.next() {
+                    Some(']') | None => {
+                        is_in_class = false;
+                        characters.push('-');
+                    }
+                    Some(last) => {
+                        let last = if last == '\\' {
+                            match parse_escape(&mut iterator) {
+                                None => break,
+                                Some(last) => last,
+                            }
+                        } else {
+                            last
+                        };
+                        let first = previous.unwrap_or(last) as u32;
+                        for code_point in first + 1..=last as u32 {
+                            characters.extend(::std::char::from_u32(code_point));
+                        }
+                    }
+                }
+                previous = None;
+                continue;
+            }
+            '\\' => match parse_escape(&mut iterator) {
+                None => break,
+                Some(character) => character,
+            },
+            '(' | ')' | '*' | '+' | '?' | ':' | '|' if !is_in_class => {
+                previous = None;
+                continue;
+            }
+            _ => character,
+        };
+        if !characters.contains(character) {
+            characters.push(character);
+        }
+        previous = Some(character);
+    }
+    characters
+}
+
+/// Gets the character given by an escape sequence in a regular expression, following the backslash. A hexadecimal escape is either two digits such as `\xe0` or digits in braces such as `\x{e0}`.
+fn parse_escape(iterator: &mut ::std::str::Chars) -> Option<char> {
+    let character = iterator.next()?;
+    if character != 'x' {
+        return Some(character);
+    }
+    let rest = iterator.as_str();
+    let (digits, length) = if rest.starts_with('{') {
+        match rest.find('}') {
+            None => return Some(character),
+            Some(end) => (&rest[1..end], end + 1),
+        }
+    } else {
+        let end = rest
+            .char_indices()
+            .take(2)
+            .take_while(|(_, character)| character.is_ascii_hexdigit())
+            .last()
+            .map_or(0, |(position, _)| position + 1);
+        (&rest[..end], end)
+    };
+    match u32::from_str_radix(digits, 16)
+        .ok()
+        .and_then(::std::char::from_u32)
+    {
+        None => Some(character),
+        Some(escaped) => {
+            *iterator = rest[length..].chars();
+            Some(escaped)
+        }
+    }
+}
@@ -0,0 +1,175 @@
+// Copyright 2018 Fredrik Portström <https://portstrom.com>
+// This is free software distributed under the terms specified in
+// the file LICENSE at the top-level directory of this distribution.
+
+/// Unit in which columns and offsets are measured.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum ColumnUnit {
+    /// Bytes of UTF-8, the unit of the positions given by the parser.
+    Byte,
+
+    /// Unicode scalar values, the unit of Rust's `char`.
+    Char,
+
+    /// Code units of UTF-16, the unit used by JavaScript strings.
+    Utf16,
+}
+
+/// Line and column of a position in the wiki text, both starting from zero.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct LineColumn {
+    /// The column, counted in the unit given when converting.
+    pub column: usize,
+
+    /// The line, where lines are separated by `\n`. A `\r` before the `\n` is the last character of its line.
+    pub line: usize,
+}
+
+/// Table for converting the byte positions given by the parser to lines and columns and to offsets in UTF-16, and back.
+///
+/// Build the table once for a wiki text and reuse it for all positions in that text.
+#[derive(Clone, Debug)]
+pub struct SourceMap<'a> {
+    line_starts: Vec<usize>,
+    utf16_line_starts: Vec<usize>,
+    wiki_text: &'a str,
+}
+
+impl<'a> SourceMap<'a> {
+    /// Builds the table for the given wiki text.
+    #[must_use]
+    pub fn new(wiki_text: &'a str) -> Self {
+        let mut line_starts = vec![0];
+        let mut utf16_line_starts = vec![0];
+        let mut utf16_offset = 0;
+        for (position, character) in wiki_text.char_indices() {
+            utf16_offset += character.len_utf16();
+            if character == '\n' {
+                line_starts.push(position + 1);
+                utf16_line_starts.push(utf16_offset);
+            }
+        }
+        SourceMap {
+            line_starts,
+            utf16_line_starts,
+            wiki_text,
+        }
+    }
+
+    /// Returns the line containing the given byte position.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the position is beyond the end of the wiki text.
+    #[must_use]
+    pub fn line(&self, position: usize) -> usize {
+        assert!(position <= self.wiki_text.len());
+        match self.line_starts.binary_search(&position) {
+            Err(line) => line - 1,
+            Ok(line) => line,
+        }
+    }
+
+    /// Returns the line and column of the given byte position, with the column counted in the given unit.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the position is beyond the end of the wiki text or not at the boundary of a character.
+    #[must_use]
+    pub fn line_column(&self, position: usize, unit: ColumnUnit) -> LineColumn {
+        let line = self.line(position);
+        LineColumn {
+            column: measure(&self.wiki_text[self.line_starts[line]..position], unit),
+            line,
+        }
+    }
+
+    /// Returns the number of lines in the wiki text. This is one more than the number of line breaks.
+    #[must_use]
+    pub fn line_count(&self) -> usize {
+        self.line_starts.len()
+    }
+
+    /// Returns the byte positions where the given line starts and ends, not including the line break, or `None` if there is no such line.
+    #[must_use]
+    pub fn line_span(&self, line: usize) -> Option<::std::ops::Range<usize>> {
+        let start = *self.line_starts.get(line)?;
+        let end = match self.line_starts.get(line + 1) {
+            None => self.wiki_text.len(),
+            Some(next_line_start) => next_line_start - 1,
+        };
+        Some(start..end)
+    }
+
+    /// Returns the byte position of the given line and column, with the column counted in the given unit.
+    ///
+    /// Returns `None` if the line doesn't exist, if the column is beyond the end of the line, or if the column is in the middle of a character.
+    #[must_use]
+    pub fn position(&self, line_column: LineColumn, unit: ColumnUnit) -> Option<usize> {
+        let span = self.line_span(line_column.line)?;
+        let mut column = 0;
+        for (position, character) in self.wiki_text[span.clone()].char_indices() {
+            if column == line_column.column {
+                return Some(span.start + position);
+            }
+            if column > line_column.column {
+                return None;
+            }
+            column += measure_char(character, unit);
+        }
+        if column == line_column.column {
+            Some(span.end)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the byte position of the given offset in UTF-16 code units from the start of the wiki text.
+    ///
+    /// Returns `None` if the offset is beyond the end of the wiki text or in the middle of a character.
+    #[must_use]
+    pub fn position_from_utf16(&self, offset: usize) -> Option<usize> {
+        let line = match self.utf16_line_starts.binary_search(&offset) {
+            Err(line) => line - 1,
+            Ok(line) => line,
+        };
+        let line_column = LineColumn {
+            column: offset - self.utf16_line_starts[line],
+            line,
+        };
+        self.position(line_column, ColumnUnit::Utf16)
+    }
+
+    /// Returns the offset in UTF-16 code units from the start of the wiki text of the given byte position.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the position is beyond the end of the wiki text or not at the boundary of a character.
+    #[must_use]
+    pub fn utf16_offset(&self, position: usize) -> usize {
+        let line_column = self.line_column(position, ColumnUnit::Utf16);
+        self.utf16_line_starts[line_column.line] + line_column.column
+    }
+
+    /// Returns the wiki text the table was built for.
+    #[must_use]
+    pub fn wiki_text(&self) -> &'a str {
+        self.wiki_text
+    }
+}
+
+fn measure(text: &str, unit: ColumnUnit) -> usize {
+    match unit {
+        ColumnUnit::Byte => text.len(),
+        ColumnUnit::Char => text.chars().count(),
+        ColumnUnit::Utf16 => text.chars().map(char::len_utf16).sum(),
+    }
+}
+
+fn measure_char(character: char, unit: ColumnUnit) -> usize {
+    match unit {
+        ColumnUnit::Byte => character.len_utf8(),
+        ColumnUnit::Char => 1,
+        ColumnUnit::Utf16 => character.len_utf16(),
+    }
+}
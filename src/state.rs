@@ -30,6 +30,7 @@ pub enum OpenNodeType<'a> {
     Preformatted,
     Table(Table<'a>),
     Tag {
+        attributes: Vec<::Attribute<'a>>,
         name: ::Cow<'a, str>,
     },
     Template {
@@ -41,9 +42,24 @@ pub enum OpenNodeType<'a> {
     },
 }
 
+impl<'a> OpenNodeType<'a> {
+    /// Whether the open node is an external link or a link other than an image, in whose text free external links are not recognized.
+    fn is_link(&self) -> bool {
+        match self {
+            OpenNodeType::ExternalLink => true,
+            OpenNodeType::Link { namespace, .. } => *namespace != Some(::Namespace::File),
+            _ => false,
+        }
+    }
+}
+
 pub struct State<'a> {
     pub flushed_position: usize,
+    pub is_only_include: bool,
+    pub mode: ::TransclusionMode,
+    pub node_count: usize,
     pub nodes: Vec<::Node<'a>>,
+    pub open_link_count: usize,
     pub scan_position: usize,
     pub stack: Vec<OpenNode<'a>>,
     pub warnings: Vec<::Warning>,
@@ -76,6 +92,7 @@ impl<'a> State<'a> {
     pub fn flush(&mut self, end_position: usize) {
         flush(
             &mut self.nodes,
+            &mut self.node_count,
             self.flushed_position,
             end_position,
             self.wiki_text,
@@ -86,18 +103,41 @@ impl<'a> State<'a> {
         self.wiki_text.as_bytes().get(position).cloned()
     }
 
+    /// Removes the innermost open node. All open nodes are removed through this function or `truncate_open_nodes`, to keep count of the open links.
+    pub fn pop_open_node(&mut self) -> Option<OpenNode<'a>> {
+        let open_node = self.stack.pop()?;
+        if open_node.type_.is_link() {
+            self.open_link_count -= 1;
+        }
+        Some(open_node)
+    }
+
+    pub fn push_node(&mut self, node: ::Node<'a>) {
+        self.node_count += 1;
+        self.nodes.push(node);
+    }
+
     pub fn push_open_node(&mut self, type_: OpenNodeType<'a>, inner_start_position: usize) {
         let scan_position = self.scan_position;
         self.flush(scan_position);
-        self.stack.push(OpenNode {
+        let open_node = OpenNode {
             nodes: ::std::mem::replace(&mut self.nodes, vec![]),
             start: scan_position,
             type_,
-        });
+        };
+        self.restore_open_node(open_node);
         self.scan_position = inner_start_position;
         self.flushed_position = inner_start_position;
     }
 
+    /// Puts back an open node that was removed with `pop_open_node`.
+    pub fn restore_open_node(&mut self, open_node: OpenNode<'a>) {
+        if open_node.type_.is_link() {
+            self.open_link_count += 1;
+        }
+        self.stack.push(open_node);
+    }
+
     pub fn rewind(&mut self, nodes: Vec<::Node<'a>>, position: usize) {
         self.scan_position = position + 1;
         self.nodes = nodes;
@@ -127,6 +167,12 @@ impl<'a> State<'a> {
         }
     }
 
+    pub fn truncate_open_nodes(&mut self, length: usize) {
+        while self.stack.len() > length {
+            self.pop_open_node();
+        }
+    }
+
     pub fn skip_whitespace_backwards(&self, position: usize) -> usize {
         skip_whitespace_backwards(self.wiki_text, position)
     }
@@ -138,15 +184,17 @@ impl<'a> State<'a> {
 
 pub fn flush<'a>(
     nodes: &mut Vec<::Node<'a>>,
+    node_count: &mut usize,
     flushed_position: usize,
     end_position: usize,
     wiki_text: &'a str,
 ) {
     if end_position > flushed_position {
+        *node_count += 1;
         nodes.push(::Node::Text {
             end: end_position,
             start: flushed_position,
-            value: &wiki_text[flushed_position..end_position],
+            value: ::Cow::Borrowed(&wiki_text[flushed_position..end_position]),
         });
     }
 }
@@ -3,6 +3,7 @@
 // the file LICENSE at the top-level directory of this distribution.
 
 use state::TableState;
+use Positioned;
 
 pub fn parse_heading_cell(state: &mut ::State) {
     let table = get_table(&mut state.stack);
@@ -11,6 +12,7 @@ pub fn parse_heading_cell(state: &mut ::State) {
         let end = ::state::skip_whitespace_backwards(state.wiki_text, position_before_token);
         ::state::flush(
             &mut state.nodes,
+            &mut state.node_count,
             state.flushed_position,
             end,
             state.wiki_text,
@@ -20,6 +22,7 @@ pub fn parse_heading_cell(state: &mut ::State) {
                 attributes: vec![],
                 cells: vec![],
                 end,
+                parsed_attributes: vec![],
                 start: table.start,
             });
         }
@@ -28,6 +31,7 @@ pub fn parse_heading_cell(state: &mut ::State) {
             attributes: table.child_element_attributes.take(),
             content: ::std::mem::replace(&mut state.nodes, vec![]),
             end,
+            parsed_attributes: vec![],
             start: table.start,
             type_: ::TableCellType::Heading,
         });
@@ -136,6 +140,7 @@ fn change_state(
     if paragraph_break_possible {
         ::state::flush(
             &mut state.nodes,
+            &mut state.node_count,
             state.flushed_position,
             end,
             state.wiki_text,
@@ -157,6 +162,7 @@ fn change_state(
                 attributes: table.child_element_attributes.take(),
                 content: ::std::mem::replace(&mut state.nodes, vec![]),
                 end,
+                parsed_attributes: vec![],
                 start: table.start,
             });
         }
@@ -166,6 +172,7 @@ fn change_state(
                     attributes: vec![],
                     cells: vec![],
                     end,
+                    parsed_attributes: vec![],
                     start: table.start,
                 });
             }
@@ -174,6 +181,7 @@ fn change_state(
                 attributes: table.child_element_attributes.take(),
                 content: ::std::mem::replace(&mut state.nodes, vec![]),
                 end,
+                parsed_attributes: vec![],
                 start: table.start,
                 type_: ::TableCellType::Ordinary,
             });
@@ -185,6 +193,7 @@ fn change_state(
                     attributes: vec![],
                     cells: vec![],
                     end,
+                    parsed_attributes: vec![],
                     start: table.start,
                 });
             }
@@ -193,6 +202,7 @@ fn change_state(
                 attributes: table.child_element_attributes.take(),
                 content: ::std::mem::replace(&mut state.nodes, vec![]),
                 end,
+                parsed_attributes: vec![],
                 start: table.start,
                 type_: ::TableCellType::Heading,
             });
@@ -203,6 +213,7 @@ fn change_state(
                 attributes: ::std::mem::replace(&mut state.nodes, vec![]),
                 cells: vec![],
                 end,
+                parsed_attributes: vec![],
                 start: table.start,
             });
         }
@@ -222,7 +233,7 @@ fn parse_end(
     position_after_token: usize,
     paragraph_break_possible: bool,
 ) {
-    let open_node = state.stack.pop().unwrap();
+    let open_node = state.pop_open_node().unwrap();
     match open_node.type_ {
         ::OpenNodeType::Table(::state::Table {
             mut attributes,
@@ -253,6 +264,7 @@ fn parse_end(
                         attributes: child_element_attributes.take(),
                         content: ::std::mem::replace(&mut state.nodes, open_node.nodes),
                         end: position_before_line_break,
+                        parsed_attributes: vec![],
                         start,
                     });
                 }
@@ -262,6 +274,7 @@ fn parse_end(
                             attributes: vec![],
                             cells: vec![],
                             end: 0,
+                            parsed_attributes: vec![],
                             start,
                         });
                     }
@@ -270,6 +283,7 @@ fn parse_end(
                         attributes: child_element_attributes.take(),
                         content: ::std::mem::replace(&mut state.nodes, open_node.nodes),
                         end: position_before_line_break,
+                        parsed_attributes: vec![],
                         start,
                         type_: ::TableCellType::Ordinary,
                     });
@@ -281,6 +295,7 @@ fn parse_end(
                             attributes: vec![],
                             cells: vec![],
                             end: 0,
+                            parsed_attributes: vec![],
                             start,
                         });
                     }
@@ -289,6 +304,7 @@ fn parse_end(
                         attributes: child_element_attributes.take(),
                         content: ::std::mem::replace(&mut state.nodes, open_node.nodes),
                         end: position_before_line_break,
+                        parsed_attributes: vec![],
                         start,
                         type_: ::TableCellType::Heading,
                     });
@@ -299,6 +315,7 @@ fn parse_end(
                         attributes: ::std::mem::replace(&mut state.nodes, open_node.nodes),
                         cells: vec![],
                         end: position_before_line_break,
+                        parsed_attributes: vec![],
                         start,
                     });
                 }
@@ -306,9 +323,23 @@ fn parse_end(
                     attributes = ::std::mem::replace(&mut state.nodes, open_node.nodes);
                 }
             }
+            for caption in &mut captions {
+                if let Some(attributes) = &caption.attributes {
+                    caption.parsed_attributes = parse_attributes(state.wiki_text, attributes);
+                }
+            }
+            for row in &mut rows {
+                row.parsed_attributes = parse_attributes(state.wiki_text, &row.attributes);
+                for cell in &mut row.cells {
+                    if let Some(attributes) = &cell.attributes {
+                        cell.parsed_attributes = parse_attributes(state.wiki_text, attributes);
+                    }
+                }
+            }
             state.scan_position = position_after_token;
             state.nodes.append(&mut before);
-            state.nodes.push(::Node::Table {
+            state.push_node(::Node::Table {
+                parsed_attributes: parse_attributes(state.wiki_text, &attributes),
                 attributes,
                 captions,
                 end: state.scan_position,
@@ -331,6 +362,15 @@ fn parse_end(
     }
 }
 
+fn parse_attributes<'a>(wiki_text: &'a str, nodes: &[::Node<'a>]) -> Vec<::Attribute<'a>> {
+    match (nodes.first(), nodes.last()) {
+        (Some(first_node), Some(last_node)) => {
+            ::attribute::parse_attributes(wiki_text, first_node.start(), last_node.end())
+        }
+        _ => vec![],
+    }
+}
+
 fn parse_line_break(
     state: &mut ::State,
     position_before_line_break: usize,
@@ -357,32 +397,34 @@ fn parse_line_break(
             TableState::CellFirstLine => {
                 ::state::flush(
                     &mut state.nodes,
+                    &mut state.node_count,
                     state.flushed_position,
                     ::state::skip_whitespace_backwards(state.wiki_text, position_before_line_break),
                     state.wiki_text,
                 );
-                state.nodes.push(::Node::ParagraphBreak {
+                table.start = position_after_line_break;
+                table.state = TableState::CellRemainder;
+                state.push_node(::Node::ParagraphBreak {
                     end: position_after_line_break,
                     start: position_before_line_break,
                 });
-                table.start = position_after_line_break;
-                table.state = TableState::CellRemainder;
                 state.flushed_position = position_after_line_break;
                 state.scan_position = position_after_line_break;
             }
             TableState::HeadingFirstLine => {
                 ::state::flush(
                     &mut state.nodes,
+                    &mut state.node_count,
                     state.flushed_position,
                     ::state::skip_whitespace_backwards(state.wiki_text, position_before_line_break),
                     state.wiki_text,
                 );
-                state.nodes.push(::Node::ParagraphBreak {
+                table.start = position_after_line_break;
+                table.state = TableState::HeadingRemainder;
+                state.push_node(::Node::ParagraphBreak {
                     end: position_after_line_break,
                     start: position_before_line_break,
                 });
-                table.start = position_after_line_break;
-                table.state = TableState::HeadingRemainder;
                 state.flushed_position = position_after_line_break;
                 state.scan_position = position_after_line_break;
             }
@@ -392,6 +434,7 @@ fn parse_line_break(
             TableState::TableAttributes => {
                 ::state::flush(
                     &mut state.nodes,
+                    &mut state.node_count,
                     state.flushed_position,
                     ::state::skip_whitespace_backwards(state.wiki_text, position_before_line_break),
                     state.wiki_text,
@@ -408,6 +451,7 @@ fn parse_line_break(
             TableState::Row => {
                 ::state::flush(
                     &mut state.nodes,
+                    &mut state.node_count,
                     state.flushed_position,
                     ::state::skip_whitespace_backwards(state.wiki_text, position_before_line_break),
                     state.wiki_text,
@@ -416,6 +460,7 @@ fn parse_line_break(
                     attributes: ::std::mem::replace(&mut state.nodes, vec![]),
                     cells: vec![],
                     end: position_before_line_break,
+                    parsed_attributes: vec![],
                     start: table.start,
                 });
                 table.start = position_after_token;
@@ -453,6 +498,7 @@ pub fn parse_inline_token(state: &mut ::State) {
                     ::state::skip_whitespace_backwards(state.wiki_text, position_before_token);
                 ::state::flush(
                     &mut state.nodes,
+                    &mut state.node_count,
                     state.flushed_position,
                     end,
                     state.wiki_text,
@@ -461,6 +507,7 @@ pub fn parse_inline_token(state: &mut ::State) {
                     attributes: table.child_element_attributes.take(),
                     content: ::std::mem::replace(&mut state.nodes, vec![]),
                     end,
+                    parsed_attributes: vec![],
                     start: table.start,
                 });
                 table.start = position_before_token;
@@ -478,6 +525,7 @@ pub fn parse_inline_token(state: &mut ::State) {
                     ::state::skip_whitespace_backwards(state.wiki_text, position_before_token);
                 ::state::flush(
                     &mut state.nodes,
+                    &mut state.node_count,
                     state.flushed_position,
                     end,
                     state.wiki_text,
@@ -487,6 +535,7 @@ pub fn parse_inline_token(state: &mut ::State) {
                         attributes: vec![],
                         cells: vec![],
                         end,
+                        parsed_attributes: vec![],
                         start: table.start,
                     });
                 }
@@ -495,6 +544,7 @@ pub fn parse_inline_token(state: &mut ::State) {
                     attributes: table.child_element_attributes.take(),
                     content: ::std::mem::replace(&mut state.nodes, vec![]),
                     end,
+                    parsed_attributes: vec![],
                     start: table.start,
                     type_: ::TableCellType::Ordinary,
                 });
@@ -514,6 +564,7 @@ pub fn parse_inline_token(state: &mut ::State) {
                     ::state::skip_whitespace_backwards(state.wiki_text, position_before_token);
                 ::state::flush(
                     &mut state.nodes,
+                    &mut state.node_count,
                     state.flushed_position,
                     end,
                     state.wiki_text,
@@ -523,6 +574,7 @@ pub fn parse_inline_token(state: &mut ::State) {
                         attributes: vec![],
                         cells: vec![],
                         end,
+                        parsed_attributes: vec![],
                         start: table.start,
                     });
                 }
@@ -531,6 +583,7 @@ pub fn parse_inline_token(state: &mut ::State) {
                     attributes: table.child_element_attributes.take(),
                     content: ::std::mem::replace(&mut state.nodes, vec![]),
                     end,
+                    parsed_attributes: vec![],
                     start: table.start,
                     type_: ::TableCellType::Heading,
                 });
@@ -555,6 +608,7 @@ pub fn parse_inline_token(state: &mut ::State) {
             {
                 ::state::flush(
                     &mut state.nodes,
+                    &mut state.node_count,
                     state.flushed_position,
                     ::state::skip_whitespace_backwards(state.wiki_text, position_before_token),
                     state.wiki_text,
@@ -579,6 +633,7 @@ pub fn start_table(state: &mut ::State, position_before_line_break: Option<usize
     if let Some(position) = position_before_line_break {
         ::state::flush(
             &mut state.nodes,
+            &mut state.node_count,
             state.flushed_position,
             ::state::skip_whitespace_backwards(state.wiki_text, position),
             state.wiki_text,
@@ -31,6 +31,9 @@ pub fn parse_end_tag(state: &mut ::State, configuration: &::Configuration) {
                 start: tag_name_start_position,
             });
         }
+        Some(::TagClass::ExtensionTag) if ::transclusion::is_transclusion_tag(&tag_name) => {
+            ::transclusion::parse_end_tag(state, start_position, tag_name_end_position, tag_name);
+        }
         Some(::TagClass::ExtensionTag) => {
             let mut tag_end_position = tag_name_end_position;
             loop {
@@ -73,17 +76,21 @@ pub fn parse_end_tag(state: &mut ::State, configuration: &::Configuration) {
                             message: ::WarningMessage::MissingEndTagRewinding,
                             start: start_position,
                         });
-                        state.stack.truncate(open_node_index + 2);
-                        let open_node = state.stack.pop().unwrap();
+                        state.truncate_open_nodes(open_node_index + 2);
+                        let open_node = state.pop_open_node().unwrap();
                         state.rewind(open_node.nodes, open_node.start);
                     } else {
                         state.flush(start_position);
-                        let open_node = state.stack.pop().unwrap();
+                        let open_node = state.pop_open_node().unwrap();
                         tag_end_position += 1;
                         state.flushed_position = tag_end_position;
                         state.scan_position = state.flushed_position;
                         let nodes = ::std::mem::replace(&mut state.nodes, open_node.nodes);
-                        state.nodes.push(::Node::Tag {
+                        state.push_node(::Node::Tag {
+                            attributes: match open_node.type_ {
+                                ::OpenNodeType::Tag { attributes, .. } => attributes,
+                                _ => unreachable!(),
+                            },
                             end: state.scan_position,
                             name: tag_name,
                             nodes,
@@ -113,7 +120,7 @@ pub fn parse_end_tag(state: &mut ::State, configuration: &::Configuration) {
             state.flush(start_position);
             state.flushed_position = tag_end_position + 1;
             state.scan_position = state.flushed_position;
-            state.nodes.push(::Node::EndTag {
+            state.push_node(::Node::EndTag {
                 end: state.scan_position,
                 name: tag_name,
                 start: start_position,
@@ -165,13 +172,30 @@ pub fn parse_start_tag(state: &mut ::State, configuration: &::Configuration) {
             }
             Some(tag_end_position) => {
                 let tag_end_position = tag_name_end_position + tag_end_position + 1;
+                let self_closing = state.get_byte(tag_end_position - 2) == Some(b'/');
+                let attributes = ::attribute::parse_attributes(
+                    state.wiki_text,
+                    tag_name_end_position,
+                    tag_end_position - if self_closing { 2 } else { 1 },
+                );
                 match tag_class {
+                    ::TagClass::ExtensionTag if ::transclusion::is_transclusion_tag(&tag_name) => {
+                        ::transclusion::parse_start_tag(
+                            state,
+                            start_position,
+                            tag_end_position,
+                            tag_name,
+                            attributes,
+                            self_closing,
+                        );
+                    }
                     ::TagClass::ExtensionTag => {
-                        if state.get_byte(tag_end_position - 2) == Some(b'/') {
+                        if self_closing {
                             state.flush(start_position);
                             state.flushed_position = tag_end_position;
                             state.scan_position = state.flushed_position;
-                            state.nodes.push(::Node::Tag {
+                            state.push_node(::Node::Tag {
+                                attributes,
                                 end: tag_end_position,
                                 name: tag_name,
                                 nodes: vec![],
@@ -184,12 +208,16 @@ pub fn parse_start_tag(state: &mut ::State, configuration: &::Configuration) {
                                         state,
                                         start_position,
                                         tag_end_position,
-                                        &tag_name,
+                                        tag_name,
+                                        attributes,
                                     );
                                 }
                                 _ => {
                                     state.push_open_node(
-                                        ::OpenNodeType::Tag { name: tag_name },
+                                        ::OpenNodeType::Tag {
+                                            attributes,
+                                            name: tag_name,
+                                        },
                                         tag_end_position,
                                     );
                                 }
@@ -200,9 +228,11 @@ pub fn parse_start_tag(state: &mut ::State, configuration: &::Configuration) {
                         state.flush(start_position);
                         state.flushed_position = tag_end_position;
                         state.scan_position = state.flushed_position;
-                        state.nodes.push(::Node::StartTag {
+                        state.push_node(::Node::StartTag {
+                            attributes,
                             end: tag_end_position,
                             name: tag_name,
+                            self_closing,
                             start: start_position,
                         });
                     }
@@ -216,8 +246,10 @@ fn parse_plain_text_tag<'a>(
     state: &mut ::State<'a>,
     position_before_start_tag: usize,
     position_after_start_tag: usize,
-    start_tag_name: &::Cow<'a, str>,
+    start_tag_name: ::Cow<'a, str>,
+    attributes: Vec<::Attribute<'a>>,
 ) {
+    let mut attributes = Some(attributes);
     loop {
         match state.get_byte(state.scan_position) {
             None => {
@@ -235,6 +267,7 @@ fn parse_plain_text_tag<'a>(
                     position_before_start_tag,
                     position_after_start_tag,
                     &start_tag_name,
+                    &mut attributes,
                 ) {
                 break;
             },
@@ -249,6 +282,7 @@ fn parse_plain_text_end_tag<'a>(
     position_before_start_tag: usize,
     position_after_start_tag: usize,
     start_tag_name: &::Cow<'a, str>,
+    attributes: &mut Option<Vec<::Attribute<'a>>>,
 ) -> bool {
     let position_before_end_tag = state.scan_position;
     let position_before_end_tag_name = state.scan_position + 2;
@@ -279,14 +313,17 @@ fn parse_plain_text_end_tag<'a>(
             vec![::Node::Text {
                 end: position_before_end_tag,
                 start: position_after_start_tag,
-                value: &state.wiki_text[position_after_start_tag..position_before_end_tag],
+                value: ::Cow::Borrowed(
+                    &state.wiki_text[position_after_start_tag..position_before_end_tag],
+                ),
             }]
         } else {
             vec![]
         };
         state.flushed_position = position_after_end_tag;
         state.scan_position = position_after_end_tag;
-        state.nodes.push(::Node::Tag {
+        state.push_node(::Node::Tag {
+            attributes: attributes.take().unwrap_or_default(),
             end: position_after_end_tag,
             name: end_tag_name,
             nodes,
@@ -19,6 +19,7 @@ pub fn parse_parameter_name_end(state: &mut ::State) {
             if name.is_none() {
                 ::state::flush(
                     &mut state.nodes,
+                    &mut state.node_count,
                     state.flushed_position,
                     ::state::skip_whitespace_backwards(state.wiki_text, state.scan_position),
                     state.wiki_text,
@@ -45,6 +46,7 @@ pub fn parse_parameter_separator(state: &mut ::State) {
                     ::state::skip_whitespace_backwards(state.wiki_text, state.scan_position);
                 ::state::flush(
                     &mut state.nodes,
+                    &mut state.node_count,
                     state.flushed_position,
                     position,
                     state.wiki_text,
@@ -53,6 +55,7 @@ pub fn parse_parameter_separator(state: &mut ::State) {
             } else {
                 ::state::flush(
                     &mut state.nodes,
+                    &mut state.node_count,
                     state.flushed_position,
                     state.scan_position,
                     state.wiki_text,
@@ -72,7 +75,7 @@ pub fn parse_parameter_separator(state: &mut ::State) {
 }
 
 pub fn parse_template_end(state: &mut ::State) {
-    match state.stack.pop() {
+    match state.pop_open_node() {
         Some(::OpenNode {
             nodes,
             start,
@@ -82,7 +85,7 @@ pub fn parse_template_end(state: &mut ::State) {
                 let start_position = state.scan_position;
                 state.flush(start_position);
                 let nodes = ::std::mem::replace(&mut state.nodes, nodes);
-                state.nodes.push(::Node::Parameter {
+                state.push_node(::Node::Parameter {
                     default: Some(default.unwrap_or(nodes)),
                     end: state.scan_position,
                     name,
@@ -92,7 +95,7 @@ pub fn parse_template_end(state: &mut ::State) {
                 let start_position = state.skip_whitespace_backwards(state.scan_position);
                 state.flush(start_position);
                 let nodes = ::std::mem::replace(&mut state.nodes, nodes);
-                state.nodes.push(::Node::Parameter {
+                state.push_node(::Node::Parameter {
                     default: None,
                     end: state.scan_position,
                     name: nodes,
@@ -132,7 +135,7 @@ pub fn parse_template_end(state: &mut ::State) {
                     name
                 }
             };
-            state.nodes.push(::Node::Template {
+            state.push_node(::Node::Template {
                 end: state.scan_position,
                 name,
                 parameters,
@@ -167,6 +170,7 @@ pub fn parse_template_separator(state: &mut ::State) {
             let position = ::state::skip_whitespace_backwards(state.wiki_text, state.scan_position);
             ::state::flush(
                 &mut state.nodes,
+                &mut state.node_count,
                 state.flushed_position,
                 position,
                 state.wiki_text,
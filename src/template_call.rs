@@ -0,0 +1,172 @@
+// Copyright 2018 Fredrik Portström <https://portstrom.com>
+// This is free software distributed under the terms specified in
+// the file LICENSE at the top-level directory of this distribution.
+
+use Positioned;
+
+/// Argument of a template call, after resolving its name.
+#[derive(Clone, Debug)]
+pub struct Argument<'a, 'b> {
+    /// The name of the argument, with whitespace trimmed. Positional arguments are named by their number, starting from `1`.
+    pub name: String,
+
+    /// The parameter in the template node that gives the value of the argument. When several parameters have the same name, it's the last one.
+    pub parameter: &'b ::Parameter<'a>,
+
+    /// The text of the value in the wiki text, with comments removed. Like in MediaWiki, whitespace is trimmed from the value of a named argument after removing the comments, but not from the value of a positional argument.
+    pub text: ::Cow<'b, str>,
+}
+
+/// View of a template node with the arguments resolved the same way as by MediaWiki.
+///
+/// Positional arguments are numbered from `1`, counting only positional arguments, and an argument with an explicit name such as `2=` replaces a positional argument with the same number. When several arguments have the same name, the last one is used, but it keeps the place of the first one when iterating over the arguments.
+#[derive(Clone, Debug)]
+pub struct TemplateCall<'a, 'b> {
+    arguments: Vec<Argument<'a, 'b>>,
+    name: &'b [::Node<'a>],
+    wiki_text: &'b str,
+}
+
+impl<'a, 'b> TemplateCall<'a, 'b> {
+    /// Resolves the arguments of the given template node, or returns `None` if the node is not a template.
+    ///
+    /// The wiki text must be the text the node was parsed from.
+    #[must_use]
+    pub fn new(node: &'b ::Node<'a>, wiki_text: &'b str) -> Option<Self> {
+        let (name, parameters) = match node {
+            ::Node::Template {
+                name, parameters, ..
+            } => (name, parameters),
+            _ => return None,
+        };
+        let mut arguments: Vec<Argument> = vec![];
+        let mut position = 0;
+        for parameter in parameters {
+            let argument = match &parameter.name {
+                None => {
+                    position += 1;
+                    // The parser leaves out the whitespace around the value, which is part of a positional argument.
+                    let start = ::state::skip_whitespace_backwards(wiki_text, parameter.start);
+                    let end = ::state::skip_whitespace_forwards(wiki_text, parameter.end);
+                    Argument {
+                        name: position.to_string(),
+                        parameter,
+                        text: text_without_comments(wiki_text, start, end, &parameter.value),
+                    }
+                }
+                Some(name) => Argument {
+                    name: node_text(name, wiki_text).trim().to_string(),
+                    parameter,
+                    text: match (parameter.value.first(), parameter.value.last()) {
+                        (Some(first), Some(last)) => trim(text_without_comments(
+                            wiki_text,
+                            first.start(),
+                            last.end(),
+                            &parameter.value,
+                        )),
+                        _ => "".into(),
+                    },
+                },
+            };
+            match arguments
+                .iter_mut()
+                .find(|existing| existing.name == argument.name)
+            {
+                None => arguments.push(argument),
+                Some(existing) => *existing = argument,
+            }
+        }
+        Some(TemplateCall {
+            arguments,
+            name,
+            wiki_text,
+        })
+    }
+
+    /// Gets the argument with the given name, where positional arguments are named by their number.
+    #[must_use]
+    pub fn get(&self, name: &str) -> Option<&Argument<'a, 'b>> {
+        self.arguments
+            .iter()
+            .find(|argument| argument.name == name)
+    }
+
+    /// Gets the text of the value of the argument with the given name, where positional arguments are named by their number.
+    #[must_use]
+    pub fn get_text(&self, name: &str) -> Option<&str> {
+        self.get(name).map(|argument| &argument.text as &str)
+    }
+
+    /// Iterates over the arguments, each name once, in the order the names first appear in the template call.
+    pub fn iter(&self) -> ::std::slice::Iter<'_, Argument<'a, 'b>> {
+        self.arguments.iter()
+    }
+
+    /// The nodes of the name of the template.
+    #[must_use]
+    pub fn name(&self) -> &'b [::Node<'a>] {
+        self.name
+    }
+
+    /// The name of the template as text, with comments removed and whitespace trimmed.
+    #[must_use]
+    pub fn name_text(&self) -> String {
+        node_text(self.name, self.wiki_text).trim().to_string()
+    }
+}
+
+impl<'a, 'b, 'c> IntoIterator for &'c TemplateCall<'a, 'b> {
+    type IntoIter = ::std::slice::Iter<'c, Argument<'a, 'b>>;
+    type Item = &'c Argument<'a, 'b>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.arguments.iter()
+    }
+}
+
+fn node_text(nodes: &[::Node], wiki_text: &str) -> String {
+    let mut text = String::new();
+    for node in nodes {
+        if let ::Node::Comment { .. } = node {
+            continue;
+        }
+        text += &wiki_text[node.start()..node.end()];
+    }
+    text
+}
+
+/// Returns the text between the positions in the wiki text, leaving out the comments among the nodes.
+fn text_without_comments<'b>(
+    wiki_text: &'b str,
+    start: usize,
+    end: usize,
+    nodes: &[::Node],
+) -> ::Cow<'b, str> {
+    let mut text: Option<String> = None;
+    let mut position = start;
+    for node in ::depth_first(nodes) {
+        if let ::Node::Comment {
+            end: comment_end,
+            start: comment_start,
+        } = node
+        {
+            text.get_or_insert_with(String::new)
+                .push_str(&wiki_text[position..*comment_start]);
+            position = *comment_end;
+        }
+    }
+    match text {
+        None => ::Cow::Borrowed(&wiki_text[start..end]),
+        Some(mut text) => {
+            text.push_str(&wiki_text[position..end]);
+            ::Cow::Owned(text)
+        }
+    }
+}
+
+fn trim(text: ::Cow<str>) -> ::Cow<str> {
+    match text {
+        ::Cow::Borrowed(text) => ::Cow::Borrowed(text.trim()),
+        ::Cow::Owned(text) => ::Cow::Owned(text.trim().to_string()),
+    }
+}
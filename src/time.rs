@@ -0,0 +1,519 @@
+// Copyright 2018 Fredrik Portström <https://portstrom.com>
+// This is free software distributed under the terms specified in
+// the file LICENSE at the top-level directory of this distribution.
+
+const MONTH_NAMES: [&str; 12] = [
+    "January",
+    "February",
+    "March",
+    "April",
+    "May",
+    "June",
+    "July",
+    "August",
+    "September",
+    "October",
+    "November",
+    "December",
+];
+
+const WEEKDAY_NAMES: [&str; 7] = [
+    "Sunday",
+    "Monday",
+    "Tuesday",
+    "Wednesday",
+    "Thursday",
+    "Friday",
+    "Saturday",
+];
+
+#[derive(Default)]
+struct Date {
+    day: Option<i64>,
+    month: Option<i64>,
+    offset: Option<i64>,
+    relative_days: i64,
+    relative_months: i64,
+    relative_seconds: i64,
+    time: Option<i64>,
+    timestamp: Option<i64>,
+    year: Option<i64>,
+}
+
+/// Formats a date and time the same way as `#time`, or returns the error message.
+///
+/// The date is parsed in a subset of the formats accepted by PHP: absolute dates such as `2018-07-01`, `1 July 2018` and `July 1, 2018`, times such as `12:30` and `12:30:15`, time zones such as `Z` and `+02:00`, Unix timestamps such as `@1530403200`, the words `now`, `today`, `yesterday`, `tomorrow`, `midnight` and `noon` and relative times such as `+1 day` and `2 weeks ago`. Like in Mediawiki, a four-digit number is a year. Missing parts of the date are taken from the current time.
+pub fn format_time(format: &str, date: &str, now: i64) -> Result<String, &'static str> {
+    let time = parse_date(date, now).ok_or("Error: Invalid time.")?;
+    let days = time.div_euclid(86400);
+    let (year, _, _) = civil_from_days(days);
+    if year < 0 {
+        return Err("Error: #time only supports years from 0.");
+    }
+    if year > 9999 {
+        return Err("Error: #time only supports years up to 9999.");
+    }
+    Ok(format_timestamp(format, time))
+}
+
+fn format_timestamp(format: &str, time: i64) -> String {
+    let days = time.div_euclid(86400);
+    let seconds = time.rem_euclid(86400);
+    let (year, month, day) = civil_from_days(days);
+    let (hour, minute, second) = (seconds / 3600, seconds / 60 % 60, seconds % 60);
+    let weekday = (days + 4).rem_euclid(7);
+    let day_of_year = days - days_from_civil(year, 1, 1);
+    let (iso_year, iso_week) = iso_week(year, day_of_year, weekday);
+    let characters: Vec<char> = format.chars().collect();
+    let mut output = String::new();
+    let mut roman = false;
+    let mut position = 0;
+    while position < characters.len() {
+        let mut code = characters[position].to_string();
+        if code == "x" && position + 1 < characters.len() {
+            position += 1;
+            code.push(characters[position]);
+        }
+        // Numeric codes give the number and the number of digits to pad it to.
+        let number = match &code as &str {
+            "G" => Some((hour, 1)),
+            "H" => Some((hour, 2)),
+            "L" => Some((is_leap_year(year) as i64, 1)),
+            "N" => Some((if weekday == 0 { 7 } else { weekday }, 1)),
+            "U" => Some((time, 1)),
+            "W" => Some((iso_week, 2)),
+            "Y" => Some((year, 4)),
+            "d" => Some((day, 2)),
+            "g" => Some(((hour + 11) % 12 + 1, 1)),
+            "h" => Some(((hour + 11) % 12 + 1, 2)),
+            "i" => Some((minute, 2)),
+            "j" => Some((day, 1)),
+            "m" => Some((month, 2)),
+            "n" => Some((month, 1)),
+            "o" => Some((iso_year, 1)),
+            "s" => Some((second, 2)),
+            "t" => Some((days_in_month(year, month), 1)),
+            "w" => Some((weekday, 1)),
+            "y" => Some((year % 100, 2)),
+            "z" => Some((day_of_year, 1)),
+            _ => None,
+        };
+        if let Some((number, width)) = number {
+            if roman {
+                output += &to_roman(number);
+                roman = false;
+            } else {
+                output += &format!("{:01$}", number, width);
+            }
+            position += 1;
+            continue;
+        }
+        match &code as &str {
+            "A" => output += if hour < 12 { "AM" } else { "PM" },
+            "D" => output += &WEEKDAY_NAMES[weekday as usize][..3],
+            "F" | "xg" => output += MONTH_NAMES[month as usize - 1],
+            "I" | "Z" => output.push('0'),
+            "M" => output += &MONTH_NAMES[month as usize - 1][..3],
+            "O" => output += "+0000",
+            "P" => output += "+00:00",
+            "T" | "e" => output += "UTC",
+            "a" => output += if hour < 12 { "am" } else { "pm" },
+            "c" => {
+                output += &format!(
+                    "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}+00:00",
+                    year, month, day, hour, minute, second
+                )
+            }
+            "l" => output += WEEKDAY_NAMES[weekday as usize],
+            "r" => {
+                output += &format!(
+                    "{}, {:02} {} {:04} {:02}:{:02}:{:02} +0000",
+                    &WEEKDAY_NAMES[weekday as usize][..3],
+                    day,
+                    &MONTH_NAMES[month as usize - 1][..3],
+                    year,
+                    hour,
+                    minute,
+                    second
+                )
+            }
+            "xN" | "xn" => {}
+            "xr" => roman = true,
+            "\\" => match characters.get(position + 1) {
+                None => output.push('\\'),
+                Some(character) => {
+                    output.push(*character);
+                    position += 1;
+                }
+            },
+            "\"" => match characters[position + 1..]
+                .iter()
+                .position(|character| *character == '"')
+            {
+                None => output.push('"'),
+                Some(length) => {
+                    output.extend(&characters[position + 1..position + 1 + length]);
+                    position += length + 1;
+                }
+            },
+            // Like in Mediawiki, an unknown code starting with `x` gives its last character.
+            _ => output.push(characters[position]),
+        }
+        position += 1;
+    }
+    output
+}
+
+fn parse_date(text: &str, now: i64) -> Option<i64> {
+    let text = text.trim().to_lowercase();
+    let words = tokenize(&text)?;
+    let mut date = Date::default();
+    let mut index = 0;
+    while index < words.len() {
+        let word = words[index];
+        let next = words.get(index + 1).cloned();
+        index += 1;
+        match word {
+            "," | "." | "at" | "of" | "the" => continue,
+            "now" => continue,
+            "today" | "midnight" => date.time = Some(0),
+            "noon" => date.time = Some(43200),
+            "yesterday" => {
+                date.relative_days -= 1;
+                date.time = Some(0);
+            }
+            "tomorrow" => {
+                date.relative_days += 1;
+                date.time = Some(0);
+            }
+            "ago" => {
+                date.relative_days = -date.relative_days;
+                date.relative_months = -date.relative_months;
+                date.relative_seconds = -date.relative_seconds;
+            }
+            "z" | "utc" | "gmt" => date.offset = Some(0),
+            "@" => {
+                let (negative, digits) = match next {
+                    Some("-") => (true, words.get(index + 1).cloned()),
+                    _ => (false, next),
+                };
+                let digits = digits?;
+                let timestamp: i64 = digits.parse().ok()?;
+                date.timestamp = Some(if negative { -timestamp } else { timestamp });
+                index += if negative { 2 } else { 1 };
+            }
+            "+" | "-" => {
+                let sign = if word == "-" { -1 } else { 1 };
+                let number = next?;
+                if !number.bytes().all(|byte| byte.is_ascii_digit()) {
+                    return None;
+                }
+                match words.get(index + 1).cloned() {
+                    Some(unit) if unit_seconds(unit).is_some() => {
+                        add_relative(&mut date, sign * number.parse::<i64>().ok()?, unit);
+                        index += 2;
+                    }
+                    // A sign after a time is the offset of the time zone.
+                    _ if date.time.is_some() => {
+                        let (hours, minutes) = if words.get(index + 1) == Some(&":") {
+                            let minutes = words.get(index + 2)?.parse::<i64>().ok()?;
+                            index += 2;
+                            (number.parse::<i64>().ok()?, minutes)
+                        } else if number.len() == 4 {
+                            (number[..2].parse().ok()?, number[2..].parse().ok()?)
+                        } else {
+                            (number.parse::<i64>().ok()?, 0)
+                        };
+                        date.offset = Some(sign * (hours * 3600 + minutes * 60));
+                        index += 1;
+                    }
+                    _ => return None,
+                }
+            }
+            _ if word.bytes().all(|byte| byte.is_ascii_digit()) => {
+                let number: i64 = word.parse().ok()?;
+                match next {
+                    Some(":") => {
+                        let minutes: i64 = words.get(index + 1)?.parse().ok()?;
+                        index += 2;
+                        let mut seconds = 0;
+                        if words.get(index) == Some(&":") {
+                            seconds = words.get(index + 1)?.parse().ok()?;
+                            index += 2;
+                            // Fractions of seconds are ignored.
+                            if words.get(index) == Some(&".") && words.get(index + 1).is_some() {
+                                index += 2;
+                            }
+                        }
+                        let mut hours = number;
+                        match words.get(index).cloned() {
+                            Some("am") if hours <= 12 => {
+                                hours %= 12;
+                                index += 1;
+                            }
+                            Some("pm") if hours <= 12 => {
+                                hours = hours % 12 + 12;
+                                index += 1;
+                            }
+                            _ => {}
+                        }
+                        if hours > 24 || minutes > 59 || seconds > 60 {
+                            return None;
+                        }
+                        date.time = Some(hours * 3600 + minutes * 60 + seconds);
+                    }
+                    Some("-") | Some("/") | Some(".")
+                        if words
+                            .get(index + 1)
+                            .is_some_and(|word| word.bytes().all(|byte| byte.is_ascii_digit()))
+                            && words.get(index + 2) == next.as_ref() =>
+                    {
+                        let second: i64 = words[index + 1].parse().ok()?;
+                        let third: i64 = words.get(index + 3)?.parse().ok()?;
+                        let (year, month, day) = match next {
+                            Some("-") if word.len() == 4 => (number, second, third),
+                            Some("/") if word.len() == 4 => (number, second, third),
+                            Some("/") => (third, number, second),
+                            Some(".") => (third, second, number),
+                            _ => return None,
+                        };
+                        set_date(&mut date, Some(year), month, Some(day))?;
+                        index += 4;
+                        if words.get(index) == Some(&"t") {
+                            index += 1;
+                        }
+                    }
+                    Some("st") | Some("nd") | Some("rd") | Some("th") if word.len() <= 2 => {
+                        date.day = Some(number);
+                        index += 1;
+                    }
+                    Some(unit) if unit_seconds(unit).is_some() => {
+                        add_relative(&mut date, number, unit);
+                        index += 1;
+                    }
+                    _ if word.len() == 8 => {
+                        set_date(
+                            &mut date,
+                            Some(number / 10000),
+                            number / 100 % 100,
+                            Some(number % 100),
+                        )?;
+                    }
+                    _ if word.len() == 4 => {
+                        date.year = Some(number);
+                        date.time.get_or_insert(0);
+                    }
+                    _ if word.len() <= 2 && date.day.is_none() => date.day = Some(number),
+                    _ => return None,
+                }
+            }
+            _ => match month_number(word) {
+                Some(month) => {
+                    let (year, day) = (date.year, date.day);
+                    set_date(&mut date, year, month, day)?
+                }
+                // Names of weekdays are accepted but don't affect the date.
+                None if WEEKDAY_NAMES
+                    .iter()
+                    .any(|name| name.to_lowercase().starts_with(word) && word.len() >= 3) => {}
+                None => return None,
+            },
+        }
+    }
+    resolve(&date, now)
+}
+
+fn add_relative(date: &mut Date, number: i64, unit: &str) {
+    match unit.trim_end_matches('s') {
+        "month" | "mon" => date.relative_months += number,
+        "year" | "yr" => date.relative_months += number * 12,
+        unit => {
+            let seconds = unit_seconds(unit).unwrap_or(0);
+            if seconds % 86400 == 0 {
+                date.relative_days += number * (seconds / 86400);
+            } else {
+                date.relative_seconds += number * seconds;
+            }
+        }
+    }
+}
+
+fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let days = days + 719468;
+    let era = days.div_euclid(146097);
+    let day_of_era = days.rem_euclid(146097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
+}
+
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year.rem_euclid(400);
+    let month_index = (month + 9) % 12;
+    let day_of_year = (153 * month_index + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146097 + day_of_era - 719468
+}
+
+fn days_in_month(year: i64, month: i64) -> i64 {
+    match month {
+        2 if is_leap_year(year) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+fn is_leap_year(year: i64) -> bool {
+    year % 4 == 0 && (year % 100 != 0 || year % 400 == 0)
+}
+
+/// The year and number of the week according to ISO 8601, given the day of the year counted from 0 and the day of the week counted from 0 for Sunday.
+fn iso_week(year: i64, day_of_year: i64, weekday: i64) -> (i64, i64) {
+    let iso_weekday = if weekday == 0 { 7 } else { weekday };
+    let week = (day_of_year - iso_weekday + 10) / 7;
+    if week < 1 {
+        let previous_year_days = if is_leap_year(year - 1) { 366 } else { 365 };
+        iso_week(year - 1, day_of_year + previous_year_days, weekday)
+    } else if week == 53
+        && day_of_year - iso_weekday + 4 >= if is_leap_year(year) { 366 } else { 365 }
+    {
+        (year + 1, 1)
+    } else {
+        (year, week)
+    }
+}
+
+fn month_number(word: &str) -> Option<i64> {
+    if word.len() < 3 {
+        return None;
+    }
+    let word = if word == "sept" { "sep" } else { word };
+    MONTH_NAMES
+        .iter()
+        .position(|name| name.to_lowercase() == word || name[..3].to_lowercase() == word)
+        .map(|index| index as i64 + 1)
+}
+
+fn resolve(date: &Date, now: i64) -> Option<i64> {
+    let base = date.timestamp.unwrap_or(now);
+    let (now_year, now_month, now_day) = civil_from_days(base.div_euclid(86400));
+    let has_date = date.year.is_some() || date.month.is_some() || date.day.is_some();
+    let year = date.year.unwrap_or(now_year);
+    // Like in PHP, a month without a day is the first day of the month.
+    let month = date.month.unwrap_or(now_month);
+    let day = date
+        .day
+        .unwrap_or(if date.month.is_some() { 1 } else { now_day });
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+        return None;
+    }
+    let time = match date.time {
+        Some(time) => time,
+        None if has_date => 0,
+        None => base.rem_euclid(86400),
+    };
+    let total_months = year * 12 + month - 1 + date.relative_months;
+    // Like in PHP, a day past the end of the month overflows into the next month.
+    let days = days_from_civil(
+        total_months.div_euclid(12),
+        total_months.rem_euclid(12) + 1,
+        1,
+    ) + day
+        - 1
+        + date.relative_days;
+    Some(days * 86400 + time - date.offset.unwrap_or(0) + date.relative_seconds)
+}
+
+fn set_date(date: &mut Date, year: Option<i64>, month: i64, day: Option<i64>) -> Option<()> {
+    if !(1..=12).contains(&month) {
+        return None;
+    }
+    date.year = year;
+    date.month = Some(month);
+    date.day = day;
+    Some(())
+}
+
+fn to_roman(number: i64) -> String {
+    if number <= 0 || number > 10000 {
+        return number.to_string();
+    }
+    let mut number = number;
+    let mut output = String::new();
+    for (value, numeral) in &[
+        (1000, "M"),
+        (900, "CM"),
+        (500, "D"),
+        (400, "CD"),
+        (100, "C"),
+        (90, "XC"),
+        (50, "L"),
+        (40, "XL"),
+        (10, "X"),
+        (9, "IX"),
+        (5, "V"),
+        (4, "IV"),
+        (1, "I"),
+    ] {
+        while number >= *value {
+            output += numeral;
+            number -= value;
+        }
+    }
+    output
+}
+
+/// Splits a date into words, numbers and single punctuation characters, leaving out whitespace.
+fn tokenize(text: &str) -> Option<Vec<&str>> {
+    let mut words = vec![];
+    let mut position = 0;
+    let bytes = text.as_bytes();
+    while position < bytes.len() {
+        let start = position;
+        match bytes[position] {
+            b' ' | b'\t' | b'\n' => {
+                position += 1;
+                continue;
+            }
+            b'0'..=b'9' => {
+                while position < bytes.len() && bytes[position].is_ascii_digit() {
+                    position += 1;
+                }
+            }
+            b'a'..=b'z' => {
+                while position < bytes.len() && bytes[position].is_ascii_lowercase() {
+                    position += 1;
+                }
+            }
+            b'+' | b'-' | b'/' | b'.' | b',' | b':' | b'@' => position += 1,
+            _ => return None,
+        }
+        words.push(&text[start..position]);
+    }
+    Some(words)
+}
+
+fn unit_seconds(unit: &str) -> Option<i64> {
+    Some(match unit.trim_end_matches('s') {
+        "sec" | "second" => 1,
+        "min" | "minute" => 60,
+        "hour" => 3600,
+        "day" => 86400,
+        "week" => 604800,
+        "fortnight" => 1209600,
+        "month" | "mon" | "year" | "yr" => 0,
+        _ => return None,
+    })
+}
@@ -0,0 +1,366 @@
+// Copyright 2018 Fredrik Portström <https://portstrom.com>
+// This is free software distributed under the terms specified in
+// the file LICENSE at the top-level directory of this distribution.
+
+use std::fmt;
+
+/// Namespace of a wiki, as known to the configuration.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct NamespaceData {
+    /// Other names of the namespace.
+    pub aliases: Vec<String>,
+
+    /// The name of the namespace that is the same for all wikis, such as `Project`. Empty for the main namespace and for namespaces that have no canonical name.
+    pub canonical_name: String,
+
+    /// Whether the first letter of page names in the namespace is case sensitive. When false, the first letter is capitalized.
+    pub case_sensitive: bool,
+
+    /// The number of the namespace.
+    pub id: i32,
+
+    /// The name of the namespace as used on the wiki, such as `Wikipedia` for the project namespace of Wikipedia. Empty for the main namespace.
+    pub name: String,
+
+    /// Whether `/` in page names in the namespace separates subpages.
+    pub subpages: bool,
+}
+
+/// Title of a page, resolved and normalized the same way as by MediaWiki.
+///
+/// Titles that refer to the same page compare equal, as long as their fragments are also the same. To compare titles regardless of fragments, such as when deduplicating categories, compare the results of `full_text`.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Title {
+    /// The canonical name of the namespace of the title, the same for all wikis, such as `Project`. Empty for the main namespace.
+    pub canonical_namespace_name: String,
+
+    /// The fragment following `#`, if any, with underscores replaced by spaces.
+    pub fragment: Option<String>,
+
+    /// The interwiki prefix in lowercase, if the title refers to a page on another wiki. The namespace and page name of such a title are not resolved, since they depend on the configuration of the other wiki.
+    pub interwiki: Option<String>,
+
+    /// The number of the namespace of the title. The main namespace is number 0.
+    pub namespace: i32,
+
+    /// The name of the namespace of the title as used on the wiki, such as `Wikipedia` for the project namespace of Wikipedia. Empty for the main namespace.
+    pub namespace_name: String,
+
+    /// The name of the page without the namespace, with underscores replaced by spaces.
+    pub page_name: String,
+
+    /// Whether the namespace of the title has subpages, in which case `/` in the page name separates subpages.
+    pub subpages: bool,
+}
+
+/// Reason a text is not a valid title.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum TitleError {
+    /// The title has no page name.
+    Empty,
+
+    /// The title contains a character that is not allowed in titles, such as `<`, `[`, `{` or `|`, a character entity that could not be decoded or a percent-encoded character.
+    IllegalCharacter,
+
+    /// The page name starts with a colon.
+    LeadingColon,
+
+    /// The page name is a relative path such as `..` or contains such a path between slashes.
+    RelativePath,
+
+    /// A talk page of an interwiki link, such as `Talk:wikt:Foo`.
+    TalkNamespaceWithInterwiki,
+
+    /// The title contains three or more tildes, which would be replaced by a signature.
+    Tildes,
+
+    /// The page name is longer than 255 bytes, or 512 bytes in the special namespace.
+    TooLong,
+}
+
+impl TitleError {
+    /// Human-readable description of the error.
+    pub fn message(self) -> &'static str {
+        match self {
+            TitleError::Empty => "The title has no page name.",
+            TitleError::IllegalCharacter => "The title contains a character that is not allowed.",
+            TitleError::LeadingColon => "The page name starts with a colon.",
+            TitleError::RelativePath => "The page name is a relative path.",
+            TitleError::TalkNamespaceWithInterwiki => "Talk page of an interwiki link.",
+            TitleError::Tildes => "The title contains three or more tildes.",
+            TitleError::TooLong => "The title is too long.",
+        }
+    }
+}
+
+impl fmt::Display for TitleError {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str(self.message())
+    }
+}
+
+impl Title {
+    /// The page name up to the last `/`, or the whole page name if the namespace doesn't have subpages or the title is not a subpage.
+    #[must_use]
+    pub fn base_page_name(&self) -> &str {
+        match self.page_name.rfind('/') {
+            Some(position) if self.subpages && self.interwiki.is_none() => {
+                &self.page_name[..position]
+            }
+            _ => &self.page_name,
+        }
+    }
+
+    /// The title as it would be written in a link, with the interwiki prefix and namespace if any, but without the fragment.
+    #[must_use]
+    pub fn full_text(&self) -> String {
+        let mut text = String::new();
+        if let Some(interwiki) = &self.interwiki {
+            text += interwiki;
+            text.push(':');
+        }
+        if !self.namespace_name.is_empty() {
+            text += &self.namespace_name;
+            text.push(':');
+        }
+        text += &self.page_name;
+        text
+    }
+
+    /// The parts of the page name separated by `/`, starting with the root page and ending with the page itself. Only one part if the namespace doesn't have subpages.
+    #[must_use]
+    pub fn subpage_path(&self) -> Vec<&str> {
+        if self.subpages && self.interwiki.is_none() {
+            self.page_name.split('/').collect()
+        } else {
+            vec![&self.page_name]
+        }
+    }
+}
+
+/// Namespaces that exist in every wiki, as numbers, canonical names and whether they have subpages by default. Used when the configuration doesn't list the namespaces.
+pub const CANONICAL_NAMESPACES: &[(i32, &str, bool)] = &[
+    (-2, "Media", false),
+    (-1, "Special", false),
+    (0, "", false),
+    (1, "Talk", true),
+    (2, "User", true),
+    (3, "User talk", true),
+    (4, "Project", true),
+    (5, "Project talk", true),
+    (6, "File", false),
+    (7, "File talk", true),
+    (8, "MediaWiki", true),
+    (9, "MediaWiki talk", true),
+    (10, "Template", true),
+    (11, "Template talk", true),
+    (12, "Help", true),
+    (13, "Help talk", true),
+    (14, "Category", false),
+    (15, "Category talk", true),
+];
+
+impl ::Configuration {
+    /// Gets the namespace with the given number.
+    #[must_use]
+    pub fn namespace(&self, id: i32) -> Option<&NamespaceData> {
+        self.namespace_data.iter().find(|data| data.id == id)
+    }
+
+    /// Gets the number of the namespace with the given name, canonical name or alias, ignoring case and treating underscores as spaces.
+    #[must_use]
+    pub fn namespace_id(&self, name: &str) -> Option<i32> {
+        self.namespace_ids
+            .get(&normalize_whitespace(name).to_lowercase())
+            .cloned()
+    }
+
+    /// Resolves and normalizes a title the same way as MediaWiki, for example the target of a link.
+    ///
+    /// Character entities are decoded, underscores and runs of whitespace become single spaces, the interwiki prefix and namespace are recognized, the fragment is separated and the first letter is capitalized unless the namespace is case sensitive. A title without a namespace gets the given default namespace, which is 0 for links and 10 for templates.
+    pub fn parse_title(&self, text: &str, default_namespace: i32) -> Result<Title, TitleError> {
+        let text = normalize_whitespace(&decode_entities(self, text));
+        let mut text = text.as_str();
+        if text.is_empty() {
+            return Err(TitleError::Empty);
+        }
+        if text.contains('\u{FFFD}') {
+            return Err(TitleError::IllegalCharacter);
+        }
+        let mut namespace = default_namespace;
+        let mut interwiki = None;
+        if text.starts_with(':') {
+            text = text[1..].trim_start_matches(' ');
+            namespace = 0;
+        }
+        if let Some((prefix, rest)) = split_prefix(text) {
+            if let Some(id) = self.namespace_ids.get(&prefix.to_lowercase()) {
+                if *id == 1 {
+                    if let Some((prefix, _)) = split_prefix(rest) {
+                        if self.interwiki_prefixes.contains(&prefix.to_lowercase()) {
+                            return Err(TitleError::TalkNamespaceWithInterwiki);
+                        }
+                    }
+                }
+                namespace = *id;
+                text = rest;
+            } else if self.interwiki_prefixes.contains(&prefix.to_lowercase()) {
+                // The rest of the title is resolved by the other wiki, so it doesn't get the default namespace.
+                interwiki = Some(prefix.to_lowercase());
+                namespace = 0;
+                text = rest;
+                if text.starts_with(':') {
+                    text = text[1..].trim_start_matches(' ');
+                }
+            }
+        }
+        let fragment = text.find('#').map(|position| {
+            let fragment = text[position + 1..].to_string();
+            text = text[..position].trim_end_matches(' ');
+            fragment
+        });
+        if text.is_empty() && interwiki.is_none() && namespace != 0 {
+            return Err(TitleError::Empty);
+        }
+        if text.starts_with(':') {
+            return Err(TitleError::LeadingColon);
+        }
+        validate(text, namespace)?;
+        let data = match interwiki {
+            None => self.namespace(namespace),
+            Some(_) => None,
+        };
+        let mut page_name = String::with_capacity(text.len());
+        let mut characters = text.chars();
+        if interwiki.is_none() && !data.is_some_and(|data| data.case_sensitive) {
+            if let Some(character) = characters.next() {
+                // MediaWiki uses the simple case mapping, so characters such as `ß` that become several characters in uppercase are left unchanged.
+                let mut uppercase = character.to_uppercase();
+                page_name.push(match (uppercase.next(), uppercase.next()) {
+                    (Some(uppercase), None) => uppercase,
+                    _ => character,
+                });
+            }
+        }
+        page_name.extend(characters);
+        Ok(Title {
+            canonical_namespace_name: data.map_or_else(String::new, |data| {
+                data.canonical_name.clone()
+            }),
+            fragment,
+            interwiki,
+            namespace,
+            namespace_name: data.map_or_else(String::new, |data| data.name.clone()),
+            page_name,
+            subpages: data.is_some_and(|data| data.subpages),
+        })
+    }
+}
+
+fn decode_entities(configuration: &::Configuration, text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(position) = rest.find('&') {
+        result += &rest[..position];
+        rest = &rest[position + 1..];
+        if let Ok((length, character)) = configuration.character_entities.find(rest) {
+            result.push(character);
+            rest = &rest[length..];
+        } else if let Some((length, character)) = ::character_entity::decode_numeric_entity(rest) {
+            result.push(character);
+            rest = &rest[length..];
+        } else {
+            result.push('&');
+        }
+    }
+    result + rest
+}
+
+fn is_illegal_character(character: char) -> bool {
+    match character {
+        '#' | '<' | '>' | '[' | ']' | '{' | '|' | '}' | '\u{7F}' => true,
+        _ => character < ' ',
+    }
+}
+
+fn normalize_whitespace(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut is_space = false;
+    for character in text.chars() {
+        match character {
+            '\u{200E}' | '\u{200F}' | '\u{202A}'..='\u{202E}' => {}
+            ' '
+            | '_'
+            | '\u{A0}'
+            | '\u{1680}'
+            | '\u{180E}'
+            | '\u{2000}'..='\u{200A}'
+            | '\u{2028}'
+            | '\u{2029}'
+            | '\u{202F}'
+            | '\u{205F}'
+            | '\u{3000}' => is_space = true,
+            _ => {
+                if is_space && !result.is_empty() {
+                    result.push(' ');
+                }
+                is_space = false;
+                result.push(character);
+            }
+        }
+    }
+    result
+}
+
+fn split_prefix(text: &str) -> Option<(&str, &str)> {
+    let position = text.find(':')?;
+    let prefix = text[..position].trim_end_matches(' ');
+    if prefix.is_empty() {
+        return None;
+    }
+    Some((prefix, text[position + 1..].trim_start_matches(' ')))
+}
+
+fn validate(text: &str, namespace: i32) -> Result<(), TitleError> {
+    let bytes = text.as_bytes();
+    for (position, character) in text.char_indices() {
+        if is_illegal_character(character) {
+            return Err(TitleError::IllegalCharacter);
+        }
+        if character == '%'
+            && bytes.len() > position + 2
+            && (bytes[position + 1] as char).is_ascii_hexdigit()
+            && (bytes[position + 2] as char).is_ascii_hexdigit()
+        {
+            return Err(TitleError::IllegalCharacter);
+        }
+        // A character entity that was not decoded.
+        if character == '&' {
+            let length = text[position + 1..]
+                .find(|character: char| !character.is_alphanumeric())
+                .unwrap_or(0);
+            if length > 0 && bytes.get(position + 1 + length) == Some(&b';') {
+                return Err(TitleError::IllegalCharacter);
+            }
+        }
+    }
+    if text == "."
+        || text == ".."
+        || text.starts_with("./")
+        || text.starts_with("../")
+        || text.contains("/./")
+        || text.contains("/../")
+        || text.ends_with("/.")
+        || text.ends_with("/..")
+    {
+        return Err(TitleError::RelativePath);
+    }
+    if text.contains("~~~") {
+        return Err(TitleError::Tildes);
+    }
+    let max_length = if namespace == -1 { 512 } else { 255 };
+    if text.len() > max_length {
+        return Err(TitleError::TooLong);
+    }
+    Ok(())
+}
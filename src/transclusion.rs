@@ -0,0 +1,236 @@
+// Copyright 2018 Fredrik Portström <https://portstrom.com>
+// This is free software distributed under the terms specified in
+// the file LICENSE at the top-level directory of this distribution.
+
+/// Whether wiki text is parsed as the page itself or as transcluded in another page, which determines the meaning of the tags `noinclude`, `includeonly` and `onlyinclude`.
+///
+/// The tags themselves are parsed as `Node::StartTag` and `Node::EndTag` and mean nothing. The text that is left out in the mode is parsed as a single `Node::Tag` whose content is not parsed. Such a tag node has the name `includeonly` or `noinclude`, even when it's text outside `onlyinclude` that is left out, and its text is never displayed.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub enum TransclusionMode {
+    /// The page as transcluded in another page. The text inside `noinclude` is left out, and if the text contains both `<onlyinclude>` and `</onlyinclude>`, only the text between them is kept.
+    Transclude,
+
+    /// The page itself, as when it's viewed. The text inside `includeonly` is left out.
+    #[default]
+    View,
+}
+
+impl ::Configuration {
+    /// Parses wiki text into structured data, either as the page itself or as transcluded in another page.
+    ///
+    /// `parse` is the same as parsing in the mode `TransclusionMode::View`.
+    #[must_use]
+    pub fn parse_with_mode<'a>(&self, wiki_text: &'a str, mode: TransclusionMode) -> ::Output<'a> {
+        ::parse::parse(self, wiki_text, &::ParseLimits::default(), mode)
+    }
+}
+
+/// Whether only the text inside `onlyinclude` is transcluded, which Mediawiki decides by looking for the start tag and the end tag exactly as written here. It's decided once for the whole text, when parsing starts.
+pub fn is_only_include(wiki_text: &str, mode: TransclusionMode) -> bool {
+    mode == TransclusionMode::Transclude
+        && wiki_text.contains("<onlyinclude>")
+        && wiki_text.contains("</onlyinclude>")
+}
+
+/// Whether the tag node with the given name is one of the tags for transclusion, which are left out of the output.
+pub fn is_transclusion_tag(name: &str) -> bool {
+    name == "includeonly" || name == "noinclude" || name == "onlyinclude"
+}
+
+/// Leaves out the text before `<onlyinclude>`, if it's only the text inside `onlyinclude` that is transcluded. Returns whether text was left out.
+pub fn parse_beginning_of_text(state: &mut ::State) -> bool {
+    if !state.is_only_include {
+        return false;
+    }
+    skip_to_only_include(state, 0);
+    true
+}
+
+pub fn parse_end_tag<'a>(
+    state: &mut ::State<'a>,
+    start_position: usize,
+    tag_name_end_position: usize,
+    tag_name: ::Cow<'a, str>,
+) {
+    if tag_name == "onlyinclude"
+        && state.wiki_text[start_position..].starts_with("</onlyinclude>")
+        && state.is_only_include
+    {
+        skip_to_only_include(state, start_position);
+        return;
+    }
+    if tag_name == left_out_tag_name(state.mode) || !is_ignored_tag(state.mode, &tag_name) {
+        state.scan_position = start_position + 2;
+        state.warnings.push(::Warning {
+            end: tag_name_end_position,
+            message: if tag_name == "onlyinclude" {
+                ::WarningMessage::UnrecognizedTagName
+            } else {
+                ::WarningMessage::UnexpectedEndTag
+            },
+            start: start_position + 2,
+        });
+        return;
+    }
+    let tag_end_position = match state.wiki_text[tag_name_end_position..].find('>') {
+        None => {
+            state.scan_position = start_position + 2;
+            state.warnings.push(::Warning {
+                end: tag_name_end_position,
+                message: ::WarningMessage::InvalidTagSyntax,
+                start: start_position + 2,
+            });
+            return;
+        }
+        Some(position) => tag_name_end_position + position + 1,
+    };
+    state.flush(start_position);
+    state.flushed_position = tag_end_position;
+    state.scan_position = tag_end_position;
+    state.push_node(::Node::EndTag {
+        end: tag_end_position,
+        name: tag_name,
+        start: start_position,
+    });
+}
+
+pub fn parse_start_tag<'a>(
+    state: &mut ::State<'a>,
+    start_position: usize,
+    tag_end_position: usize,
+    tag_name: ::Cow<'a, str>,
+    attributes: Vec<::Attribute<'a>>,
+    self_closing: bool,
+) {
+    if tag_name == left_out_tag_name(state.mode) {
+        // Like in Mediawiki, text left out with no end tag runs to the end of the text.
+        let (content_end_position, end_position) = if self_closing {
+            (tag_end_position, tag_end_position)
+        } else {
+            match find_end_tag(state.wiki_text, tag_end_position, &tag_name) {
+                None => (state.wiki_text.len(), state.wiki_text.len()),
+                Some(positions) => positions,
+            }
+        };
+        state.flush(start_position);
+        push_left_out(
+            state,
+            start_position,
+            tag_end_position,
+            content_end_position,
+            end_position,
+            tag_name,
+            attributes,
+        );
+        return;
+    }
+    if !is_ignored_tag(state.mode, &tag_name) {
+        state.scan_position = start_position + 1;
+        state.warnings.push(::Warning {
+            end: start_position + 1 + tag_name.len(),
+            message: ::WarningMessage::UnrecognizedTagName,
+            start: start_position + 1,
+        });
+        return;
+    }
+    state.flush(start_position);
+    state.flushed_position = tag_end_position;
+    state.scan_position = tag_end_position;
+    state.push_node(::Node::StartTag {
+        attributes,
+        end: tag_end_position,
+        name: tag_name,
+        self_closing,
+        start: start_position,
+    });
+}
+
+/// Finds the end tag with the given name, ignoring case. Returns the positions before and after the end tag.
+fn find_end_tag(wiki_text: &str, position: usize, tag_name: &str) -> Option<(usize, usize)> {
+    let bytes = wiki_text.as_bytes();
+    let mut position = position;
+    while let Some(offset) = wiki_text[position..].find("</") {
+        let start_position = position + offset;
+        position = start_position + 2;
+        let name_end_position = position + tag_name.len();
+        if !bytes
+            .get(position..name_end_position)
+            .is_some_and(|name| name.eq_ignore_ascii_case(tag_name.as_bytes()))
+        {
+            continue;
+        }
+        let end_position = name_end_position
+            + bytes[name_end_position..]
+                .iter()
+                .take_while(|byte| byte.is_ascii_whitespace())
+                .count();
+        if bytes.get(end_position) == Some(&b'>') {
+            return Some((start_position, end_position + 1));
+        }
+    }
+    None
+}
+
+/// Whether the tag with the given name means nothing in the mode, so that only the tag itself is left out.
+fn is_ignored_tag(mode: TransclusionMode, tag_name: &str) -> bool {
+    match mode {
+        TransclusionMode::Transclude => tag_name == "includeonly",
+        TransclusionMode::View => tag_name == "noinclude" || tag_name == "onlyinclude",
+    }
+}
+
+/// The name of the tag whose content is left out in the mode.
+fn left_out_tag_name(mode: TransclusionMode) -> &'static str {
+    match mode {
+        TransclusionMode::Transclude => "noinclude",
+        TransclusionMode::View => "includeonly",
+    }
+}
+
+fn push_left_out<'a>(
+    state: &mut ::State<'a>,
+    start_position: usize,
+    content_start_position: usize,
+    content_end_position: usize,
+    end_position: usize,
+    tag_name: ::Cow<'a, str>,
+    attributes: Vec<::Attribute<'a>>,
+) {
+    let nodes = if content_start_position < content_end_position {
+        vec![::Node::Text {
+            end: content_end_position,
+            start: content_start_position,
+            value: ::Cow::Borrowed(&state.wiki_text[content_start_position..content_end_position]),
+        }]
+    } else {
+        vec![]
+    };
+    state.flushed_position = end_position;
+    state.scan_position = end_position;
+    state.push_node(::Node::Tag {
+        attributes,
+        end: end_position,
+        name: tag_name,
+        nodes,
+        start: start_position,
+    });
+}
+
+/// Leaves out the text from the given position up to and including the next `<onlyinclude>`, or to the end of the text if there is none.
+fn skip_to_only_include(state: &mut ::State, start_position: usize) {
+    let end_position = state.wiki_text[start_position..]
+        .find("<onlyinclude>")
+        .map_or(state.wiki_text.len(), |position| {
+            start_position + position + "<onlyinclude>".len()
+        });
+    state.flush(start_position);
+    push_left_out(
+        state,
+        start_position,
+        start_position,
+        end_position,
+        end_position,
+        ::Cow::Borrowed("noinclude"),
+        vec![],
+    );
+}
@@ -0,0 +1,690 @@
+// Copyright 2018 Fredrik Portström <https://portstrom.com>
+// This is free software distributed under the terms specified in
+// the file LICENSE at the top-level directory of this distribution.
+
+use std::{slice, vec};
+
+/// Iterator over the ancestors of a node in breadth first order, created by `BreadthFirst::ancestors`.
+pub struct Ancestors<'a, 'b: 'a, 'c> {
+    nodes: &'c [(&'a ::Node<'b>, Option<usize>)],
+    parent: Option<usize>,
+}
+
+/// Iterator over nodes in breadth first order, created by `breadth_first`.
+pub struct BreadthFirst<'a, 'b: 'a> {
+    current: Option<usize>,
+    next_index: usize,
+    nodes: Vec<(&'a ::Node<'b>, Option<usize>)>,
+}
+
+/// Iterator over the children of a node, created by `Node::children`.
+pub struct Children<'a, 'b: 'a> {
+    current: slice::Iter<'a, ::Node<'b>>,
+    sequences: vec::IntoIter<&'a [::Node<'b>]>,
+}
+
+/// Iterator over nodes in depth first order, created by `depth_first`.
+pub struct DepthFirst<'a, 'b: 'a> {
+    ancestors: Vec<&'a ::Node<'b>>,
+    depth: usize,
+    stack: Vec<(&'a ::Node<'b>, usize)>,
+}
+
+/// Visitor of parsed nodes.
+///
+/// The method `visit_node` is called for every node and by default calls the method for the type of node, which by default visits the children of the node. Override a method to handle a type of node, and call `walk_node` from it to also visit the children of the node.
+pub trait Visitor<'a> {
+    /// Visits a node of type `Node::Bold`.
+    fn visit_bold(&mut self, node: &::Node<'a>) {
+        walk_node(self, node);
+    }
+
+    /// Visits a node of type `Node::BoldItalic`.
+    fn visit_bold_italic(&mut self, node: &::Node<'a>) {
+        walk_node(self, node);
+    }
+
+    /// Visits a node of type `Node::Category`.
+    fn visit_category(&mut self, node: &::Node<'a>) {
+        walk_node(self, node);
+    }
+
+    /// Visits a node of type `Node::CharacterEntity`.
+    fn visit_character_entity(&mut self, node: &::Node<'a>) {
+        walk_node(self, node);
+    }
+
+    /// Visits a node of type `Node::Comment`.
+    fn visit_comment(&mut self, node: &::Node<'a>) {
+        walk_node(self, node);
+    }
+
+    /// Visits a node of type `Node::DefinitionList`.
+    fn visit_definition_list(&mut self, node: &::Node<'a>) {
+        walk_node(self, node);
+    }
+
+    /// Visits a node of type `Node::EndTag`.
+    fn visit_end_tag(&mut self, node: &::Node<'a>) {
+        walk_node(self, node);
+    }
+
+    /// Visits a node of type `Node::ExternalLink`.
+    fn visit_external_link(&mut self, node: &::Node<'a>) {
+        walk_node(self, node);
+    }
+
+    /// Visits a node of type `Node::FreeExternalLink`.
+    fn visit_free_external_link(&mut self, node: &::Node<'a>) {
+        walk_node(self, node);
+    }
+
+    /// Visits a node of type `Node::Heading`.
+    fn visit_heading(&mut self, node: &::Node<'a>) {
+        walk_node(self, node);
+    }
+
+    /// Visits a node of type `Node::HorizontalDivider`.
+    fn visit_horizontal_divider(&mut self, node: &::Node<'a>) {
+        walk_node(self, node);
+    }
+
+    /// Visits a node of type `Node::Image`.
+    fn visit_image(&mut self, node: &::Node<'a>) {
+        walk_node(self, node);
+    }
+
+    /// Visits a node of type `Node::InterwikiLink`.
+    fn visit_interwiki_link(&mut self, node: &::Node<'a>) {
+        walk_node(self, node);
+    }
+
+    /// Visits a node of type `Node::Italic`.
+    fn visit_italic(&mut self, node: &::Node<'a>) {
+        walk_node(self, node);
+    }
+
+    /// Visits a node of type `Node::LanguageLink`.
+    fn visit_language_link(&mut self, node: &::Node<'a>) {
+        walk_node(self, node);
+    }
+
+    /// Visits a node of type `Node::Link`.
+    fn visit_link(&mut self, node: &::Node<'a>) {
+        walk_node(self, node);
+    }
+
+    /// Visits a node of type `Node::MagicWord`.
+    fn visit_magic_word(&mut self, node: &::Node<'a>) {
+        walk_node(self, node);
+    }
+
+    /// Visits a node. By default calls the method for the type of node.
+    fn visit_node(&mut self, node: &::Node<'a>) {
+        match node.kind() {
+            ::NodeKind::Bold => self.visit_bold(node),
+            ::NodeKind::BoldItalic => self.visit_bold_italic(node),
+            ::NodeKind::Category => self.visit_category(node),
+            ::NodeKind::CharacterEntity => self.visit_character_entity(node),
+            ::NodeKind::Comment => self.visit_comment(node),
+            ::NodeKind::DefinitionList => self.visit_definition_list(node),
+            ::NodeKind::EndTag => self.visit_end_tag(node),
+            ::NodeKind::ExternalLink => self.visit_external_link(node),
+            ::NodeKind::FreeExternalLink => self.visit_free_external_link(node),
+            ::NodeKind::Heading => self.visit_heading(node),
+            ::NodeKind::HorizontalDivider => self.visit_horizontal_divider(node),
+            ::NodeKind::Image => self.visit_image(node),
+            ::NodeKind::InterwikiLink => self.visit_interwiki_link(node),
+            ::NodeKind::Italic => self.visit_italic(node),
+            ::NodeKind::LanguageLink => self.visit_language_link(node),
+            ::NodeKind::Link => self.visit_link(node),
+            ::NodeKind::MagicWord => self.visit_magic_word(node),
+            ::NodeKind::OrderedList => self.visit_ordered_list(node),
+            ::NodeKind::ParagraphBreak => self.visit_paragraph_break(node),
+            ::NodeKind::Parameter => self.visit_parameter(node),
+            ::NodeKind::Preformatted => self.visit_preformatted(node),
+            ::NodeKind::Redirect => self.visit_redirect(node),
+            ::NodeKind::StartTag => self.visit_start_tag(node),
+            ::NodeKind::Table => self.visit_table(node),
+            ::NodeKind::Tag => self.visit_tag(node),
+            ::NodeKind::Template => self.visit_template(node),
+            ::NodeKind::Text => self.visit_text(node),
+            ::NodeKind::UnorderedList => self.visit_unordered_list(node),
+        }
+    }
+
+    /// Visits a sequence of nodes. By default visits each of the nodes.
+    fn visit_nodes(&mut self, nodes: &[::Node<'a>]) {
+        for node in nodes {
+            self.visit_node(node);
+        }
+    }
+
+    /// Visits a node of type `Node::OrderedList`.
+    fn visit_ordered_list(&mut self, node: &::Node<'a>) {
+        walk_node(self, node);
+    }
+
+    /// Visits a node of type `Node::ParagraphBreak`.
+    fn visit_paragraph_break(&mut self, node: &::Node<'a>) {
+        walk_node(self, node);
+    }
+
+    /// Visits a node of type `Node::Parameter`.
+    fn visit_parameter(&mut self, node: &::Node<'a>) {
+        walk_node(self, node);
+    }
+
+    /// Visits a node of type `Node::Preformatted`.
+    fn visit_preformatted(&mut self, node: &::Node<'a>) {
+        walk_node(self, node);
+    }
+
+    /// Visits a node of type `Node::Redirect`.
+    fn visit_redirect(&mut self, node: &::Node<'a>) {
+        walk_node(self, node);
+    }
+
+    /// Visits a node of type `Node::StartTag`.
+    fn visit_start_tag(&mut self, node: &::Node<'a>) {
+        walk_node(self, node);
+    }
+
+    /// Visits a node of type `Node::Table`.
+    fn visit_table(&mut self, node: &::Node<'a>) {
+        walk_node(self, node);
+    }
+
+    /// Visits a node of type `Node::Tag`.
+    fn visit_tag(&mut self, node: &::Node<'a>) {
+        walk_node(self, node);
+    }
+
+    /// Visits a node of type `Node::Template`.
+    fn visit_template(&mut self, node: &::Node<'a>) {
+        walk_node(self, node);
+    }
+
+    /// Visits a node of type `Node::Text`.
+    fn visit_text(&mut self, node: &::Node<'a>) {
+        walk_node(self, node);
+    }
+
+    /// Visits a node of type `Node::UnorderedList`.
+    fn visit_unordered_list(&mut self, node: &::Node<'a>) {
+        walk_node(self, node);
+    }
+}
+
+/// Visitor that can modify parsed nodes.
+///
+/// The method `visit_node_mut` is called for every node and by default calls the method for the type of node, which by default visits the children of the node. Override a method to handle a type of node, and call `walk_node_mut` from it to also visit the children of the node. Override `visit_nodes_mut` to insert or remove nodes.
+pub trait VisitorMut<'a> {
+    /// Visits a node of type `Node::Bold`.
+    fn visit_bold_mut(&mut self, node: &mut ::Node<'a>) {
+        walk_node_mut(self, node);
+    }
+
+    /// Visits a node of type `Node::BoldItalic`.
+    fn visit_bold_italic_mut(&mut self, node: &mut ::Node<'a>) {
+        walk_node_mut(self, node);
+    }
+
+    /// Visits a node of type `Node::Category`.
+    fn visit_category_mut(&mut self, node: &mut ::Node<'a>) {
+        walk_node_mut(self, node);
+    }
+
+    /// Visits a node of type `Node::CharacterEntity`.
+    fn visit_character_entity_mut(&mut self, node: &mut ::Node<'a>) {
+        walk_node_mut(self, node);
+    }
+
+    /// Visits a node of type `Node::Comment`.
+    fn visit_comment_mut(&mut self, node: &mut ::Node<'a>) {
+        walk_node_mut(self, node);
+    }
+
+    /// Visits a node of type `Node::DefinitionList`.
+    fn visit_definition_list_mut(&mut self, node: &mut ::Node<'a>) {
+        walk_node_mut(self, node);
+    }
+
+    /// Visits a node of type `Node::EndTag`.
+    fn visit_end_tag_mut(&mut self, node: &mut ::Node<'a>) {
+        walk_node_mut(self, node);
+    }
+
+    /// Visits a node of type `Node::ExternalLink`.
+    fn visit_external_link_mut(&mut self, node: &mut ::Node<'a>) {
+        walk_node_mut(self, node);
+    }
+
+    /// Visits a node of type `Node::FreeExternalLink`.
+    fn visit_free_external_link_mut(&mut self, node: &mut ::Node<'a>) {
+        walk_node_mut(self, node);
+    }
+
+    /// Visits a node of type `Node::Heading`.
+    fn visit_heading_mut(&mut self, node: &mut ::Node<'a>) {
+        walk_node_mut(self, node);
+    }
+
+    /// Visits a node of type `Node::HorizontalDivider`.
+    fn visit_horizontal_divider_mut(&mut self, node: &mut ::Node<'a>) {
+        walk_node_mut(self, node);
+    }
+
+    /// Visits a node of type `Node::Image`.
+    fn visit_image_mut(&mut self, node: &mut ::Node<'a>) {
+        walk_node_mut(self, node);
+    }
+
+    /// Visits a node of type `Node::InterwikiLink`.
+    fn visit_interwiki_link_mut(&mut self, node: &mut ::Node<'a>) {
+        walk_node_mut(self, node);
+    }
+
+    /// Visits a node of type `Node::Italic`.
+    fn visit_italic_mut(&mut self, node: &mut ::Node<'a>) {
+        walk_node_mut(self, node);
+    }
+
+    /// Visits a node of type `Node::LanguageLink`.
+    fn visit_language_link_mut(&mut self, node: &mut ::Node<'a>) {
+        walk_node_mut(self, node);
+    }
+
+    /// Visits a node of type `Node::Link`.
+    fn visit_link_mut(&mut self, node: &mut ::Node<'a>) {
+        walk_node_mut(self, node);
+    }
+
+    /// Visits a node of type `Node::MagicWord`.
+    fn visit_magic_word_mut(&mut self, node: &mut ::Node<'a>) {
+        walk_node_mut(self, node);
+    }
+
+    /// Visits a node. By default calls the method for the type of node.
+    fn visit_node_mut(&mut self, node: &mut ::Node<'a>) {
+        match node.kind() {
+            ::NodeKind::Bold => self.visit_bold_mut(node),
+            ::NodeKind::BoldItalic => self.visit_bold_italic_mut(node),
+            ::NodeKind::Category => self.visit_category_mut(node),
+            ::NodeKind::CharacterEntity => self.visit_character_entity_mut(node),
+            ::NodeKind::Comment => self.visit_comment_mut(node),
+            ::NodeKind::DefinitionList => self.visit_definition_list_mut(node),
+            ::NodeKind::EndTag => self.visit_end_tag_mut(node),
+            ::NodeKind::ExternalLink => self.visit_external_link_mut(node),
+            ::NodeKind::FreeExternalLink => self.visit_free_external_link_mut(node),
+            ::NodeKind::Heading => self.visit_heading_mut(node),
+            ::NodeKind::HorizontalDivider => self.visit_horizontal_divider_mut(node),
+            ::NodeKind::Image => self.visit_image_mut(node),
+            ::NodeKind::InterwikiLink => self.visit_interwiki_link_mut(node),
+            ::NodeKind::Italic => self.visit_italic_mut(node),
+            ::NodeKind::LanguageLink => self.visit_language_link_mut(node),
+            ::NodeKind::Link => self.visit_link_mut(node),
+            ::NodeKind::MagicWord => self.visit_magic_word_mut(node),
+            ::NodeKind::OrderedList => self.visit_ordered_list_mut(node),
+            ::NodeKind::ParagraphBreak => self.visit_paragraph_break_mut(node),
+            ::NodeKind::Parameter => self.visit_parameter_mut(node),
+            ::NodeKind::Preformatted => self.visit_preformatted_mut(node),
+            ::NodeKind::Redirect => self.visit_redirect_mut(node),
+            ::NodeKind::StartTag => self.visit_start_tag_mut(node),
+            ::NodeKind::Table => self.visit_table_mut(node),
+            ::NodeKind::Tag => self.visit_tag_mut(node),
+            ::NodeKind::Template => self.visit_template_mut(node),
+            ::NodeKind::Text => self.visit_text_mut(node),
+            ::NodeKind::UnorderedList => self.visit_unordered_list_mut(node),
+        }
+    }
+
+    /// Visits a sequence of nodes. By default visits each of the nodes.
+    fn visit_nodes_mut(&mut self, nodes: &mut Vec<::Node<'a>>) {
+        for node in nodes {
+            self.visit_node_mut(node);
+        }
+    }
+
+    /// Visits a node of type `Node::OrderedList`.
+    fn visit_ordered_list_mut(&mut self, node: &mut ::Node<'a>) {
+        walk_node_mut(self, node);
+    }
+
+    /// Visits a node of type `Node::ParagraphBreak`.
+    fn visit_paragraph_break_mut(&mut self, node: &mut ::Node<'a>) {
+        walk_node_mut(self, node);
+    }
+
+    /// Visits a node of type `Node::Parameter`.
+    fn visit_parameter_mut(&mut self, node: &mut ::Node<'a>) {
+        walk_node_mut(self, node);
+    }
+
+    /// Visits a node of type `Node::Preformatted`.
+    fn visit_preformatted_mut(&mut self, node: &mut ::Node<'a>) {
+        walk_node_mut(self, node);
+    }
+
+    /// Visits a node of type `Node::Redirect`.
+    fn visit_redirect_mut(&mut self, node: &mut ::Node<'a>) {
+        walk_node_mut(self, node);
+    }
+
+    /// Visits a node of type `Node::StartTag`.
+    fn visit_start_tag_mut(&mut self, node: &mut ::Node<'a>) {
+        walk_node_mut(self, node);
+    }
+
+    /// Visits a node of type `Node::Table`.
+    fn visit_table_mut(&mut self, node: &mut ::Node<'a>) {
+        walk_node_mut(self, node);
+    }
+
+    /// Visits a node of type `Node::Tag`.
+    fn visit_tag_mut(&mut self, node: &mut ::Node<'a>) {
+        walk_node_mut(self, node);
+    }
+
+    /// Visits a node of type `Node::Template`.
+    fn visit_template_mut(&mut self, node: &mut ::Node<'a>) {
+        walk_node_mut(self, node);
+    }
+
+    /// Visits a node of type `Node::Text`.
+    fn visit_text_mut(&mut self, node: &mut ::Node<'a>) {
+        walk_node_mut(self, node);
+    }
+
+    /// Visits a node of type `Node::UnorderedList`.
+    fn visit_unordered_list_mut(&mut self, node: &mut ::Node<'a>) {
+        walk_node_mut(self, node);
+    }
+}
+
+impl<'a> ::Node<'a> {
+    /// Returns an iterator over the nodes directly contained in this node, in the order they appear in the wiki text.
+    ///
+    /// For a node containing list items, table cells or template parameters, the children are the nodes contained in those elements, including nodes in HTML attributes and parameter names.
+    pub fn children<'b>(&'b self) -> Children<'b, 'a> {
+        let mut sequences = child_sequences(self).into_iter();
+        Children {
+            current: sequences.next().unwrap_or(&[]).iter(),
+            sequences,
+        }
+    }
+
+    /// Returns the identifier for the type of the node.
+    pub fn kind(&self) -> ::NodeKind {
+        match self {
+            ::Node::Bold { .. } => ::NodeKind::Bold,
+            ::Node::BoldItalic { .. } => ::NodeKind::BoldItalic,
+            ::Node::Category { .. } => ::NodeKind::Category,
+            ::Node::CharacterEntity { .. } => ::NodeKind::CharacterEntity,
+            ::Node::Comment { .. } => ::NodeKind::Comment,
+            ::Node::DefinitionList { .. } => ::NodeKind::DefinitionList,
+            ::Node::EndTag { .. } => ::NodeKind::EndTag,
+            ::Node::ExternalLink { .. } => ::NodeKind::ExternalLink,
+            ::Node::FreeExternalLink { .. } => ::NodeKind::FreeExternalLink,
+            ::Node::Heading { .. } => ::NodeKind::Heading,
+            ::Node::HorizontalDivider { .. } => ::NodeKind::HorizontalDivider,
+            ::Node::Image { .. } => ::NodeKind::Image,
+            ::Node::InterwikiLink { .. } => ::NodeKind::InterwikiLink,
+            ::Node::Italic { .. } => ::NodeKind::Italic,
+            ::Node::LanguageLink { .. } => ::NodeKind::LanguageLink,
+            ::Node::Link { .. } => ::NodeKind::Link,
+            ::Node::MagicWord { .. } => ::NodeKind::MagicWord,
+            ::Node::OrderedList { .. } => ::NodeKind::OrderedList,
+            ::Node::ParagraphBreak { .. } => ::NodeKind::ParagraphBreak,
+            ::Node::Parameter { .. } => ::NodeKind::Parameter,
+            ::Node::Preformatted { .. } => ::NodeKind::Preformatted,
+            ::Node::Redirect { .. } => ::NodeKind::Redirect,
+            ::Node::StartTag { .. } => ::NodeKind::StartTag,
+            ::Node::Table { .. } => ::NodeKind::Table,
+            ::Node::Tag { .. } => ::NodeKind::Tag,
+            ::Node::Template { .. } => ::NodeKind::Template,
+            ::Node::Text { .. } => ::NodeKind::Text,
+            ::Node::UnorderedList { .. } => ::NodeKind::UnorderedList,
+        }
+    }
+}
+
+impl<'a, 'b> BreadthFirst<'a, 'b> {
+    /// Returns an iterator over the ancestors of the node most recently returned by the iterator, starting with the parent of the node and ending with the outermost ancestor.
+    pub fn ancestors<'c>(&'c self) -> Ancestors<'a, 'b, 'c> {
+        Ancestors {
+            nodes: &self.nodes,
+            parent: self.current.and_then(|index| self.nodes[index].1),
+        }
+    }
+}
+
+impl<'a, 'b> DepthFirst<'a, 'b> {
+    /// Returns the ancestors of the node most recently returned by the iterator, starting with the outermost ancestor and ending with the parent of the node.
+    pub fn ancestors(&self) -> &[&'a ::Node<'b>] {
+        &self.ancestors[..self.depth]
+    }
+}
+
+impl<'a, 'b, 'c> Iterator for Ancestors<'a, 'b, 'c> {
+    type Item = &'a ::Node<'b>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (node, parent) = self.nodes[self.parent?];
+        self.parent = parent;
+        Some(node)
+    }
+}
+
+impl<'a, 'b> Iterator for BreadthFirst<'a, 'b> {
+    type Item = &'a ::Node<'b>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let index = self.next_index;
+        let node = self.nodes.get(index)?.0;
+        self.nodes
+            .extend(node.children().map(|child| (child, Some(index))));
+        self.current = Some(index);
+        self.next_index += 1;
+        Some(node)
+    }
+}
+
+impl<'a, 'b> Iterator for Children<'a, 'b> {
+    type Item = &'a ::Node<'b>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(node) = self.current.next() {
+                return Some(node);
+            }
+            self.current = self.sequences.next()?.iter();
+        }
+    }
+}
+
+impl<'a, 'b> Iterator for DepthFirst<'a, 'b> {
+    type Item = &'a ::Node<'b>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (node, depth) = self.stack.pop()?;
+        self.ancestors.truncate(depth);
+        self.ancestors.push(node);
+        self.depth = depth;
+        let length = self.stack.len();
+        self.stack
+            .extend(node.children().map(|child| (child, depth + 1)));
+        self.stack[length..].reverse();
+        Some(node)
+    }
+}
+
+/// Returns an iterator over the given nodes and all nodes contained in them, in breadth first order.
+///
+/// The ancestors of the node most recently returned are available from the method `ancestors` of the iterator.
+pub fn breadth_first<'a, 'b>(nodes: &'a [::Node<'b>]) -> BreadthFirst<'a, 'b> {
+    BreadthFirst {
+        current: None,
+        next_index: 0,
+        nodes: nodes.iter().map(|node| (node, None)).collect(),
+    }
+}
+
+/// Returns an iterator over the given nodes and all nodes contained in them, in depth first order, with each node coming before the nodes it contains.
+///
+/// The ancestors of the node most recently returned are available from the method `ancestors` of the iterator.
+pub fn depth_first<'a, 'b>(nodes: &'a [::Node<'b>]) -> DepthFirst<'a, 'b> {
+    DepthFirst {
+        ancestors: vec![],
+        depth: 0,
+        stack: nodes.iter().rev().map(|node| (node, 0)).collect(),
+    }
+}
+
+/// Visits the children of a node with the given visitor.
+pub fn walk_node<'a, V: Visitor<'a> + ?Sized>(visitor: &mut V, node: &::Node<'a>) {
+    for nodes in child_sequences(node) {
+        visitor.visit_nodes(nodes);
+    }
+}
+
+/// Visits the children of a node with the given visitor that can modify them.
+pub fn walk_node_mut<'a, V: VisitorMut<'a> + ?Sized>(visitor: &mut V, node: &mut ::Node<'a>) {
+    for nodes in child_sequences_mut(node) {
+        visitor.visit_nodes_mut(nodes);
+    }
+}
+
+pub fn child_sequences<'a, 'b>(node: &'a ::Node<'b>) -> Vec<&'a [::Node<'b>]> {
+    let mut sequences: Vec<&[_]> = vec![];
+    match node {
+        ::Node::Category { ordinal: nodes, .. }
+        | ::Node::ExternalLink { nodes, .. }
+        | ::Node::Heading { nodes, .. }
+        | ::Node::Image { text: nodes, .. }
+        | ::Node::InterwikiLink { text: nodes, .. }
+        | ::Node::LanguageLink { text: nodes, .. }
+        | ::Node::Link { text: nodes, .. }
+        | ::Node::Preformatted { nodes, .. }
+        | ::Node::Tag { nodes, .. } => sequences.push(nodes),
+        ::Node::DefinitionList { items, .. } => {
+            sequences.extend(items.iter().map(|item| &item.nodes as &[_]))
+        }
+        ::Node::OrderedList { items, .. } | ::Node::UnorderedList { items, .. } => {
+            sequences.extend(items.iter().map(|item| &item.nodes as &[_]))
+        }
+        ::Node::Parameter { default, name, .. } => {
+            sequences.push(name);
+            if let Some(default) = default {
+                sequences.push(default);
+            }
+        }
+        ::Node::Table {
+            attributes,
+            captions,
+            rows,
+            ..
+        } => {
+            sequences.push(attributes);
+            for caption in captions {
+                if let Some(attributes) = &caption.attributes {
+                    sequences.push(attributes);
+                }
+                sequences.push(&caption.content);
+            }
+            for row in rows {
+                sequences.push(&row.attributes);
+                for cell in &row.cells {
+                    if let Some(attributes) = &cell.attributes {
+                        sequences.push(attributes);
+                    }
+                    sequences.push(&cell.content);
+                }
+            }
+        }
+        ::Node::Template {
+            name, parameters, ..
+        } => {
+            sequences.push(name);
+            for parameter in parameters {
+                if let Some(name) = &parameter.name {
+                    sequences.push(name);
+                }
+                sequences.push(&parameter.value);
+            }
+        }
+        _ => {}
+    }
+    sequences
+}
+
+pub fn child_sequences_mut<'a, 'b>(node: &'a mut ::Node<'b>) -> Vec<&'a mut Vec<::Node<'b>>> {
+    let mut sequences = vec![];
+    for_each_child_sequence_mut(node, |nodes| sequences.push(nodes));
+    sequences
+}
+
+/// Calls the given function with each sequence of child nodes of the node, without allocating.
+pub fn for_each_child_sequence_mut<'a, 'b, F: FnMut(&'a mut Vec<::Node<'b>>)>(
+    node: &'a mut ::Node<'b>,
+    mut f: F,
+) {
+    match node {
+        ::Node::Category { ordinal: nodes, .. }
+        | ::Node::ExternalLink { nodes, .. }
+        | ::Node::Heading { nodes, .. }
+        | ::Node::Image { text: nodes, .. }
+        | ::Node::InterwikiLink { text: nodes, .. }
+        | ::Node::LanguageLink { text: nodes, .. }
+        | ::Node::Link { text: nodes, .. }
+        | ::Node::Preformatted { nodes, .. }
+        | ::Node::Tag { nodes, .. } => f(nodes),
+        ::Node::DefinitionList { items, .. } => {
+            items.iter_mut().for_each(|item| f(&mut item.nodes))
+        }
+        ::Node::OrderedList { items, .. } | ::Node::UnorderedList { items, .. } => {
+            items.iter_mut().for_each(|item| f(&mut item.nodes))
+        }
+        ::Node::Parameter { default, name, .. } => {
+            f(name);
+            if let Some(default) = default {
+                f(default);
+            }
+        }
+        ::Node::Table {
+            attributes,
+            captions,
+            rows,
+            ..
+        } => {
+            f(attributes);
+            for caption in captions {
+                if let Some(attributes) = &mut caption.attributes {
+                    f(attributes);
+                }
+                f(&mut caption.content);
+            }
+            for row in rows {
+                f(&mut row.attributes);
+                for cell in &mut row.cells {
+                    if let Some(attributes) = &mut cell.attributes {
+                        f(attributes);
+                    }
+                    f(&mut cell.content);
+                }
+            }
+        }
+        ::Node::Template {
+            name, parameters, ..
+        } => {
+            f(name);
+            for parameter in parameters {
+                if let Some(name) = &mut parameter.name {
+                    f(name);
+                }
+                f(&mut parameter.value);
+            }
+        }
+        _ => {}
+    }
+}
@@ -5,7 +5,8 @@
 use std::fmt;
 
 /// Warning from the parser telling that something is not well-formed.
-#[derive(Debug)]
+#[derive(Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 pub struct Warning {
     /// The byte position in the wiki text where the warning ends.
     pub end: usize,
@@ -19,13 +20,26 @@ pub struct Warning {
 
 /// Identifier for a kind of warning from the parser.
 #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 pub enum WarningMessage {
+    /// Parsing was cancelled. The rest of the text is not parsed.
+    Cancelled,
+
+    /// The deadline for parsing was reached. The rest of the text is not parsed.
+    DeadlineExceeded,
+
     /// List broken by definition term.
     DefinitionTermContinuation,
 
+    /// The limit for nesting was reached. The rest of the text is not parsed.
+    DepthLimitExceeded,
+
     /// End tag in comment.
     EndTagInComment,
 
+    /// The wiki text is longer than the limit for the size of the input. The text is not parsed.
+    InputSizeLimitExceeded,
+
     /// Invalid character.
     InvalidCharacter,
 
@@ -44,6 +58,9 @@ pub enum WarningMessage {
     /// Missing end tag. Rewinding.
     MissingEndTagRewinding,
 
+    /// The limit for the number of nodes was reached. The rest of the text is not parsed.
+    NodeLimitExceeded,
+
     /// Repeated empty line.
     RepeatedEmptyLine,
 
@@ -70,20 +87,38 @@ pub enum WarningMessage {
 
     /// Useless text in redirect.
     UselessTextInRedirect,
+
+    /// The limit for the number of warnings was reached. Further warnings are not given.
+    WarningLimitExceeded,
 }
 
 impl WarningMessage {
     /// Human-readable description of the warning.
     pub fn message(self) -> &'static str {
         match self {
+            WarningMessage::Cancelled => {
+                "Parsing was cancelled. The rest of the text is not parsed."
+            }
+            WarningMessage::DeadlineExceeded => {
+                "The deadline for parsing was reached. The rest of the text is not parsed."
+            }
             WarningMessage::DefinitionTermContinuation => "List broken by definition term.",
+            WarningMessage::DepthLimitExceeded => {
+                "The limit for nesting was reached. The rest of the text is not parsed."
+            }
             WarningMessage::EndTagInComment => "End tag in comment.",
+            WarningMessage::InputSizeLimitExceeded => {
+                "The wiki text is longer than the limit for the size of the input. The text is not parsed."
+            }
             WarningMessage::InvalidCharacter => "Invalid character.",
             WarningMessage::InvalidHeadingSyntaxRewinding => "Invalid heading syntax. Rewinding.",
             WarningMessage::InvalidLinkSyntax => "Invalid link syntax.",
             WarningMessage::InvalidParameterSyntax => "Invalid parameter syntax.",
             WarningMessage::InvalidTagSyntax => "Invalid tag syntax.",
             WarningMessage::MissingEndTagRewinding => "Missing end tag. Rewinding.",
+            WarningMessage::NodeLimitExceeded => {
+                "The limit for the number of nodes was reached. The rest of the text is not parsed."
+            }
             WarningMessage::RepeatedEmptyLine => "Repeated empty line.",
             WarningMessage::StrayTextInTable => "Stray text in table.",
             WarningMessage::TextAfterRedirect => "Wiki text comes after a redirect.",
@@ -99,6 +134,9 @@ impl WarningMessage {
             WarningMessage::UnrecognizedTagName => "A tag with an unrecognized tag name was found.",
             WarningMessage::UselessTextInParameter => "Useless text in parameter.",
             WarningMessage::UselessTextInRedirect => "Useless text in redirect.",
+            WarningMessage::WarningLimitExceeded => {
+                "The limit for the number of warnings was reached. Further warnings are not given."
+            }
         }
     }
 }